@@ -10,22 +10,87 @@ use routes::{
 	account::{
 		account_sign_in,
 		create_account,
+		get_account_applications,
 		get_account_by_username_or_email,
 		get_all_accounts,
+		link_applications,
 		verify_account,
+		withdraw_application,
 	},
-	admin::{ admin_sign_in, verify_admin },
+	admin::{ admin_sign_in, get_email_templates, preview_email, verify_admin },
 	announcement::{
 		create_announcement,
 		delete_announcement,
+		edit_announcement,
+		get_announcement_broadcasts,
+		get_broadcast_report,
 		return_amount_of_announcements,
 		return_announcements,
 	},
+	application_window::{
+		delete_application_window,
+		get_application_windows,
+		get_form_statuses,
+		set_application_window,
+	},
+	attendance::{
+		check_in,
+		get_attendance_by_member,
+		get_checkin_code,
+		get_checkin_qr,
+		get_event_attendance,
+		get_member_attendance,
+	},
+	calendar::{
+		create_calendar_link,
+		get_event_calendar,
+		get_events_calendar,
+		get_private_calendar,
+	},
+	ctf::{
+		create_challenge,
+		create_competition,
+		delete_challenge,
+		delete_competition,
+		edit_challenge,
+		edit_competition,
+		get_all_challenges,
+		get_challenge_solves,
+		get_challenges,
+		get_competitions,
+		get_scoreboard,
+		set_scoreboard_freeze,
+		submit_flag,
+		unlock_hint,
+	},
+	evaluation::{
+		evaluate_executive_member,
+		get_evaluations,
+		get_ranked_executive_members,
+		get_rubric,
+		set_rubric,
+	},
+	event::{
+		cancel_event,
+		cancel_rsvp,
+		create_event,
+		edit_event,
+		get_amount_of_events,
+		get_event_by_id,
+		get_event_rsvps,
+		get_events,
+		rsvp_to_event,
+	},
 	executive_member::{
-		// 	create_executive_member,
+		change_executive_member_status,
+		create_executive_member,
+		edit_executive_member,
 		get_all_executive_members,
 		get_executive_member_by_full_name_or_email,
+		get_executive_member_for_edit,
 	},
+	export::export_records,
+	feed::get_feed,
 	forum_post::{
 		create_post,
 		delete_comment,
@@ -39,13 +104,73 @@ use routes::{
 		return_posts,
 	},
 	general_member::{
+		change_general_member_status,
 		create_general_member,
+		edit_general_member,
 		get_all_general_members,
 		get_general_member_by_full_name_or_email,
+		get_general_member_for_edit,
+		import_general_members,
+	},
+	interview::{
+		book_interview_slot,
+		create_interview_slot,
+		delete_interview_slot,
+		get_available_interview_slots,
+		get_interview_schedule,
+		invite_to_interview,
+	},
+	learning_path::{
+		complete_path_resource,
+		create_learning_path,
+		delete_learning_path,
+		edit_learning_path,
+		get_learning_path,
+		get_learning_paths,
+		get_path_progress,
+		uncomplete_path_resource,
 	},
-	resource::get_resources,
+	material::{
+		delete_material,
+		edit_material,
+		get_material_file,
+		get_materials,
+		upload_material,
+	},
+	resource::{
+		bookmark_resource,
+		check_links,
+		create_resource,
+		create_resource_tag,
+		delete_resource,
+		delete_resource_bookmark,
+		delete_resource_rating,
+		delete_resource_tag,
+		edit_resource,
+		get_link_report,
+		get_resource_tags,
+		get_resources,
+		go_to_resource,
+		rate_resource,
+	},
+	school_year::{ get_alumni, get_roster, get_school_years, roll_over_school_year },
+	team::{
+		create_team,
+		get_my_team,
+		get_teams,
+		join_team,
+		leave_team,
+		reset_invite_code,
+		set_max_team_size,
+		transfer_captain,
+	},
+};
+use services::{
+	db::Database,
+	interview::start_reminders,
+	link_checker::start_link_checker,
+	mail_queue::MailQueue,
 };
-use services::db::Database;
 
 #[macro_use]
 extern crate validator_derive;
@@ -56,6 +181,15 @@ async fn main() -> std::io::Result<()> {
 	let db = Database::init().await;
 	let db_data = Data::new(db);
 
+	// Start the queue that sends emails in the background
+	let mail_queue = Data::new(MailQueue::start(db_data.clone()));
+
+	// Start reminding candidates of their interviews
+	start_reminders(db_data.clone());
+
+	// Start checking the resource links for ones that have stopped working
+	start_link_checker(db_data.clone());
+
 	// Start the server
 	HttpServer::new(move || {
 		// Enable CORS
@@ -70,16 +204,42 @@ async fn main() -> std::io::Result<()> {
 		App::new()
 			.wrap(cors)
 			.app_data(db_data.clone())
+			.app_data(mail_queue.clone())
 			.service(create_general_member)
 			.service(get_general_member_by_full_name_or_email)
 			.service(get_all_general_members)
-			// .service(create_executive_member)
+			.service(change_general_member_status)
+			.service(import_general_members)
+			.service(get_general_member_for_edit)
+			.service(edit_general_member)
+			.service(create_executive_member)
 			.service(get_executive_member_by_full_name_or_email)
 			.service(get_all_executive_members)
+			.service(change_executive_member_status)
+			.service(get_executive_member_for_edit)
+			.service(edit_executive_member)
+			.service(get_rubric)
+			.service(set_rubric)
+			.service(evaluate_executive_member)
+			.service(get_evaluations)
+			.service(get_ranked_executive_members)
+			.service(create_interview_slot)
+			.service(delete_interview_slot)
+			.service(get_interview_schedule)
+			.service(invite_to_interview)
+			.service(get_available_interview_slots)
+			.service(book_interview_slot)
+			.service(roll_over_school_year)
+			.service(get_school_years)
+			.service(get_roster)
+			.service(get_alumni)
 			.service(create_announcement)
+			.service(edit_announcement)
 			.service(delete_announcement)
 			.service(return_announcements)
 			.service(return_amount_of_announcements)
+			.service(get_broadcast_report)
+			.service(get_announcement_broadcasts)
 			.service(get_account_by_username_or_email)
 			.service(create_post)
 			.service(delete_post_as_user)
@@ -95,9 +255,87 @@ async fn main() -> std::io::Result<()> {
 			.service(account_sign_in)
 			.service(verify_account)
 			.service(get_all_accounts)
+			.service(link_applications)
+			.service(get_account_applications)
+			.service(withdraw_application)
+			.service(export_records)
 			.service(admin_sign_in)
 			.service(verify_admin)
+			.service(get_email_templates)
+			.service(preview_email)
 			.service(get_resources)
+			.service(create_resource)
+			.service(edit_resource)
+			.service(delete_resource)
+			.service(get_resource_tags)
+			.service(create_resource_tag)
+			.service(delete_resource_tag)
+			.service(get_link_report)
+			.service(check_links)
+			.service(go_to_resource)
+			.service(rate_resource)
+			.service(delete_resource_rating)
+			.service(bookmark_resource)
+			.service(delete_resource_bookmark)
+			.service(get_learning_paths)
+			.service(get_path_progress)
+			.service(get_learning_path)
+			.service(create_learning_path)
+			.service(edit_learning_path)
+			.service(delete_learning_path)
+			.service(complete_path_resource)
+			.service(uncomplete_path_resource)
+			.service(get_materials)
+			.service(get_material_file)
+			.service(upload_material)
+			.service(edit_material)
+			.service(delete_material)
+			.service(get_events)
+			.service(get_amount_of_events)
+			.service(get_event_by_id)
+			.service(create_event)
+			.service(edit_event)
+			.service(cancel_event)
+			.service(get_event_rsvps)
+			.service(rsvp_to_event)
+			.service(cancel_rsvp)
+			.service(get_checkin_code)
+			.service(get_checkin_qr)
+			.service(check_in)
+			.service(get_event_attendance)
+			.service(get_member_attendance)
+			.service(get_attendance_by_member)
+			.service(get_events_calendar)
+			.service(get_private_calendar)
+			.service(get_event_calendar)
+			.service(create_calendar_link)
+			.service(get_challenges)
+			.service(get_all_challenges)
+			.service(create_challenge)
+			.service(edit_challenge)
+			.service(delete_challenge)
+			.service(submit_flag)
+			.service(unlock_hint)
+			.service(get_challenge_solves)
+			.service(get_scoreboard)
+			.service(set_scoreboard_freeze)
+			.service(get_competitions)
+			.service(create_competition)
+			.service(edit_competition)
+			.service(delete_competition)
+			.service(get_teams)
+			.service(get_my_team)
+			.service(create_team)
+			.service(join_team)
+			.service(leave_team)
+			.service(transfer_captain)
+			.service(reset_invite_code)
+			.service(set_max_team_size)
+			.service(get_feed)
+			.service(get_form_statuses)
+			.service(get_application_windows)
+			.service(set_application_window)
+			.service(delete_application_window)
 	})
 		// Bind the server to the host and port
 		.bind((
@@ -22,6 +22,8 @@ pub struct Account {
 	pub password: String,
 	pub verified: bool,
 	pub date_created: DateTime,
+	#[serde(default)]
+	pub calendar_token: String,
 }
 
 // Create functions to validate the username and email
@@ -69,6 +71,7 @@ impl TryFrom<AccountRequest> for Account {
 			password: item.password,
 			verified: item.verified,
 			date_created: DateTime::from(chrono_datetime),
+			calendar_token: String::new(),
 		})
 	}
 }
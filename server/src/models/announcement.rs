@@ -5,8 +5,10 @@ use serde::{ Deserialize, Serialize };
 use std::time::SystemTime;
 use validator::ValidationError;
 
+use crate::models::broadcast::BroadcastAudience;
+
 // Define the Announcement struct
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Announcement {
 	pub _id: ObjectId,
 	pub author: String,
@@ -14,6 +16,16 @@ pub struct Announcement {
 	pub date_created: DateTime,
 	pub title: String,
 	pub body: String,
+	#[serde(default)]
+	pub draft: bool,
+	#[serde(default)]
+	pub publish_at: Option<DateTime>,
+	#[serde(default)]
+	pub expires_at: Option<DateTime>,
+	#[serde(default)]
+	pub date_updated: Option<DateTime>,
+	#[serde(default)]
+	pub date_published: Option<DateTime>,
 }
 
 // Create functions to validate the author and email
@@ -42,6 +54,10 @@ pub struct AnnouncementRequest {
 	pub date_created: String,
 	pub title: String,
 	pub body: String,
+	pub draft: bool,
+	pub publish_at: String,
+	pub expires_at: String,
+	pub date_updated: String,
 }
 
 // Define the AnnouncementRequestRequest struct (no id with token)
@@ -55,6 +71,53 @@ pub struct AnnouncementRequestRequest {
 	pub title: String,
 	pub body: String,
 	pub token: String,
+	#[serde(default)]
+	pub draft: bool,
+	#[serde(default)]
+	pub publish_at: String,
+	#[serde(default)]
+	pub expires_at: String,
+	pub broadcast: Option<BroadcastAudience>,
+}
+
+// Define the AnnouncementEditRequest struct (the fields an admin can change)
+#[derive(Serialize, Deserialize)]
+pub struct AnnouncementEditRequest {
+	pub title: String,
+	pub body: String,
+	pub draft: bool,
+	pub publish_at: String,
+	pub expires_at: String,
+	pub token: String,
+	pub broadcast: Option<BroadcastAudience>,
+}
+
+// Parse an optional RFC 3339 date, where an empty string means no date
+pub fn parse_optional_date(date: &str) -> Result<Option<DateTime>, Box<dyn std::error::Error>> {
+	if date.is_empty() {
+		return Ok(None);
+	}
+
+	let chrono_datetime: SystemTime = chrono::DateTime
+		::parse_from_rfc3339(date)
+		.map_err(|err| format!("Error parsing date: {err}"))?
+		.with_timezone(&Utc)
+		.into();
+
+	Ok(Some(DateTime::from(chrono_datetime)))
+}
+
+// Check that an announcement expires after it is published
+pub fn validate_schedule(
+	publish_at: Option<DateTime>,
+	expires_at: Option<DateTime>
+) -> Result<(), String> {
+	if let (Some(publish_at), Some(expires_at)) = (publish_at, expires_at) {
+		if expires_at <= publish_at {
+			return Err("The announcement must expire after it is published.".to_string());
+		}
+	}
+	Ok(())
 }
 
 // Implement the TryFrom trait for AnnouncementRequest
@@ -75,6 +138,93 @@ impl TryFrom<AnnouncementRequest> for Announcement {
 			date_created: DateTime::from(chrono_datetime),
 			title: item.title,
 			body: item.body,
-		})
+			draft: item.draft,
+			publish_at: parse_optional_date(&item.publish_at)?,
+			expires_at: parse_optional_date(&item.expires_at)?,
+			date_updated: parse_optional_date(&item.date_updated)?,
+			date_published: None,
+		}.with_date_published())
+	}
+}
+
+impl Announcement {
+	// Check if the announcement can be seen by everyone
+	pub fn is_live(&self) -> bool {
+		let now = DateTime::now();
+		!self.draft &&
+			self.publish_at.is_none_or(|publish_at| publish_at <= now) &&
+			self.expires_at.is_none_or(|expires_at| expires_at > now)
+	}
+
+	// Get the date the announcement was (or will be) published
+	pub fn date_published(&self) -> DateTime {
+		self.publish_at.unwrap_or(self.date_created)
+	}
+
+	// Store the publishing date so announcements can be sorted by it in the database
+	pub fn with_date_published(mut self) -> Self {
+		self.date_published = Some(self.date_published());
+		self
+	}
+}
+
+// Implement the From trait for Announcement
+impl From<Announcement> for AnnouncementRequest {
+	fn from(item: Announcement) -> Self {
+		Self {
+			id: item._id.to_string(),
+			author: item.author,
+			email: item.email,
+			date_created: item.date_created.to_string(),
+			title: item.title,
+			body: item.body,
+			draft: item.draft,
+			publish_at: item.publish_at.map(|date| date.to_string()).unwrap_or_default(),
+			expires_at: item.expires_at.map(|date| date.to_string()).unwrap_or_default(),
+			date_updated: item.date_updated.map(|date| date.to_string()).unwrap_or_default(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// Get the date some minutes from now
+	fn minutes_from_now(minutes: i64) -> Option<DateTime> {
+		Some(DateTime::from_millis(DateTime::now().timestamp_millis() + minutes * 60 * 1000))
+	}
+
+	fn announcement(draft: bool, publish_at: Option<DateTime>, expires_at: Option<DateTime>) -> Announcement {
+		Announcement {
+			_id: ObjectId::new(),
+			author: "Admin".to_string(),
+			email: "admin@example.com".to_string(),
+			date_created: DateTime::now(),
+			title: "Title".to_string(),
+			body: "Body".to_string(),
+			draft,
+			publish_at,
+			expires_at,
+			date_updated: None,
+			date_published: None,
+		}
+	}
+
+	#[test]
+	fn published_announcements_are_live() {
+		assert!(announcement(false, None, None).is_live());
+		assert!(announcement(false, minutes_from_now(-5), minutes_from_now(5)).is_live());
+	}
+
+	#[test]
+	fn drafts_are_not_live() {
+		assert!(!announcement(true, None, None).is_live());
+	}
+
+	#[test]
+	fn scheduled_and_expired_announcements_are_not_live() {
+		assert!(!announcement(false, minutes_from_now(5), None).is_live());
+		assert!(!announcement(false, None, minutes_from_now(-5)).is_live());
 	}
 }
@@ -0,0 +1,130 @@
+use lazy_static::lazy_static;
+use mongodb::bson::DateTime;
+use regex::Regex;
+use serde::{ Deserialize, Serialize };
+
+// Store the regex patterns for the status and note
+lazy_static! {
+	static ref RE_STATUS: Regex = Regex::new(
+		r"^(submitted|under_review|interview|accepted|rejected|withdrawn)$"
+	).unwrap();
+	static ref RE_NOTE: Regex = Regex::new(r"^(?s).{0,600}$").unwrap();
+}
+
+// The status every application starts with
+pub fn default_status() -> String {
+	"submitted".to_string()
+}
+
+// Define the ReviewerNote struct (a status change of an application)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ReviewerNote {
+	pub status: String,
+	pub note: String,
+	pub date_created: DateTime,
+}
+
+// Define the ReviewerNoteRequest struct
+#[derive(Serialize, Deserialize)]
+pub struct ReviewerNoteRequest {
+	pub status: String,
+	pub note: String,
+	pub date_created: String,
+}
+
+// Implement the From trait for ReviewerNote
+impl From<ReviewerNote> for ReviewerNoteRequest {
+	fn from(item: ReviewerNote) -> Self {
+		Self {
+			status: item.status,
+			note: item.note,
+			date_created: item.date_created.to_string(),
+		}
+	}
+}
+
+// Define the StatusChangeRequest struct (an admin reviewing an application, where the note stays
+// private to the reviewers and the message is emailed to the applicant)
+#[derive(Serialize, Deserialize, Validate)]
+pub struct StatusChangeRequest {
+	pub token: String,
+	#[validate(
+		regex(
+			path = *RE_STATUS,
+			message = "Invalid status. Must be submitted, under_review, interview, accepted, rejected, or withdrawn."
+		)
+	)]
+	pub status: String,
+	#[validate(regex(path = *RE_NOTE, message = "Note should be from 0 to 600 characters."))]
+	pub note: String,
+	#[serde(default)]
+	#[validate(regex(path = *RE_NOTE, message = "Message should be from 0 to 600 characters."))]
+	pub message: String,
+}
+
+// Define the AccountApplication struct (an application as its applicant sees it)
+#[derive(Serialize, Deserialize)]
+pub struct AccountApplication {
+	pub id: String,
+	pub form: String,
+	pub exec_type: String,
+	pub status: String,
+	pub term_ended: bool,
+	pub date_created: String,
+}
+
+// Define the FieldChange struct (one field an applicant changed)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FieldChange {
+	pub field: String,
+	pub old: String,
+	pub new: String,
+}
+
+impl FieldChange {
+	// Record the change of a field, if it changed at all
+	pub fn between(field: &str, old: String, new: String) -> Option<Self> {
+		if old == new {
+			return None;
+		}
+		Some(Self {
+			field: field.to_string(),
+			old,
+			new,
+		})
+	}
+}
+
+// Define the ApplicationEdit struct (an applicant's edit of their own application)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ApplicationEdit {
+	pub editor: String,
+	pub changes: Vec<FieldChange>,
+	pub date_created: DateTime,
+}
+
+// Define the ApplicationEditRequest struct
+#[derive(Serialize, Deserialize)]
+pub struct ApplicationEditRequest {
+	pub editor: String,
+	pub changes: Vec<FieldChange>,
+	pub date_created: String,
+}
+
+// Implement the From trait for ApplicationEdit
+impl From<ApplicationEdit> for ApplicationEditRequest {
+	fn from(item: ApplicationEdit) -> Self {
+		Self {
+			editor: item.editor,
+			changes: item.changes,
+			date_created: item.date_created.to_string(),
+		}
+	}
+}
+
+// Define the EditArgs struct (the secret of an application's edit link)
+#[derive(Deserialize)]
+pub struct EditArgs {
+	#[serde(default)]
+	pub token: String,
+}
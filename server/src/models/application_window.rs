@@ -0,0 +1,135 @@
+use chrono::Utc;
+use lazy_static::lazy_static;
+use mongodb::bson::{ oid::ObjectId, DateTime };
+use regex::Regex;
+use serde::{ Deserialize, Serialize };
+use std::time::SystemTime;
+
+// Store the regex patterns for the form and exec type
+lazy_static! {
+	static ref RE_FORM: Regex = Regex::new(r"^(general|executive)$").unwrap();
+	static ref RE_EXEC_TYPE: Regex = Regex::new(r"^(development|marketing|events)?$").unwrap();
+}
+
+// Define the ApplicationWindow struct (when a form accepts responses)
+#[derive(Serialize, Deserialize)]
+pub struct ApplicationWindow {
+	pub _id: ObjectId,
+	pub form: String,
+	pub exec_type: String,
+	pub opens_at: DateTime,
+	pub closes_at: DateTime,
+}
+
+// Define the ApplicationWindowRequest struct (an empty exec type applies to every exec type)
+#[derive(Serialize, Deserialize, Validate)]
+pub struct ApplicationWindowRequest {
+	#[validate(regex(path = *RE_FORM, message = "Invalid form. Must be general or executive."))]
+	pub form: String,
+	#[validate(
+		regex(
+			path = *RE_EXEC_TYPE,
+			message = "Invalid exec type. Must be development, marketing, events, or empty."
+		)
+	)]
+	pub exec_type: String,
+	pub opens_at: String,
+	pub closes_at: String,
+	pub token: String,
+}
+
+// Define the FormStatus struct (whether a form is accepting responses right now)
+#[derive(Serialize, Deserialize)]
+pub struct FormStatus {
+	pub form: String,
+	pub exec_type: String,
+	pub open: bool,
+	pub opens_at: String,
+	pub closes_at: String,
+}
+
+// Parse an RFC 3339 date
+fn parse_date(date: &str) -> Result<DateTime, Box<dyn std::error::Error>> {
+	let chrono_datetime: SystemTime = chrono::DateTime
+		::parse_from_rfc3339(date)
+		.map_err(|err| format!("Error parsing date: {err}"))?
+		.with_timezone(&Utc)
+		.into();
+
+	Ok(DateTime::from(chrono_datetime))
+}
+
+// Implement the TryFrom trait for ApplicationWindowRequest
+impl TryFrom<ApplicationWindowRequest> for ApplicationWindow {
+	type Error = Box<dyn std::error::Error>;
+
+	fn try_from(item: ApplicationWindowRequest) -> Result<Self, Self::Error> {
+		let opens_at = parse_date(&item.opens_at)?;
+		let closes_at = parse_date(&item.closes_at)?;
+		if closes_at <= opens_at {
+			return Err("The form must close after it opens.".into());
+		}
+
+		// General applications do not have an exec type
+		let exec_type = if item.form == "general" { String::new() } else { item.exec_type };
+
+		Ok(Self {
+			_id: ObjectId::new(),
+			form: item.form,
+			exec_type,
+			opens_at,
+			closes_at,
+		})
+	}
+}
+
+impl ApplicationWindow {
+	// Check if the form is accepting responses right now
+	pub fn is_open(&self) -> bool {
+		let now = DateTime::now();
+		self.opens_at <= now && now < self.closes_at
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// Get the date some minutes from now
+	fn minutes_from_now(minutes: i64) -> DateTime {
+		DateTime::from_millis(DateTime::now().timestamp_millis() + minutes * 60 * 1000)
+	}
+
+	fn window(opens_at: DateTime, closes_at: DateTime) -> ApplicationWindow {
+		ApplicationWindow {
+			_id: ObjectId::new(),
+			form: "general".to_string(),
+			exec_type: String::new(),
+			opens_at,
+			closes_at,
+		}
+	}
+
+	#[test]
+	fn is_open_between_its_dates() {
+		assert!(window(minutes_from_now(-5), minutes_from_now(5)).is_open());
+	}
+
+	#[test]
+	fn is_closed_before_it_opens_and_after_it_closes() {
+		assert!(!window(minutes_from_now(5), minutes_from_now(10)).is_open());
+		assert!(!window(minutes_from_now(-10), minutes_from_now(-5)).is_open());
+	}
+
+	#[test]
+	fn must_close_after_it_opens() {
+		let request = ApplicationWindowRequest {
+			form: "general".to_string(),
+			exec_type: "events".to_string(),
+			opens_at: "2025-09-10T00:00:00Z".to_string(),
+			closes_at: "2025-09-01T00:00:00Z".to_string(),
+			token: String::new(),
+		};
+		assert!(ApplicationWindow::try_from(request).is_err());
+	}
+}
@@ -0,0 +1,72 @@
+use mongodb::bson::{ oid::ObjectId, DateTime };
+use serde::{ Deserialize, Serialize };
+
+// Define the Attendance struct (an account checking in to an event)
+#[derive(Serialize, Deserialize)]
+pub struct Attendance {
+	pub _id: ObjectId,
+	pub event_id: ObjectId,
+	pub title: String,
+	pub account_id: ObjectId,
+	pub username: String,
+	pub email: String,
+	pub date_created: DateTime,
+}
+
+// Define the AttendanceRequest struct
+#[derive(Serialize, Deserialize)]
+pub struct AttendanceRequest {
+	pub event_id: String,
+	pub title: String,
+	pub username: String,
+	pub email: String,
+	pub date_created: String,
+}
+
+// Implement the From trait for Attendance
+impl From<Attendance> for AttendanceRequest {
+	fn from(item: Attendance) -> Self {
+		Self {
+			event_id: item.event_id.to_string(),
+			title: item.title,
+			username: item.username,
+			email: item.email,
+			date_created: item.date_created.to_string(),
+		}
+	}
+}
+
+// Define the CheckInRequest struct
+#[derive(Serialize, Deserialize)]
+pub struct CheckInRequest {
+	pub code: String,
+}
+
+// Define the CheckInCode struct (what the check-in screen shows)
+#[derive(Serialize, Deserialize)]
+pub struct CheckInCode {
+	pub code: String,
+	pub url: String,
+	pub expires_at: String,
+}
+
+// Define the EventAttendance struct (who came to an event)
+#[derive(Serialize, Deserialize)]
+pub struct EventAttendance {
+	pub event_id: String,
+	pub title: String,
+	pub going: u64,
+	pub attended: u64,
+	pub attendees: Vec<AttendanceRequest>,
+	pub no_shows: Vec<String>,
+}
+
+// Define the MemberAttendance struct (how many events of a school year a member came to)
+#[derive(Serialize, Deserialize)]
+pub struct MemberAttendance {
+	pub account_id: String,
+	pub username: String,
+	pub email: String,
+	pub attended: u64,
+	pub events: u64,
+}
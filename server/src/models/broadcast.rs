@@ -0,0 +1,89 @@
+use lazy_static::lazy_static;
+use mongodb::bson::{ oid::ObjectId, DateTime };
+use regex::Regex;
+use serde::{ Deserialize, Serialize };
+use validator::ValidationError;
+
+// Store the regex pattern for the exec types
+lazy_static! {
+	static ref RE_EXEC_TYPE: Regex = Regex::new(r"^(development|marketing|events)$").unwrap();
+}
+
+// Define the BroadcastAudience struct (who an announcement is emailed to: verified accounts, and accepted
+// members whose executive terms have not ended)
+#[derive(Clone, Serialize, Deserialize, Validate)]
+pub struct BroadcastAudience {
+	pub accounts: bool,
+	pub general_members: bool,
+	pub executive_members: bool,
+	#[validate(custom(function = "validate_grades"))]
+	pub grades: Vec<u8>,
+	#[validate(custom(function = "validate_exec_types"))]
+	pub exec_types: Vec<String>,
+}
+
+// Create functions to validate the audience filters
+fn validate_grades(grades: &[u8]) -> Result<(), ValidationError> {
+	if grades.iter().any(|grade| !(9..=12).contains(grade)) {
+		return Err(ValidationError::new("Grades should be 9, 10, 11, or 12."));
+	}
+	Ok(())
+}
+
+fn validate_exec_types(exec_types: &[String]) -> Result<(), ValidationError> {
+	if exec_types.iter().any(|exec_type| !RE_EXEC_TYPE.is_match(exec_type)) {
+		return Err(
+			ValidationError::new("Invalid exec type. Must be development, marketing, or events.")
+		);
+	}
+	Ok(())
+}
+
+// Define the Broadcast struct (the delivery report of an announcement email, which is queued, sending, finished,
+// or failed if the server stopped before it was sent to everyone)
+#[derive(Serialize, Deserialize)]
+pub struct Broadcast {
+	pub _id: ObjectId,
+	pub announcement_id: ObjectId,
+	pub audience: BroadcastAudience,
+	pub status: String,
+	pub total: u32,
+	pub sent: u32,
+	pub failed: u32,
+	pub failed_emails: Vec<String>,
+	pub date_created: DateTime,
+	pub date_finished: Option<DateTime>,
+}
+
+// Define the BroadcastRequest struct
+#[derive(Serialize, Deserialize)]
+pub struct BroadcastRequest {
+	pub id: String,
+	pub announcement_id: String,
+	pub audience: BroadcastAudience,
+	pub status: String,
+	pub total: u32,
+	pub sent: u32,
+	pub failed: u32,
+	pub failed_emails: Vec<String>,
+	pub date_created: String,
+	pub date_finished: String,
+}
+
+// Implement the From trait for Broadcast
+impl From<Broadcast> for BroadcastRequest {
+	fn from(item: Broadcast) -> Self {
+		Self {
+			id: item._id.to_string(),
+			announcement_id: item.announcement_id.to_string(),
+			audience: item.audience,
+			status: item.status,
+			total: item.total,
+			sent: item.sent,
+			failed: item.failed,
+			failed_emails: item.failed_emails,
+			date_created: item.date_created.to_string(),
+			date_finished: item.date_finished.map(|date| date.to_string()).unwrap_or_default(),
+		}
+	}
+}
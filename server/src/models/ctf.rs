@@ -0,0 +1,620 @@
+use chrono::Utc;
+use lazy_static::lazy_static;
+use mongodb::bson::{ oid::ObjectId, DateTime };
+use regex::Regex;
+use serde::{ Deserialize, Serialize };
+use std::time::SystemTime;
+use validator::ValidationError;
+
+// Store the regex patterns for various fields
+lazy_static! {
+	static ref RE_TITLE: Regex = Regex::new(r"^.{1,100}$").unwrap();
+	static ref RE_CATEGORY: Regex = Regex::new(
+		r"^(web|crypto|forensics|pwn|reverse|osint|misc)$"
+	).unwrap();
+	static ref RE_DESCRIPTION: Regex = Regex::new(r"^(?s).{1,5000}$").unwrap();
+	static ref RE_ATTACHMENT: Regex = Regex::new(r"^https?://\S{1,500}$").unwrap();
+	static ref RE_FLAG: Regex = Regex::new(r"^.{0,200}$").unwrap();
+	static ref RE_HINT: Regex = Regex::new(r"^(?s).{1,1000}$").unwrap();
+	static ref RE_COMPETITION_NAME: Regex = Regex::new(r"^.{1,100}$").unwrap();
+	static ref RE_TEAM_NAME: Regex = Regex::new(r"^[\w .'-]{3,30}$").unwrap();
+}
+
+// Create functions to validate the attachments and the flag regex
+fn validate_attachments(attachments: &[String]) -> Result<(), ValidationError> {
+	if attachments.len() > 10 || attachments.iter().any(|link| !RE_ATTACHMENT.is_match(link)) {
+		return Err(ValidationError::new("Attachments should be at most 10 http or https links."));
+	}
+	Ok(())
+}
+
+fn validate_flag_regex(flag_regex: &str) -> Result<(), ValidationError> {
+	if flag_regex.len() > 200 || Regex::new(flag_regex).is_err() {
+		return Err(ValidationError::new("Flag regex should be a valid regex of at most 200 characters."));
+	}
+	Ok(())
+}
+
+fn validate_hints(hints: &[HintRequest]) -> Result<(), ValidationError> {
+	if
+		hints.len() > 10 ||
+		hints.iter().any(|hint| !RE_HINT.is_match(&hint.content) || hint.cost > 1000)
+	{
+		return Err(
+			ValidationError::new(
+				"Hints should be at most 10, each from 1 to 1000 characters and costing at most 1000 points."
+			)
+		);
+	}
+	Ok(())
+}
+
+// Parse an RFC 3339 date
+fn parse_date(date: &str) -> Result<DateTime, Box<dyn std::error::Error>> {
+	let chrono_datetime: SystemTime = chrono::DateTime
+		::parse_from_rfc3339(date)
+		.map_err(|err| format!("Error parsing date: {err}"))?
+		.with_timezone(&Utc)
+		.into();
+
+	Ok(DateTime::from(chrono_datetime))
+}
+
+// Define the Hint struct (unlocking it takes its cost off the score)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Hint {
+	pub content: String,
+	pub cost: u32,
+}
+
+// Define the HintRequest struct (the content of a locked hint is left out for players)
+#[derive(Serialize, Deserialize)]
+pub struct HintRequest {
+	#[serde(default)]
+	pub index: u32,
+	#[serde(default)]
+	pub content: String,
+	pub cost: u32,
+	#[serde(default)]
+	pub unlocked: bool,
+}
+
+// Define the Challenge struct (the flag is kept as a bcrypt hash, or as a regex the whole flag must match)
+#[derive(Serialize, Deserialize)]
+pub struct Challenge {
+	pub _id: ObjectId,
+	pub title: String,
+	pub category: String,
+	pub description: String,
+	pub points: u32,
+	pub minimum_points: u32,
+	pub decay: u32,
+	pub attachments: Vec<String>,
+	pub hints: Vec<Hint>,
+	pub flag_hash: String,
+	pub flag_regex: String,
+	pub hidden: bool,
+	pub competition_id: Option<ObjectId>,
+	pub date_created: DateTime,
+	pub date_updated: DateTime,
+}
+
+// Define the ChallengeRequest struct (the flag is never sent back, and is kept as is when left empty on an edit)
+#[derive(Serialize, Deserialize, Validate)]
+pub struct ChallengeRequest {
+	#[serde(default)]
+	pub id: String,
+	#[validate(regex(path = *RE_TITLE, message = "Title should be from 1 to 100 characters."))]
+	pub title: String,
+	#[validate(
+		regex(
+			path = *RE_CATEGORY,
+			message = "Invalid category. Must be web, crypto, forensics, pwn, reverse, osint or misc."
+		)
+	)]
+	pub category: String,
+	#[validate(
+		regex(path = *RE_DESCRIPTION, message = "Description should be from 1 to 5000 characters.")
+	)]
+	pub description: String,
+	#[validate(range(min = 1, max = 1000, message = "Points should be from 1 to 1000."))]
+	pub points: u32,
+	#[serde(default)]
+	#[validate(range(max = 1000, message = "Minimum points should be from 0 to 1000."))]
+	pub minimum_points: u32,
+	#[serde(default)]
+	#[validate(range(max = 1000, message = "Decay should be from 0 to 1000 solves."))]
+	pub decay: u32,
+	#[serde(default)]
+	pub value: u32,
+	#[serde(default)]
+	#[validate(custom(function = "validate_attachments"))]
+	pub attachments: Vec<String>,
+	#[serde(default)]
+	#[validate(custom(function = "validate_hints"))]
+	pub hints: Vec<HintRequest>,
+	#[serde(default, skip_serializing)]
+	#[validate(regex(path = *RE_FLAG, message = "Flag should be at most 200 characters."))]
+	pub flag: String,
+	#[serde(default, skip_serializing)]
+	#[validate(custom(function = "validate_flag_regex"))]
+	pub flag_regex: String,
+	#[serde(default)]
+	pub hidden: bool,
+	#[serde(default)]
+	pub competition_id: String,
+	#[serde(default)]
+	pub solves: u64,
+	#[serde(default)]
+	pub solved: bool,
+	#[serde(default)]
+	pub date_created: String,
+	#[serde(default)]
+	pub date_updated: String,
+	#[serde(default, skip_serializing)]
+	pub token: String,
+}
+
+// Implement the TryFrom trait for ChallengeRequest (the caller hashes the flag)
+impl TryFrom<ChallengeRequest> for Challenge {
+	type Error = Box<dyn std::error::Error>;
+
+	fn try_from(item: ChallengeRequest) -> Result<Self, Self::Error> {
+		if item.decay > 0 && item.minimum_points > item.points {
+			return Err("Minimum points should not be more than the points.".into());
+		}
+		let competition_id = if item.competition_id.is_empty() {
+			None
+		} else {
+			Some(ObjectId::parse_str(&item.competition_id).map_err(|_| "Invalid competition ID.")?)
+		};
+
+		Ok(Self {
+			_id: ObjectId::new(),
+			title: item.title,
+			category: item.category,
+			description: item.description,
+			points: item.points,
+			minimum_points: item.minimum_points,
+			decay: item.decay,
+			attachments: item.attachments,
+			hints: item.hints
+				.into_iter()
+				.map(|hint| Hint { content: hint.content, cost: hint.cost })
+				.collect(),
+			flag_hash: String::new(),
+			flag_regex: item.flag_regex,
+			hidden: item.hidden,
+			competition_id,
+			date_created: DateTime::now(),
+			date_updated: DateTime::now(),
+		})
+	}
+}
+
+// Implement the From trait for Challenge
+impl From<Challenge> for ChallengeRequest {
+	fn from(item: Challenge) -> Self {
+		Self {
+			id: item._id.to_string(),
+			title: item.title,
+			category: item.category,
+			description: item.description,
+			points: item.points,
+			minimum_points: item.minimum_points,
+			decay: item.decay,
+			value: item.points,
+			attachments: item.attachments,
+			hints: item.hints
+				.into_iter()
+				.enumerate()
+				.map(|(index, hint)| HintRequest {
+					index: index as u32,
+					content: hint.content,
+					cost: hint.cost,
+					unlocked: false,
+				})
+				.collect(),
+			flag: String::new(),
+			flag_regex: String::new(),
+			hidden: item.hidden,
+			competition_id: item.competition_id.map(|id| id.to_string()).unwrap_or_default(),
+			solves: 0,
+			solved: false,
+			date_created: item.date_created.to_string(),
+			date_updated: item.date_updated.to_string(),
+			token: String::new(),
+		}
+	}
+}
+
+impl Challenge {
+	// Get what a solve is worth once a challenge has some solves, dropping along a curve from the points to the
+	// minimum points at the decay'th solve (the first solve does not lower it)
+	pub fn value(&self, solves: u64) -> u32 {
+		if self.decay == 0 || self.minimum_points >= self.points {
+			return self.points;
+		}
+		let initial = self.points as f64;
+		let minimum = self.minimum_points as f64;
+		let decay = self.decay as f64;
+		let solves = solves.saturating_sub(1) as f64;
+		let value = ((minimum - initial) / (decay * decay)) * solves * solves + initial;
+		value.ceil().max(minimum) as u32
+	}
+
+	// Check a submitted flag against the regex, or else the hash
+	pub fn check_flag(&self, flag: &str) -> bool {
+		if !self.flag_regex.is_empty() {
+			return Regex::new(&format!("^(?:{})$", self.flag_regex))
+				.map(|regex| regex.is_match(flag))
+				.unwrap_or(false);
+		}
+		!self.flag_hash.is_empty() && bcrypt::verify(flag, &self.flag_hash).unwrap_or(false)
+	}
+}
+
+// Define the FlagSubmission struct
+#[derive(Serialize, Deserialize)]
+pub struct FlagSubmission {
+	pub flag: String,
+}
+
+// Define the Submission struct (every flag an account tried, kept for rate limiting and review)
+#[derive(Serialize, Deserialize)]
+pub struct Submission {
+	pub _id: ObjectId,
+	pub challenge_id: ObjectId,
+	pub account_id: ObjectId,
+	pub correct: bool,
+	pub date_created: DateTime,
+}
+
+// Define the Solve struct (an account solving a challenge)
+#[derive(Serialize, Deserialize)]
+pub struct Solve {
+	pub _id: ObjectId,
+	pub challenge_id: ObjectId,
+	pub account_id: ObjectId,
+	pub username: String,
+	pub team_id: Option<ObjectId>,
+	pub date_created: DateTime,
+}
+
+// Define the SolveRequest struct
+#[derive(Serialize, Deserialize)]
+pub struct SolveRequest {
+	pub challenge_id: String,
+	pub username: String,
+	pub date_created: String,
+}
+
+// Implement the From trait for Solve
+impl From<Solve> for SolveRequest {
+	fn from(item: Solve) -> Self {
+		Self {
+			challenge_id: item.challenge_id.to_string(),
+			username: item.username,
+			date_created: item.date_created.to_string(),
+		}
+	}
+}
+
+// Define the CtfSettings struct (when the public scoreboard stops updating, and how big teams can get)
+#[derive(Serialize, Deserialize)]
+pub struct CtfSettings {
+	pub _id: ObjectId,
+	pub name: String,
+	pub freeze_at: Option<DateTime>,
+	pub max_team_size: u32,
+}
+
+// Define the FreezeRequest struct (an empty date unfreezes the scoreboard)
+#[derive(Serialize, Deserialize)]
+pub struct FreezeRequest {
+	#[serde(default)]
+	pub freeze_at: String,
+	pub token: String,
+}
+
+// Define the ScorePoint struct (a competitor's score right after a solve or a hint)
+#[derive(Serialize, Deserialize)]
+pub struct ScorePoint {
+	pub date: String,
+	pub score: i64,
+}
+
+// Define the ScoreboardEntry struct
+#[derive(Serialize, Deserialize)]
+pub struct ScoreboardEntry {
+	pub rank: u64,
+	pub name: String,
+	pub score: i64,
+	pub solves: u64,
+	pub hint_penalty: u64,
+	pub last_solve: String,
+	pub first_bloods: Vec<String>,
+	pub history: Vec<ScorePoint>,
+}
+
+// Define the ChallengeScore struct (what a challenge is worth on the scoreboard)
+#[derive(Serialize, Deserialize)]
+pub struct ChallengeScore {
+	pub id: String,
+	pub title: String,
+	pub category: String,
+	pub value: u32,
+	pub solves: u64,
+	pub first_blood: String,
+}
+
+// Define the Scoreboard struct
+#[derive(Serialize, Deserialize)]
+pub struct Scoreboard {
+	pub frozen: bool,
+	pub freeze_at: String,
+	pub challenges: Vec<ChallengeScore>,
+	pub entries: Vec<ScoreboardEntry>,
+}
+
+// Define the ScoreboardArgs struct
+#[derive(Serialize, Deserialize)]
+pub struct ScoreboardArgs {
+	#[serde(default)]
+	pub competition: String,
+	#[serde(default)]
+	pub teams: bool,
+}
+
+// Define the CompetitionArgs struct (no competition means the practice challenges)
+#[derive(Serialize, Deserialize)]
+pub struct CompetitionArgs {
+	#[serde(default)]
+	pub competition: String,
+}
+
+// Define the HintUnlock struct (an account paying for a hint)
+#[derive(Serialize, Deserialize)]
+pub struct HintUnlock {
+	pub _id: ObjectId,
+	pub challenge_id: ObjectId,
+	pub account_id: ObjectId,
+	pub username: String,
+	pub team_id: Option<ObjectId>,
+	pub index: u32,
+	pub cost: u32,
+	pub date_created: DateTime,
+}
+
+// Define the Competition struct (a timed CTF with its own challenges, scoreboard freeze and team size)
+#[derive(Serialize, Deserialize)]
+pub struct Competition {
+	pub _id: ObjectId,
+	pub name: String,
+	pub starts_at: DateTime,
+	pub ends_at: DateTime,
+	pub freeze_at: Option<DateTime>,
+	pub max_team_size: u32,
+	pub date_created: DateTime,
+}
+
+// Define the CompetitionRequest struct (a team size of 0 means the usual limit)
+#[derive(Serialize, Deserialize, Validate)]
+pub struct CompetitionRequest {
+	#[serde(default)]
+	pub id: String,
+	#[validate(
+		regex(path = *RE_COMPETITION_NAME, message = "Name should be from 1 to 100 characters.")
+	)]
+	pub name: String,
+	pub starts_at: String,
+	pub ends_at: String,
+	#[serde(default)]
+	pub freeze_at: String,
+	#[serde(default)]
+	#[validate(range(max = 20, message = "Team size should be from 0 to 20."))]
+	pub max_team_size: u32,
+	#[serde(default)]
+	pub date_created: String,
+	#[serde(default, skip_serializing)]
+	pub token: String,
+}
+
+// Implement the TryFrom trait for CompetitionRequest
+impl TryFrom<CompetitionRequest> for Competition {
+	type Error = Box<dyn std::error::Error>;
+
+	fn try_from(item: CompetitionRequest) -> Result<Self, Self::Error> {
+		let starts_at = parse_date(&item.starts_at)?;
+		let ends_at = parse_date(&item.ends_at)?;
+		if ends_at <= starts_at {
+			return Err("The competition must end after it starts.".into());
+		}
+		let freeze_at = if item.freeze_at.is_empty() { None } else { Some(parse_date(&item.freeze_at)?) };
+		if freeze_at.is_some_and(|freeze_at| freeze_at < starts_at || freeze_at > ends_at) {
+			return Err("The scoreboard must freeze while the competition is running.".into());
+		}
+
+		Ok(Self {
+			_id: ObjectId::new(),
+			name: item.name,
+			starts_at,
+			ends_at,
+			freeze_at,
+			max_team_size: item.max_team_size,
+			date_created: DateTime::now(),
+		})
+	}
+}
+
+// Implement the From trait for Competition
+impl From<Competition> for CompetitionRequest {
+	fn from(item: Competition) -> Self {
+		Self {
+			id: item._id.to_string(),
+			name: item.name,
+			starts_at: item.starts_at.to_string(),
+			ends_at: item.ends_at.to_string(),
+			freeze_at: item.freeze_at.map(|date| date.to_string()).unwrap_or_default(),
+			max_team_size: item.max_team_size,
+			date_created: item.date_created.to_string(),
+			token: String::new(),
+		}
+	}
+}
+
+impl Competition {
+	// Check if the competition's challenges can be seen yet
+	pub fn has_started(&self) -> bool {
+		DateTime::now() >= self.starts_at
+	}
+
+	// Check if flags are still being accepted
+	pub fn has_ended(&self) -> bool {
+		DateTime::now() > self.ends_at
+	}
+}
+
+// Define the TeamMember struct
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TeamMember {
+	pub account_id: ObjectId,
+	pub username: String,
+	pub date_joined: DateTime,
+}
+
+// Define the Team struct (solves by its members count once for the whole team)
+#[derive(Serialize, Deserialize)]
+pub struct Team {
+	pub _id: ObjectId,
+	pub name: String,
+	pub invite_code: String,
+	pub captain_id: ObjectId,
+	pub members: Vec<TeamMember>,
+	pub date_created: DateTime,
+}
+
+// Define the TeamRequest struct (the invite code is only shown to the team's members)
+#[derive(Serialize, Deserialize, Validate)]
+pub struct TeamRequest {
+	#[serde(default)]
+	pub id: String,
+	#[validate(
+		regex(
+			path = *RE_TEAM_NAME,
+			message = "Team name should be from 3 to 30 letters, numbers, spaces, dots, apostrophes or dashes."
+		)
+	)]
+	pub name: String,
+	#[serde(default)]
+	pub invite_code: String,
+	#[serde(default)]
+	pub captain: String,
+	#[serde(default)]
+	pub members: Vec<String>,
+	#[serde(default)]
+	pub date_created: String,
+}
+
+// Implement the From trait for Team
+impl From<Team> for TeamRequest {
+	fn from(item: Team) -> Self {
+		let captain = item.members
+			.iter()
+			.find(|member| member.account_id == item.captain_id)
+			.map(|member| member.username.clone())
+			.unwrap_or_default();
+		Self {
+			id: item._id.to_string(),
+			name: item.name,
+			invite_code: String::new(),
+			captain,
+			members: item.members
+				.into_iter()
+				.map(|member| member.username)
+				.collect(),
+			date_created: item.date_created.to_string(),
+		}
+	}
+}
+
+// Define the JoinTeamRequest struct
+#[derive(Serialize, Deserialize)]
+pub struct JoinTeamRequest {
+	pub invite_code: String,
+}
+
+// Define the TransferRequest struct
+#[derive(Serialize, Deserialize)]
+pub struct TransferRequest {
+	pub username: String,
+}
+
+// Define the TeamSizeRequest struct
+#[derive(Serialize, Deserialize, Validate)]
+pub struct TeamSizeRequest {
+	#[validate(range(min = 1, max = 20, message = "Team size should be from 1 to 20."))]
+	pub max_team_size: u32,
+	pub token: String,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn challenge(points: u32, minimum_points: u32, decay: u32) -> Challenge {
+		Challenge {
+			_id: ObjectId::new(),
+			title: "Challenge".to_string(),
+			category: "misc".to_string(),
+			description: "Description".to_string(),
+			points,
+			minimum_points,
+			decay,
+			attachments: Vec::new(),
+			hints: Vec::new(),
+			flag_hash: String::new(),
+			flag_regex: String::new(),
+			hidden: false,
+			competition_id: None,
+			date_created: DateTime::now(),
+			date_updated: DateTime::now(),
+		}
+	}
+
+	#[test]
+	fn value_decays_from_the_points_to_the_minimum() {
+		let challenge = challenge(500, 100, 10);
+		assert_eq!(challenge.value(0), 500);
+		assert_eq!(challenge.value(1), 500);
+		assert_eq!(challenge.value(6), 400);
+		assert_eq!(challenge.value(11), 100);
+		assert_eq!(challenge.value(50), 100);
+	}
+
+	#[test]
+	fn value_is_static_without_a_decay() {
+		assert_eq!(challenge(500, 100, 0).value(50), 500);
+		assert_eq!(challenge(100, 500, 10).value(50), 100);
+	}
+
+	#[test]
+	fn flag_regex_matches_the_whole_flag() {
+		let mut challenge = challenge(100, 100, 0);
+		challenge.flag_regex = r"flag\{[a-z]+\}|ctf\{[0-9]+\}".to_string();
+		assert!(challenge.check_flag("flag{abc}"));
+		assert!(challenge.check_flag("ctf{123}"));
+		assert!(!challenge.check_flag("xflag{abc}"));
+		assert!(!challenge.check_flag("flag{abc}x"));
+		assert!(!challenge.check_flag("flag{abc}ctf{123}"));
+	}
+
+	#[test]
+	fn flag_hash_is_checked_without_a_regex() {
+		let mut challenge = challenge(100, 100, 0);
+		assert!(!challenge.check_flag(""));
+		challenge.flag_hash = bcrypt::hash("flag{secret}", 4).unwrap();
+		assert!(challenge.check_flag("flag{secret}"));
+		assert!(!challenge.check_flag("flag{wrong}"));
+	}
+}
@@ -0,0 +1,104 @@
+use lazy_static::lazy_static;
+use mongodb::bson::{ oid::ObjectId, DateTime };
+use regex::Regex;
+use serde::{ Deserialize, Serialize };
+
+use crate::models::rubric::Rubric;
+
+// Store the regex patterns for the reviewer and comment
+lazy_static! {
+	static ref RE_REVIEWER: Regex = Regex::new(r"^[A-Za-z0-9 ._'-]{2,40}$").unwrap();
+	static ref RE_COMMENT: Regex = Regex::new(r"^(?s).{0,600}$").unwrap();
+}
+
+// Define the CriterionScore struct (the score given for one rubric criterion)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CriterionScore {
+	pub criterion: String,
+	pub score: u8,
+}
+
+// Define the Evaluation struct (one reviewer's scores for an executive application)
+#[derive(Serialize, Deserialize)]
+pub struct Evaluation {
+	pub _id: ObjectId,
+	pub application_id: ObjectId,
+	pub exec_type: String,
+	pub reviewer: String,
+	pub scores: Vec<CriterionScore>,
+	pub comment: String,
+	pub date_created: DateTime,
+}
+
+// Define the EvaluationRequest struct
+#[derive(Serialize, Deserialize, Validate)]
+pub struct EvaluationRequest {
+	#[serde(default)]
+	pub id: String,
+	#[validate(regex(path = *RE_REVIEWER, message = "Invalid reviewer name."))]
+	pub reviewer: String,
+	pub scores: Vec<CriterionScore>,
+	#[validate(regex(path = *RE_COMMENT, message = "Comment should be from 0 to 600 characters."))]
+	pub comment: String,
+	#[serde(default)]
+	pub date_created: String,
+	#[serde(default, skip_serializing)]
+	pub token: String,
+}
+
+// Implement the From trait for Evaluation
+impl From<Evaluation> for EvaluationRequest {
+	fn from(item: Evaluation) -> Self {
+		Self {
+			id: item._id.to_string(),
+			reviewer: item.reviewer,
+			scores: item.scores,
+			comment: item.comment,
+			date_created: item.date_created.to_string(),
+			token: String::new(),
+		}
+	}
+}
+
+// Define the CriterionAverage struct
+#[derive(Serialize, Deserialize)]
+pub struct CriterionAverage {
+	pub criterion: String,
+	pub average: f64,
+	pub max_score: u8,
+}
+
+// Define the RankedApplication struct (an executive application with its aggregated scores)
+#[derive(Serialize, Deserialize)]
+pub struct RankedApplication {
+	pub rank: u32,
+	pub id: String,
+	pub full_name: String,
+	pub email: String,
+	pub grade: u8,
+	pub exec_type: String,
+	pub status: String,
+	pub score: f64,
+	pub review_count: u32,
+	pub criteria: Vec<CriterionAverage>,
+}
+
+impl Evaluation {
+	// Get the weighted score out of 100, ignoring criteria that are no longer in the rubric
+	pub fn weighted_score(&self, rubric: &Rubric) -> Option<f64> {
+		let mut total = 0.0;
+		let mut weights = 0.0;
+		for criterion in &rubric.criteria {
+			if let Some(score) = self.scores.iter().find(|score| score.criterion == criterion.name) {
+				let score = (score.score.min(criterion.max_score) as f64) / (criterion.max_score as f64);
+				total += score * (criterion.weight as f64);
+				weights += criterion.weight as f64;
+			}
+		}
+
+		if weights == 0.0 {
+			return None;
+		}
+		Some((total / weights) * 100.0)
+	}
+}
@@ -0,0 +1,179 @@
+use chrono::Utc;
+use lazy_static::lazy_static;
+use mongodb::bson::{ oid::ObjectId, DateTime };
+use regex::Regex;
+use serde::{ Deserialize, Serialize };
+use std::time::SystemTime;
+
+// Store the regex patterns for various fields
+lazy_static! {
+	static ref RE_TITLE: Regex = Regex::new(r"^.{1,100}$").unwrap();
+	static ref RE_DESCRIPTION: Regex = Regex::new(r"^(?s).{0,2000}$").unwrap();
+	static ref RE_KIND: Regex = Regex::new(r"^(session|workshop)$").unwrap();
+	static ref RE_LOCATION: Regex = Regex::new(r"^.{1,100}$").unwrap();
+}
+
+// How early before an event starts people can check in
+const CHECKIN_EARLY_MILLIS: i64 = 15 * 60 * 1000;
+
+// Define the Event struct (a learning session or hands-on workshop). Going counts the RSVPs that have a spot,
+// and is only changed together with them so two people can't take the last spot.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Event {
+	pub _id: ObjectId,
+	pub title: String,
+	pub description: String,
+	pub kind: String,
+	pub location: String,
+	pub starts_at: DateTime,
+	pub ends_at: DateTime,
+	pub capacity: u32,
+	pub cancelled: bool,
+	pub sequence: u32,
+	pub going: u32,
+	pub date_created: DateTime,
+	pub date_updated: DateTime,
+}
+
+// Define the EventRequest struct (a capacity of 0 means there is no limit)
+#[derive(Serialize, Deserialize, Validate)]
+pub struct EventRequest {
+	#[serde(default)]
+	pub id: String,
+	#[validate(regex(path = *RE_TITLE, message = "Title should be from 1 to 100 characters."))]
+	pub title: String,
+	#[validate(
+		regex(path = *RE_DESCRIPTION, message = "Description should be from 0 to 2000 characters.")
+	)]
+	pub description: String,
+	#[validate(regex(path = *RE_KIND, message = "Invalid kind. Must be session or workshop."))]
+	pub kind: String,
+	#[validate(regex(path = *RE_LOCATION, message = "Location should be from 1 to 100 characters."))]
+	pub location: String,
+	pub starts_at: String,
+	pub ends_at: String,
+	#[validate(range(max = 1000, message = "Capacity should be from 0 to 1000."))]
+	pub capacity: u32,
+	#[serde(default)]
+	pub cancelled: bool,
+	#[serde(default)]
+	pub going: u64,
+	#[serde(default)]
+	pub waitlisted: u64,
+	#[serde(default)]
+	pub rsvp: String,
+	#[serde(default)]
+	pub date_created: String,
+	#[serde(default)]
+	pub date_updated: String,
+	#[serde(default, skip_serializing)]
+	pub token: String,
+}
+
+// Parse an RFC 3339 date
+fn parse_date(date: &str) -> Result<DateTime, Box<dyn std::error::Error>> {
+	let chrono_datetime: SystemTime = chrono::DateTime
+		::parse_from_rfc3339(date)
+		.map_err(|err| format!("Error parsing date: {err}"))?
+		.with_timezone(&Utc)
+		.into();
+
+	Ok(DateTime::from(chrono_datetime))
+}
+
+// Implement the TryFrom trait for EventRequest
+impl TryFrom<EventRequest> for Event {
+	type Error = Box<dyn std::error::Error>;
+
+	fn try_from(item: EventRequest) -> Result<Self, Self::Error> {
+		let starts_at = parse_date(&item.starts_at)?;
+		let ends_at = parse_date(&item.ends_at)?;
+		if ends_at <= starts_at {
+			return Err("The event must end after it starts.".into());
+		}
+
+		Ok(Self {
+			_id: ObjectId::new(),
+			title: item.title,
+			description: item.description,
+			kind: item.kind,
+			location: item.location,
+			starts_at,
+			ends_at,
+			capacity: item.capacity,
+			cancelled: false,
+			sequence: 0,
+			going: 0,
+			date_created: DateTime::now(),
+			date_updated: DateTime::now(),
+		})
+	}
+}
+
+// Implement the From trait for Event
+impl From<Event> for EventRequest {
+	fn from(item: Event) -> Self {
+		Self {
+			id: item._id.to_string(),
+			title: item.title,
+			description: item.description,
+			kind: item.kind,
+			location: item.location,
+			starts_at: item.starts_at.to_string(),
+			ends_at: item.ends_at.to_string(),
+			capacity: item.capacity,
+			cancelled: item.cancelled,
+			going: 0,
+			waitlisted: 0,
+			rsvp: String::new(),
+			date_created: item.date_created.to_string(),
+			date_updated: item.date_updated.to_string(),
+			token: String::new(),
+		}
+	}
+}
+
+impl Event {
+	// Check if people can still RSVP
+	pub fn is_open(&self) -> bool {
+		!self.cancelled && DateTime::now() < self.ends_at
+	}
+
+	// Check if people can check in, which starts a little before the event so the doors can open early
+	pub fn is_checkin_open(&self) -> bool {
+		let now = DateTime::now().timestamp_millis();
+		!self.cancelled &&
+			now >= self.starts_at.timestamp_millis() - CHECKIN_EARLY_MILLIS &&
+			now <= self.ends_at.timestamp_millis()
+	}
+}
+
+// Define the Rsvp struct (an account going to an event or waiting for a spot)
+#[derive(Serialize, Deserialize)]
+pub struct Rsvp {
+	pub _id: ObjectId,
+	pub event_id: ObjectId,
+	pub account_id: ObjectId,
+	pub username: String,
+	pub status: String,
+	pub date_created: DateTime,
+}
+
+// Define the RsvpRequest struct
+#[derive(Serialize, Deserialize)]
+pub struct RsvpRequest {
+	pub username: String,
+	pub status: String,
+	pub date_created: String,
+}
+
+// Implement the From trait for Rsvp
+impl From<Rsvp> for RsvpRequest {
+	fn from(item: Rsvp) -> Self {
+		Self {
+			username: item.username,
+			status: item.status,
+			date_created: item.date_created.to_string(),
+		}
+	}
+}
@@ -5,6 +5,14 @@ use regex::Regex;
 use serde::{ Deserialize, Serialize };
 use std::time::SystemTime;
 
+use crate::models::application::{
+	default_status,
+	ApplicationEdit,
+	ApplicationEditRequest,
+	ReviewerNote,
+	ReviewerNoteRequest,
+};
+
 // Store the regex patterns for various fields
 lazy_static! {
 	static ref RE_FULL_NAME: Regex = Regex::new(
@@ -29,11 +37,27 @@ pub struct ExecutiveMember {
 	pub portfolio: String,
 	pub extra: String,
 	pub date_created: DateTime,
+	#[serde(default = "default_status")]
+	pub status: String,
+	#[serde(default)]
+	pub reviewer_notes: Vec<ReviewerNote>,
+	#[serde(default)]
+	pub account_id: Option<ObjectId>,
+	#[serde(default)]
+	pub edit_token: String,
+	#[serde(default)]
+	pub edit_history: Vec<ApplicationEdit>,
+	#[serde(default)]
+	pub interview_token: String,
+	#[serde(default)]
+	pub term_ended: bool,
 }
 
 // Define the ExecutiveMemberRequest struct
 #[derive(Serialize, Deserialize, Validate)]
 pub struct ExecutiveMemberRequest {
+	#[serde(default)]
+	pub id: String,
 	#[validate(regex(path = *RE_FULL_NAME, message = "Invalid name."))]
 	pub full_name: String,
 	#[validate(regex(path = *RE_EMAIL, message = "Email must be a valid PDSB email."))]
@@ -55,6 +79,14 @@ pub struct ExecutiveMemberRequest {
 	#[validate(regex(path = *RE_200, message = "Extra information should be from 0 to 200."))]
 	pub extra: String,
 	pub date_created: String,
+	#[serde(default = "default_status")]
+	pub status: String,
+	#[serde(default)]
+	pub reviewer_notes: Vec<ReviewerNoteRequest>,
+	#[serde(default)]
+	pub edit_history: Vec<ApplicationEditRequest>,
+	#[serde(default)]
+	pub term_ended: bool,
 }
 
 // Implement the TryFrom trait for ExecutiveMemberRequest
@@ -79,6 +111,13 @@ impl TryFrom<ExecutiveMemberRequest> for ExecutiveMember {
 			portfolio: item.portfolio,
 			extra: item.extra,
 			date_created: DateTime::from(chrono_datetime),
+			status: default_status(),
+			reviewer_notes: Vec::new(),
+			account_id: None,
+			edit_token: String::new(),
+			edit_history: Vec::new(),
+			interview_token: String::new(),
+			term_ended: false,
 		})
 	}
 }
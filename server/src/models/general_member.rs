@@ -4,6 +4,15 @@ use mongodb::bson::{ oid::ObjectId, DateTime };
 use regex::Regex;
 use serde::{ Deserialize, Serialize };
 use std::time::SystemTime;
+use validator::Validate;
+
+use crate::models::application::{
+	default_status,
+	ApplicationEdit,
+	ApplicationEditRequest,
+	ReviewerNote,
+	ReviewerNoteRequest,
+};
 
 // Store the regex patterns for various fields
 lazy_static! {
@@ -24,11 +33,23 @@ pub struct GeneralMember {
 	pub skills: u8,
 	pub extra: String,
 	pub date_created: DateTime,
+	#[serde(default = "default_status")]
+	pub status: String,
+	#[serde(default)]
+	pub reviewer_notes: Vec<ReviewerNote>,
+	#[serde(default)]
+	pub account_id: Option<ObjectId>,
+	#[serde(default)]
+	pub edit_token: String,
+	#[serde(default)]
+	pub edit_history: Vec<ApplicationEdit>,
 }
 
 // Define the GeneralMemberRequest struct
 #[derive(Serialize, Deserialize, Validate)]
 pub struct GeneralMemberRequest {
+	#[serde(default)]
+	pub id: String,
 	#[validate(regex(path = *RE_FULL_NAME, message = "Invalid name."))]
 	pub full_name: String,
 	#[validate(regex(path = *RE_EMAIL, message = "Email must be a valid PDSB email."))]
@@ -40,6 +61,12 @@ pub struct GeneralMemberRequest {
 	#[validate(regex(path = *RE_EXTRA, message = "Extra information should be from 0 to 350."))]
 	pub extra: String,
 	pub date_created: String,
+	#[serde(default = "default_status")]
+	pub status: String,
+	#[serde(default)]
+	pub reviewer_notes: Vec<ReviewerNoteRequest>,
+	#[serde(default)]
+	pub edit_history: Vec<ApplicationEditRequest>,
 }
 
 // Implement the TryFrom trait for GeneralMemberRequest
@@ -61,6 +88,139 @@ impl TryFrom<GeneralMemberRequest> for GeneralMember {
 			skills: item.skills,
 			extra: item.extra,
 			date_created: DateTime::from(chrono_datetime),
+			status: default_status(),
+			reviewer_notes: Vec::new(),
+			account_id: None,
+			edit_token: String::new(),
+			edit_history: Vec::new(),
 		})
 	}
 }
+
+// Define the GeneralMemberImportRow struct (one row of a CSV of general members)
+#[derive(Deserialize)]
+pub struct GeneralMemberImportRow {
+	pub full_name: String,
+	pub email: String,
+	pub grade: String,
+	#[serde(default)]
+	pub skills: String,
+	#[serde(default)]
+	pub extra: String,
+}
+
+impl GeneralMemberImportRow {
+	// Turn the row into a general member with the same rules as the form, collecting every problem with it and
+	// reporting unparsable numbers once
+	pub fn into_member(self) -> Result<GeneralMember, Vec<String>> {
+		let mut errors: Vec<String> = Vec::new();
+		let grade = self.grade.parse::<u8>().unwrap_or_else(|_| {
+			errors.push("Grade should be 9, 10, 11, or 12.".to_string());
+			9
+		});
+		let skills = if self.skills.is_empty() {
+			0
+		} else {
+			self.skills.parse::<u8>().unwrap_or_else(|_| {
+				errors.push("Skills should be from 0 to 100.".to_string());
+				0
+			})
+		};
+		let request = GeneralMemberRequest {
+			id: String::new(),
+			full_name: self.full_name,
+			email: self.email,
+			grade,
+			skills,
+			extra: self.extra,
+			date_created: Utc::now().to_rfc3339(),
+			status: default_status(),
+			reviewer_notes: Vec::new(),
+			edit_history: Vec::new(),
+		};
+		if let Err(err) = request.validate() {
+			for (field, field_errors) in err.field_errors() {
+				for error in field_errors {
+					errors.push(
+						error.message
+							.as_ref()
+							.map(|message| message.to_string())
+							.unwrap_or_else(|| format!("Invalid {}.", field))
+					);
+				}
+			}
+		}
+		if !errors.is_empty() {
+			return Err(errors);
+		}
+		GeneralMember::try_from(request).map_err(|err| vec![err.to_string()])
+	}
+}
+
+// Define the ImportRowResult struct (what happened to one row of an import)
+#[derive(Serialize, Deserialize)]
+pub struct ImportRowResult {
+	pub row: u64,
+	pub full_name: String,
+	pub email: String,
+	pub result: String,
+	pub errors: Vec<String>,
+}
+
+// Define the ImportReport struct
+#[derive(Serialize, Deserialize)]
+pub struct ImportReport {
+	pub dry_run: bool,
+	pub total: u32,
+	pub imported: u32,
+	pub duplicates: u32,
+	pub invalid: u32,
+	pub rows: Vec<ImportRowResult>,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn row(full_name: &str, email: &str, grade: &str, skills: &str) -> GeneralMemberImportRow {
+		GeneralMemberImportRow {
+			full_name: full_name.to_string(),
+			email: email.to_string(),
+			grade: grade.to_string(),
+			skills: skills.to_string(),
+			extra: String::new(),
+		}
+	}
+
+	#[test]
+	fn imports_a_valid_row() {
+		let Ok(member) = row("Ada Lovelace", "123456@pdsb.net", "11", "").into_member() else {
+			panic!("The row should be valid.");
+		};
+		assert_eq!(member.full_name, "Ada Lovelace");
+		assert_eq!(member.grade, 11);
+		assert_eq!(member.skills, 0);
+	}
+
+	#[test]
+	fn reports_every_problem_with_a_row() {
+		let Err(errors) = row("A", "someone@gmail.com", "13", "").into_member() else {
+			panic!("The row should be invalid.");
+		};
+		assert_eq!(errors.len(), 3);
+		assert!(errors.contains(&"Invalid name.".to_string()));
+		assert!(errors.contains(&"Email must be a valid PDSB email.".to_string()));
+		assert!(errors.contains(&"Grade should be 9, 10, 11, or 12.".to_string()));
+	}
+
+	#[test]
+	fn reports_unparsable_numbers_once() {
+		let Err(errors) = row("Ada Lovelace", "123456@pdsb.net", "eleven", "lots").into_member() else {
+			panic!("The row should be invalid.");
+		};
+		assert_eq!(
+			errors,
+			vec!["Grade should be 9, 10, 11, or 12.".to_string(), "Skills should be from 0 to 100.".to_string()]
+		);
+	}
+}
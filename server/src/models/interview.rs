@@ -0,0 +1,169 @@
+use chrono::Utc;
+use lazy_static::lazy_static;
+use mongodb::bson::{ oid::ObjectId, DateTime };
+use regex::Regex;
+use serde::{ Deserialize, Serialize };
+use std::time::SystemTime;
+use validator::ValidationError;
+
+// Store the regex patterns for various fields
+lazy_static! {
+	static ref RE_EXEC_TYPE: Regex = Regex::new(r"^(development|marketing|events)?$").unwrap();
+	static ref RE_LOCATION: Regex = Regex::new(r"^.{1,100}$").unwrap();
+	static ref RE_INTERVIEWER: Regex = Regex::new(r"^[A-Za-z0-9 ._'-]{2,40}$").unwrap();
+}
+
+// Create a function to validate the interviewers
+fn validate_interviewers(interviewers: &[String]) -> Result<(), ValidationError> {
+	if interviewers.len() > 5 {
+		return Err(ValidationError::new("A slot should have at most 5 interviewers."));
+	}
+	if interviewers.iter().any(|interviewer| !RE_INTERVIEWER.is_match(interviewer)) {
+		return Err(ValidationError::new("Invalid interviewer name."));
+	}
+	Ok(())
+}
+
+// Define the InterviewSlot struct (a time an executive candidate can book an interview in)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct InterviewSlot {
+	pub _id: ObjectId,
+	pub exec_type: String,
+	pub starts_at: DateTime,
+	pub ends_at: DateTime,
+	pub location: String,
+	pub interviewers: Vec<String>,
+	pub application_id: Option<ObjectId>,
+	pub reminder_sent: bool,
+	pub date_created: DateTime,
+}
+
+// Define the InterviewSlotRequest struct (an empty exec type is open to every exec type)
+#[derive(Serialize, Deserialize, Validate)]
+pub struct InterviewSlotRequest {
+	#[serde(default)]
+	pub id: String,
+	#[validate(
+		regex(
+			path = *RE_EXEC_TYPE,
+			message = "Invalid exec type. Must be development, marketing, events, or empty."
+		)
+	)]
+	pub exec_type: String,
+	pub starts_at: String,
+	pub ends_at: String,
+	#[validate(regex(path = *RE_LOCATION, message = "Location should be from 1 to 100 characters."))]
+	pub location: String,
+	#[validate(custom(function = "validate_interviewers"))]
+	pub interviewers: Vec<String>,
+	#[serde(default)]
+	pub application_id: String,
+	#[serde(default, skip_serializing)]
+	pub token: String,
+}
+
+// Parse an RFC 3339 date
+fn parse_date(date: &str) -> Result<DateTime, Box<dyn std::error::Error>> {
+	let chrono_datetime: SystemTime = chrono::DateTime
+		::parse_from_rfc3339(date)
+		.map_err(|err| format!("Error parsing date: {err}"))?
+		.with_timezone(&Utc)
+		.into();
+
+	Ok(DateTime::from(chrono_datetime))
+}
+
+// Implement the TryFrom trait for InterviewSlotRequest
+impl TryFrom<InterviewSlotRequest> for InterviewSlot {
+	type Error = Box<dyn std::error::Error>;
+
+	fn try_from(item: InterviewSlotRequest) -> Result<Self, Self::Error> {
+		let starts_at = parse_date(&item.starts_at)?;
+		let ends_at = parse_date(&item.ends_at)?;
+		if ends_at <= starts_at {
+			return Err("The slot must end after it starts.".into());
+		}
+		if ends_at.timestamp_millis() - starts_at.timestamp_millis() > 3 * 60 * 60 * 1000 {
+			return Err("A slot should be at most 3 hours long.".into());
+		}
+		if starts_at <= DateTime::now() {
+			return Err("The slot must start in the future.".into());
+		}
+
+		Ok(Self {
+			_id: ObjectId::new(),
+			exec_type: item.exec_type,
+			starts_at,
+			ends_at,
+			location: item.location,
+			interviewers: item.interviewers,
+			application_id: None,
+			reminder_sent: false,
+			date_created: DateTime::now(),
+		})
+	}
+}
+
+// Implement the From trait for InterviewSlot
+impl From<InterviewSlot> for InterviewSlotRequest {
+	fn from(item: InterviewSlot) -> Self {
+		Self {
+			id: item._id.to_string(),
+			exec_type: item.exec_type,
+			starts_at: item.starts_at.to_string(),
+			ends_at: item.ends_at.to_string(),
+			location: item.location,
+			interviewers: item.interviewers,
+			application_id: item.application_id
+				.map(|id| id.to_string())
+				.unwrap_or_default(),
+			token: String::new(),
+		}
+	}
+}
+
+impl InterviewSlot {
+	// Check if two slots happen at the same time
+	pub fn overlaps(&self, other: &InterviewSlot) -> bool {
+		self.starts_at < other.ends_at && other.starts_at < self.ends_at
+	}
+
+	// Get why two slots can't happen at the same time, if they can't
+	pub fn conflict_with(&self, other: &InterviewSlot) -> Option<String> {
+		if self._id == other._id || !self.overlaps(other) {
+			return None;
+		}
+		if self.location.eq_ignore_ascii_case(&other.location) {
+			return Some(format!("{} is already used at that time.", self.location));
+		}
+		self.interviewers
+			.iter()
+			.find(|interviewer| other.interviewers.contains(interviewer))
+			.map(|interviewer| format!("{} is already interviewing at that time.", interviewer))
+	}
+}
+
+// Define the ScheduledInterview struct (a slot in the admins' schedule, with its candidate)
+#[derive(Serialize, Deserialize)]
+pub struct ScheduledInterview {
+	pub slot: InterviewSlotRequest,
+	pub full_name: String,
+	pub email: String,
+	pub conflicts: Vec<String>,
+}
+
+// Define the AvailableSlot struct (a slot as a candidate sees it)
+#[derive(Serialize, Deserialize)]
+pub struct AvailableSlot {
+	pub id: String,
+	pub starts_at: String,
+	pub ends_at: String,
+	pub location: String,
+	pub booked: bool,
+}
+
+// Define the BookingRequest struct
+#[derive(Serialize, Deserialize)]
+pub struct BookingRequest {
+	pub slot_id: String,
+}
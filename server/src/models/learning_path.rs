@@ -0,0 +1,224 @@
+use lazy_static::lazy_static;
+use mongodb::bson::{ oid::ObjectId, DateTime };
+use regex::Regex;
+use serde::{ Deserialize, Serialize };
+use validator::ValidationError;
+
+// Store the regex patterns for various fields
+lazy_static! {
+	static ref RE_TITLE: Regex = Regex::new(r"^.{1,100}$").unwrap();
+	static ref RE_DESCRIPTION: Regex = Regex::new(r"^(?s).{1,2000}$").unwrap();
+	static ref RE_DIFFICULTY: Regex = Regex::new(r"^(beginner|intermediate|advanced)$").unwrap();
+	static ref RE_MODULE_DESCRIPTION: Regex = Regex::new(r"^(?s).{0,1000}$").unwrap();
+}
+
+// Create a function to validate the modules
+fn validate_modules(modules: &[PathModuleRequest]) -> Result<(), ValidationError> {
+	if
+		modules.is_empty() ||
+		modules.len() > 20 ||
+		modules.iter().any(|module| {
+			!RE_TITLE.is_match(&module.title) ||
+				!RE_MODULE_DESCRIPTION.is_match(&module.description) ||
+				module.resource_ids.is_empty() ||
+				module.resource_ids.len() > 30 ||
+				module.resource_ids.iter().any(|id| ObjectId::parse_str(id).is_err())
+		})
+	{
+		return Err(
+			ValidationError::new(
+				"Modules should be from 1 to 20, each with a title of 1 to 100 characters, a description of at most 1000 characters and 1 to 30 resources."
+			)
+		);
+	}
+	Ok(())
+}
+
+// Define the PathModule struct (a group of resources to go through in order)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PathModule {
+	pub title: String,
+	pub description: String,
+	pub resource_ids: Vec<ObjectId>,
+}
+
+// Define the LearningPath struct
+#[derive(Serialize, Deserialize)]
+pub struct LearningPath {
+	pub _id: ObjectId,
+	pub title: String,
+	pub description: String,
+	pub difficulty: String,
+	pub modules: Vec<PathModule>,
+	pub date_created: DateTime,
+	pub date_updated: DateTime,
+}
+
+impl LearningPath {
+	// Get the resources of every module, in order
+	pub fn resource_ids(&self) -> Vec<ObjectId> {
+		self.modules
+			.iter()
+			.flat_map(|module| module.resource_ids.iter().copied())
+			.collect()
+	}
+}
+
+// Define the PathModuleRequest struct
+#[derive(Serialize, Deserialize)]
+pub struct PathModuleRequest {
+	pub title: String,
+	#[serde(default)]
+	pub description: String,
+	pub resource_ids: Vec<String>,
+}
+
+// Define the LearningPathRequest struct
+#[derive(Serialize, Deserialize, Validate)]
+pub struct LearningPathRequest {
+	#[serde(default)]
+	pub id: String,
+	#[validate(regex(path = *RE_TITLE, message = "Title should be from 1 to 100 characters."))]
+	pub title: String,
+	#[validate(
+		regex(path = *RE_DESCRIPTION, message = "Description should be from 1 to 2000 characters.")
+	)]
+	pub description: String,
+	#[validate(
+		regex(
+			path = *RE_DIFFICULTY,
+			message = "Invalid difficulty. Must be beginner, intermediate or advanced."
+		)
+	)]
+	pub difficulty: String,
+	#[validate(custom(function = "validate_modules"))]
+	pub modules: Vec<PathModuleRequest>,
+	#[serde(default)]
+	pub date_created: String,
+	#[serde(default)]
+	pub date_updated: String,
+	#[serde(default, skip_serializing)]
+	pub token: String,
+}
+
+// Implement the TryFrom trait for LearningPathRequest
+impl TryFrom<LearningPathRequest> for LearningPath {
+	type Error = Box<dyn std::error::Error>;
+
+	fn try_from(item: LearningPathRequest) -> Result<Self, Self::Error> {
+		let mut modules: Vec<PathModule> = Vec::new();
+		for module in item.modules {
+			let mut resource_ids: Vec<ObjectId> = Vec::new();
+			for id in module.resource_ids {
+				let id = ObjectId::parse_str(&id).map_err(|_| format!("Invalid resource ID: {id}"))?;
+				if !resource_ids.contains(&id) {
+					resource_ids.push(id);
+				}
+			}
+			modules.push(PathModule {
+				title: module.title.trim().to_string(),
+				description: module.description.trim().to_string(),
+				resource_ids,
+			});
+		}
+
+		Ok(Self {
+			_id: ObjectId::new(),
+			title: item.title.trim().to_string(),
+			description: item.description.trim().to_string(),
+			difficulty: item.difficulty,
+			modules,
+			date_created: DateTime::now(),
+			date_updated: DateTime::now(),
+		})
+	}
+}
+
+// Implement the From trait for LearningPath
+impl From<LearningPath> for LearningPathRequest {
+	fn from(item: LearningPath) -> Self {
+		Self {
+			id: item._id.to_string(),
+			title: item.title,
+			description: item.description,
+			difficulty: item.difficulty,
+			modules: item.modules
+				.into_iter()
+				.map(|module| PathModuleRequest {
+					title: module.title,
+					description: module.description,
+					resource_ids: module.resource_ids
+						.iter()
+						.map(|id| id.to_string())
+						.collect(),
+				})
+				.collect(),
+			date_created: item.date_created.to_string(),
+			date_updated: item.date_updated.to_string(),
+			token: String::new(),
+		}
+	}
+}
+
+// Define the LearningPathArgs struct
+#[derive(Serialize, Deserialize)]
+pub struct LearningPathArgs {
+	#[serde(default)]
+	pub difficulty: String,
+}
+
+// Define the PathProgress struct (a resource of a path that an account has completed)
+#[derive(Serialize, Deserialize)]
+pub struct PathProgress {
+	pub _id: ObjectId,
+	pub account_id: ObjectId,
+	pub path_id: ObjectId,
+	pub resource_id: ObjectId,
+	pub date_completed: DateTime,
+}
+
+// Define the PathStep struct (a resource of a module, and whether the account has completed it)
+#[derive(Serialize, Deserialize)]
+pub struct PathStep {
+	pub id: String,
+	pub title: String,
+	pub link: String,
+	pub description: String,
+	pub tags: Vec<String>,
+	pub completed: bool,
+}
+
+// Define the PathModuleDetails struct
+#[derive(Serialize, Deserialize)]
+pub struct PathModuleDetails {
+	pub title: String,
+	pub description: String,
+	pub completed: bool,
+	pub steps: Vec<PathStep>,
+}
+
+// Define the LearningPathDetails struct (a path with its resources filled in)
+#[derive(Serialize, Deserialize)]
+pub struct LearningPathDetails {
+	pub id: String,
+	pub title: String,
+	pub description: String,
+	pub difficulty: String,
+	pub completed: u64,
+	pub total: u64,
+	pub modules: Vec<PathModuleDetails>,
+}
+
+// Define the PathProgressSummary struct (how far an account is through a path)
+#[derive(Serialize, Deserialize)]
+pub struct PathProgressSummary {
+	pub id: String,
+	pub title: String,
+	pub difficulty: String,
+	pub completed: u64,
+	pub total: u64,
+	pub percent: u32,
+	pub completed_modules: u64,
+	pub total_modules: u64,
+	pub last_completed: String,
+}
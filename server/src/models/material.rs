@@ -0,0 +1,110 @@
+use chrono::Utc;
+use lazy_static::lazy_static;
+use mongodb::bson::{ oid::ObjectId, DateTime };
+use regex::Regex;
+use serde::{ Deserialize, Serialize };
+use std::time::SystemTime;
+
+// Store the regex patterns for various fields
+lazy_static! {
+	static ref RE_TITLE: Regex = Regex::new(r"^.{1,100}$").unwrap();
+	static ref RE_SESSION: Regex = Regex::new(r"^.{1,100}$").unwrap();
+	static ref RE_PRESENTER: Regex = Regex::new(r"^.{1,100}$").unwrap();
+}
+
+// Define the Material struct (a file from a learning session, kept in the upload storage)
+#[derive(Serialize, Deserialize)]
+pub struct Material {
+	pub _id: ObjectId,
+	pub title: String,
+	pub session: String,
+	pub session_date: DateTime,
+	pub presenter: String,
+	pub filename: String,
+	pub stored_name: String,
+	pub content_type: String,
+	pub size: u64,
+	pub members_only: bool,
+	pub date_created: DateTime,
+}
+
+// Define the MaterialRequest struct (the file itself is uploaded separately)
+#[derive(Serialize, Deserialize, Validate)]
+pub struct MaterialRequest {
+	#[serde(default)]
+	pub id: String,
+	#[validate(regex(path = *RE_TITLE, message = "Title should be from 1 to 100 characters."))]
+	pub title: String,
+	#[validate(regex(path = *RE_SESSION, message = "Session should be from 1 to 100 characters."))]
+	pub session: String,
+	pub session_date: String,
+	#[validate(
+		regex(path = *RE_PRESENTER, message = "Presenter should be from 1 to 100 characters.")
+	)]
+	pub presenter: String,
+	#[serde(default)]
+	pub filename: String,
+	#[serde(default)]
+	pub content_type: String,
+	#[serde(default)]
+	pub size: u64,
+	#[serde(default)]
+	pub members_only: bool,
+	#[serde(default)]
+	pub date_created: String,
+	#[serde(default, skip_serializing)]
+	pub token: String,
+}
+
+// Implement the TryFrom trait for MaterialRequest (the caller fills in the file's details)
+impl TryFrom<MaterialRequest> for Material {
+	type Error = Box<dyn std::error::Error>;
+
+	fn try_from(item: MaterialRequest) -> Result<Self, Self::Error> {
+		let chrono_datetime: SystemTime = chrono::DateTime
+			::parse_from_rfc3339(&item.session_date)
+			.map_err(|err| format!("Error parsing date: {err}"))?
+			.with_timezone(&Utc)
+			.into();
+
+		Ok(Self {
+			_id: ObjectId::new(),
+			title: item.title,
+			session: item.session,
+			session_date: DateTime::from(chrono_datetime),
+			presenter: item.presenter,
+			filename: String::new(),
+			stored_name: String::new(),
+			content_type: String::new(),
+			size: 0,
+			members_only: item.members_only,
+			date_created: DateTime::now(),
+		})
+	}
+}
+
+// Implement the From trait for Material
+impl From<Material> for MaterialRequest {
+	fn from(item: Material) -> Self {
+		Self {
+			id: item._id.to_string(),
+			title: item.title,
+			session: item.session,
+			session_date: item.session_date.to_string(),
+			presenter: item.presenter,
+			filename: item.filename,
+			content_type: item.content_type,
+			size: item.size,
+			members_only: item.members_only,
+			date_created: item.date_created.to_string(),
+			token: String::new(),
+		}
+	}
+}
+
+// Define the MaterialArgs struct
+#[derive(Serialize, Deserialize)]
+pub struct MaterialArgs {
+	#[serde(default)]
+	pub session: String,
+}
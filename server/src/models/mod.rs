@@ -3,8 +3,20 @@
 pub mod account;
 pub mod admin;
 pub mod announcement;
+pub mod application;
+pub mod application_window;
+pub mod attendance;
+pub mod broadcast;
 pub mod comment;
+pub mod ctf;
+pub mod evaluation;
+pub mod event;
 pub mod executive_member;
 pub mod forum_post;
 pub mod general_member;
+pub mod interview;
+pub mod learning_path;
+pub mod material;
 pub mod resource;
+pub mod rubric;
+pub mod school_year;
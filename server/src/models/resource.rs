@@ -1,5 +1,24 @@
+use lazy_static::lazy_static;
+use mongodb::bson::{ oid::ObjectId, DateTime };
+use regex::Regex;
 use serde::{ Deserialize, Serialize };
-use mongodb::bson::oid::ObjectId;
+use validator::ValidationError;
+
+// Store the regex patterns for various fields
+lazy_static! {
+	static ref RE_TITLE: Regex = Regex::new(r"^.{1,100}$").unwrap();
+	static ref RE_LINK: Regex = Regex::new(r"^https?://[^\s/?#]+\.[^\s/?#]+\S{0,500}$").unwrap();
+	static ref RE_DESCRIPTION: Regex = Regex::new(r"^(?s).{1,1000}$").unwrap();
+	static ref RE_TAG: Regex = Regex::new(r"^[\w .+#-]{1,30}$").unwrap();
+}
+
+// Create a function to validate the tags (whether they are in the tag list is checked against the database)
+fn validate_tags(tags: &[String]) -> Result<(), ValidationError> {
+	if tags.len() > 10 || tags.iter().any(|tag| !RE_TAG.is_match(tag)) {
+		return Err(ValidationError::new("Tags should be at most 10, each from 1 to 30 characters."));
+	}
+	Ok(())
+}
 
 #[derive(Serialize, Deserialize)]
 pub struct Resource {
@@ -8,14 +27,44 @@ pub struct Resource {
 	pub link: String,
 	pub tags: Vec<String>,
 	pub description: String,
+	#[serde(default)]
+	pub link_health: LinkHealth,
+	#[serde(default)]
+	pub average_rating: f64,
+	#[serde(default)]
+	pub rating_count: u64,
+	#[serde(default)]
+	pub rating_total: u64,
+	#[serde(default)]
+	pub clicks: u64,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Validate)]
 pub struct ResourceRequest {
+	#[serde(default)]
+	pub id: String,
+	#[validate(regex(path = *RE_TITLE, message = "Title should be from 1 to 100 characters."))]
 	pub title: String,
+	#[validate(regex(path = *RE_LINK, message = "Link should be an http or https URL."))]
 	pub link: String,
+	#[validate(custom(function = "validate_tags"))]
 	pub tags: Vec<String>,
+	#[validate(
+		regex(path = *RE_DESCRIPTION, message = "Description should be from 1 to 1000 characters.")
+	)]
 	pub description: String,
+	#[serde(default)]
+	pub average_rating: f64,
+	#[serde(default)]
+	pub rating_count: u64,
+	#[serde(default)]
+	pub clicks: u64,
+	#[serde(default)]
+	pub my_rating: u32,
+	#[serde(default)]
+	pub bookmarked: bool,
+	#[serde(default, skip_serializing)]
+	pub token: String,
 }
 
 // Implement the TryFrom trait for ResourceRequest
@@ -23,12 +72,180 @@ impl TryFrom<ResourceRequest> for Resource {
 	type Error = Box<dyn std::error::Error>;
 
 	fn try_from(item: ResourceRequest) -> Result<Self, Self::Error> {
+		let mut tags: Vec<String> = Vec::new();
+		for tag in item.tags {
+			let tag = tag.trim().to_string();
+			if !tags.contains(&tag) {
+				tags.push(tag);
+			}
+		}
+
 		Ok(Self {
 			_id: ObjectId::new(),
+			title: item.title.trim().to_string(),
+			link: item.link.trim().to_string(),
+			tags,
+			description: item.description.trim().to_string(),
+			link_health: LinkHealth::default(),
+			average_rating: 0.0,
+			rating_count: 0,
+			rating_total: 0,
+			clicks: 0,
+		})
+	}
+}
+
+// Implement the From trait for Resource
+impl From<Resource> for ResourceRequest {
+	fn from(item: Resource) -> Self {
+		Self {
+			id: item._id.to_string(),
 			title: item.title,
 			link: item.link,
 			tags: item.tags,
 			description: item.description,
-		})
+			average_rating: item.average_rating,
+			rating_count: item.rating_count,
+			clicks: item.clicks,
+			my_rating: 0,
+			bookmarked: false,
+			token: String::new(),
+		}
+	}
+}
+
+// Define the ResourceTag struct (resources can only be given tags from this list)
+#[derive(Serialize, Deserialize)]
+pub struct ResourceTag {
+	pub _id: ObjectId,
+	pub name: String,
+	pub date_created: DateTime,
+}
+
+// Define the ResourceTagRequest struct
+#[derive(Serialize, Deserialize, Validate)]
+pub struct ResourceTagRequest {
+	#[validate(regex(path = *RE_TAG, message = "Tag should be from 1 to 30 characters."))]
+	pub name: String,
+	#[serde(default, skip_serializing)]
+	pub token: String,
+}
+
+// Define the TagCount struct (a tag and how many resources have it)
+#[derive(Serialize, Deserialize)]
+pub struct TagCount {
+	pub name: String,
+	pub count: u64,
+}
+
+// Define the ResourceRating struct (an account's rating of a resource, from 1 to 5)
+#[derive(Serialize, Deserialize)]
+pub struct ResourceRating {
+	pub _id: ObjectId,
+	pub resource_id: ObjectId,
+	pub account_id: ObjectId,
+	pub rating: u32,
+	pub date_updated: DateTime,
+}
+
+// Define the RatingRequest struct
+#[derive(Serialize, Deserialize, Validate)]
+pub struct RatingRequest {
+	#[validate(range(min = 1, max = 5, message = "Rating should be from 1 to 5."))]
+	pub rating: u32,
+}
+
+// Define the ResourceClick struct (a visitor, an account or an IP address, following a resource's link, which is
+// counted once per window)
+#[derive(Serialize, Deserialize)]
+pub struct ResourceClick {
+	pub _id: ObjectId,
+	pub resource_id: ObjectId,
+	pub visitor: String,
+	pub window: i64,
+	pub date_created: DateTime,
+}
+
+// Define the ResourceBookmark struct
+#[derive(Serialize, Deserialize)]
+pub struct ResourceBookmark {
+	pub _id: ObjectId,
+	pub resource_id: ObjectId,
+	pub account_id: ObjectId,
+	pub date_created: DateTime,
+}
+
+// Define the LinkCheck struct (the result of checking a resource's link once)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LinkCheck {
+	pub date: DateTime,
+	pub ok: bool,
+	pub status: Option<u16>,
+	pub error: String,
+}
+
+// Define the LinkHealth struct (a link is dead after failing enough checks in a row)
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct LinkHealth {
+	pub last_checked: Option<DateTime>,
+	pub consecutive_failures: u32,
+	pub dead: bool,
+	pub history: Vec<LinkCheck>,
+}
+
+// Define the LinkCheckRequest struct
+#[derive(Serialize, Deserialize)]
+pub struct LinkCheckRequest {
+	pub date: String,
+	pub ok: bool,
+	pub status: Option<u16>,
+	pub error: String,
+}
+
+// Implement the From trait for LinkCheck
+impl From<LinkCheck> for LinkCheckRequest {
+	fn from(item: LinkCheck) -> Self {
+		Self {
+			date: item.date.to_string(),
+			ok: item.ok,
+			status: item.status,
+			error: item.error,
+		}
+	}
+}
+
+// Define the LinkReportEntry struct (a resource in the broken link report)
+#[derive(Serialize, Deserialize)]
+pub struct LinkReportEntry {
+	pub id: String,
+	pub title: String,
+	pub link: String,
+	pub dead: bool,
+	pub consecutive_failures: u32,
+	pub last_checked: String,
+	pub history: Vec<LinkCheckRequest>,
+}
+
+// Implement the From trait for Resource
+impl From<Resource> for LinkReportEntry {
+	fn from(item: Resource) -> Self {
+		Self {
+			id: item._id.to_string(),
+			title: item.title,
+			link: item.link,
+			dead: item.link_health.dead,
+			consecutive_failures: item.link_health.consecutive_failures,
+			last_checked: item.link_health.last_checked
+				.map(|date| date.to_string())
+				.unwrap_or_default(),
+			history: item.link_health.history.into_iter().rev().map(LinkCheckRequest::from).collect(),
+		}
 	}
 }
+
+// Define the LinkReportArgs struct
+#[derive(Serialize, Deserialize)]
+pub struct LinkReportArgs {
+	#[serde(default)]
+	pub dead: bool,
+}
@@ -0,0 +1,120 @@
+use lazy_static::lazy_static;
+use mongodb::bson::{ oid::ObjectId, DateTime };
+use regex::Regex;
+use serde::{ Deserialize, Serialize };
+use validator::{ Validate, ValidationError };
+
+use crate::models::evaluation::CriterionScore;
+
+// Store the regex patterns for various fields
+lazy_static! {
+	static ref RE_EXEC_TYPE: Regex = Regex::new(r"^(development|marketing|events)$").unwrap();
+	static ref RE_NAME: Regex = Regex::new(r"^[A-Za-z0-9 _'-]{1,40}$").unwrap();
+	static ref RE_DESCRIPTION: Regex = Regex::new(r"^.{0,200}$").unwrap();
+}
+
+// Define the RubricCriterion struct (something each application is scored on)
+#[derive(Clone, Serialize, Deserialize, Validate)]
+pub struct RubricCriterion {
+	#[validate(regex(path = *RE_NAME, message = "Invalid criterion name."))]
+	pub name: String,
+	#[validate(
+		regex(path = *RE_DESCRIPTION, message = "Description should be from 0 to 200 characters.")
+	)]
+	pub description: String,
+	#[validate(range(min = 1, max = 100, message = "Max score should be from 1 to 100."))]
+	pub max_score: u8,
+	#[validate(range(min = 1, max = 10, message = "Weight should be from 1 to 10."))]
+	pub weight: u8,
+}
+
+// Define the Rubric struct
+#[derive(Serialize, Deserialize)]
+pub struct Rubric {
+	pub _id: ObjectId,
+	pub exec_type: String,
+	pub criteria: Vec<RubricCriterion>,
+	pub date_updated: DateTime,
+}
+
+// Create a function to validate the criteria as a whole
+fn validate_criteria(criteria: &[RubricCriterion]) -> Result<(), ValidationError> {
+	if criteria.is_empty() || criteria.len() > 10 {
+		return Err(ValidationError::new("A rubric should have from 1 to 10 criteria."));
+	}
+	let mut names: Vec<&String> = criteria
+		.iter()
+		.map(|criterion| &criterion.name)
+		.collect();
+	names.sort();
+	names.dedup();
+	if names.len() != criteria.len() {
+		return Err(ValidationError::new("Criteria names should be unique."));
+	}
+	Ok(())
+}
+
+// Define the RubricRequest struct
+#[derive(Serialize, Deserialize, Validate)]
+pub struct RubricRequest {
+	#[validate(
+		regex(
+			path = *RE_EXEC_TYPE,
+			message = "Invalid exec type. Must be development, marketing, or events."
+		)
+	)]
+	pub exec_type: String,
+	#[validate(custom(function = "validate_criteria"), nested)]
+	pub criteria: Vec<RubricCriterion>,
+	#[serde(default)]
+	pub date_updated: String,
+	#[serde(default, skip_serializing)]
+	pub token: String,
+}
+
+// Implement the From trait for RubricRequest
+impl From<RubricRequest> for Rubric {
+	fn from(item: RubricRequest) -> Self {
+		Self {
+			_id: ObjectId::new(),
+			exec_type: item.exec_type,
+			criteria: item.criteria,
+			date_updated: DateTime::now(),
+		}
+	}
+}
+
+// Implement the From trait for Rubric
+impl From<Rubric> for RubricRequest {
+	fn from(item: Rubric) -> Self {
+		Self {
+			exec_type: item.exec_type,
+			criteria: item.criteria,
+			date_updated: item.date_updated.to_string(),
+			token: String::new(),
+		}
+	}
+}
+
+impl Rubric {
+	// Check that the scores cover every criterion exactly once and stay within the max scores
+	pub fn check_scores(&self, scores: &[CriterionScore]) -> Result<(), String> {
+		if scores.len() != self.criteria.len() {
+			return Err("Every criterion of the rubric should be scored once.".to_string());
+		}
+		for criterion in &self.criteria {
+			match scores.iter().find(|score| score.criterion == criterion.name) {
+				Some(score) if score.score <= criterion.max_score => (),
+				Some(_) => {
+					return Err(
+						format!("{} should be scored from 0 to {}.", criterion.name, criterion.max_score)
+					);
+				}
+				None => {
+					return Err(format!("{} was not scored.", criterion.name));
+				}
+			}
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,176 @@
+use chrono::{ Datelike, TimeZone, Utc };
+use chrono_tz::America::Toronto;
+use lazy_static::lazy_static;
+use mongodb::bson::{ oid::ObjectId, DateTime };
+use regex::Regex;
+use serde::{ Deserialize, Serialize };
+use validator::ValidationError;
+
+// Store the regex pattern for the school year
+lazy_static! {
+	static ref RE_SCHOOL_YEAR: Regex = Regex::new(r"^([0-9]{4})-([0-9]{4})$").unwrap();
+}
+
+// Create a function to validate a school year such as 2025-2026
+fn validate_school_year(school_year: &str) -> Result<(), ValidationError> {
+	let valid = RE_SCHOOL_YEAR.captures(school_year).is_some_and(|captures| {
+		let start = captures[1].parse::<u32>().unwrap_or_default();
+		let end = captures[2].parse::<u32>().unwrap_or_default();
+		end == start + 1
+	});
+	if !valid {
+		return Err(ValidationError::new("School year should look like 2025-2026."));
+	}
+	Ok(())
+}
+
+// Define the SchoolYear struct (a school year that has been rolled over, with what happened). The record is
+// saved before the rollover starts so only one rollover of a year can run, and completed once it is done.
+#[derive(Serialize, Deserialize)]
+pub struct SchoolYear {
+	pub _id: ObjectId,
+	pub school_year: String,
+	pub general_members: u32,
+	pub executive_members: u32,
+	pub promoted: u32,
+	pub graduated: u32,
+	pub terms_ended: u32,
+	pub completed: bool,
+	pub started_at: Option<DateTime>,
+	pub date_created: DateTime,
+}
+
+// Define the SchoolYearRequest struct
+#[derive(Serialize, Deserialize, Validate)]
+pub struct SchoolYearRequest {
+	#[validate(custom(function = "validate_school_year"))]
+	pub school_year: String,
+	#[serde(default)]
+	pub general_members: u32,
+	#[serde(default)]
+	pub executive_members: u32,
+	#[serde(default)]
+	pub promoted: u32,
+	#[serde(default)]
+	pub graduated: u32,
+	#[serde(default)]
+	pub terms_ended: u32,
+	#[serde(default)]
+	pub date_created: String,
+	#[serde(default, skip_serializing)]
+	pub token: String,
+}
+
+// Implement the From trait for SchoolYear
+impl From<SchoolYear> for SchoolYearRequest {
+	fn from(item: SchoolYear) -> Self {
+		Self {
+			school_year: item.school_year,
+			general_members: item.general_members,
+			executive_members: item.executive_members,
+			promoted: item.promoted,
+			graduated: item.graduated,
+			terms_ended: item.terms_ended,
+			date_created: item.date_created.to_string(),
+			token: String::new(),
+		}
+	}
+}
+
+// Define the RosterEntry struct (a member as they were at the end of a school year)
+#[derive(Serialize, Deserialize)]
+pub struct RosterEntry {
+	pub _id: ObjectId,
+	pub school_year: String,
+	pub roster: String,
+	pub member_id: ObjectId,
+	pub full_name: String,
+	pub email: String,
+	pub grade: u8,
+	pub exec_type: String,
+	pub status: String,
+}
+
+// Define the RosterEntryRequest struct
+#[derive(Serialize, Deserialize)]
+pub struct RosterEntryRequest {
+	pub school_year: String,
+	pub roster: String,
+	pub member_id: String,
+	pub full_name: String,
+	pub email: String,
+	pub grade: u8,
+	pub exec_type: String,
+	pub status: String,
+}
+
+// Implement the From trait for RosterEntry
+impl From<RosterEntry> for RosterEntryRequest {
+	fn from(item: RosterEntry) -> Self {
+		Self {
+			school_year: item.school_year,
+			roster: item.roster,
+			member_id: item.member_id.to_string(),
+			full_name: item.full_name,
+			email: item.email,
+			grade: item.grade,
+			exec_type: item.exec_type,
+			status: item.status,
+		}
+	}
+}
+
+// Define the Alumnus struct (a member who graduated)
+#[derive(Serialize, Deserialize)]
+pub struct Alumnus {
+	pub _id: ObjectId,
+	pub full_name: String,
+	pub email: String,
+	pub school_year: String,
+	pub exec_types: Vec<String>,
+	pub date_created: DateTime,
+}
+
+// Define the AlumnusRequest struct
+#[derive(Serialize, Deserialize)]
+pub struct AlumnusRequest {
+	pub id: String,
+	pub full_name: String,
+	pub email: String,
+	pub school_year: String,
+	pub exec_types: Vec<String>,
+	pub date_created: String,
+}
+
+// Implement the From trait for Alumnus
+impl From<Alumnus> for AlumnusRequest {
+	fn from(item: Alumnus) -> Self {
+		Self {
+			id: item._id.to_string(),
+			full_name: item.full_name,
+			email: item.email,
+			school_year: item.school_year,
+			exec_types: item.exec_types,
+			date_created: item.date_created.to_string(),
+		}
+	}
+}
+
+// Get when a school year such as 2025-2026 starts and ends (September 1 to September 1 in Toronto)
+pub fn school_year_bounds(school_year: &str) -> Option<(DateTime, DateTime)> {
+	validate_school_year(school_year).ok()?;
+	let start = school_year[..4].parse::<i32>().ok()?;
+	let first_day = |year: i32| {
+		Toronto.with_ymd_and_hms(year, 9, 1, 0, 0, 0)
+			.single()
+			.map(|date| DateTime::from_millis(date.timestamp_millis()))
+	};
+	Some((first_day(start)?, first_day(start + 1)?))
+}
+
+// Get the school year happening right now
+pub fn current_school_year() -> String {
+	let today = Utc::now().with_timezone(&Toronto);
+	let start = if today.month() >= 9 { today.year() } else { today.year() - 1 };
+	format!("{}-{}", start, start + 1)
+}
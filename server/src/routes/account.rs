@@ -1,17 +1,23 @@
 use std::env::var;
-use actix_web::{ get, post, web::{ self, Data, Json }, HttpResponse };
+use actix_web::{ get, post, web::{ self, Data, Json }, HttpRequest, HttpResponse };
 use bcrypt::{ hash, verify, DEFAULT_COST };
 use jsonwebtoken::{ encode, EncodingKey, Header };
-use mail_builder::MessageBuilder;
-use mail_send::SmtpClientBuilder;
+use mongodb::bson::{ oid::ObjectId, DateTime };
 use serde::Deserialize;
 use serde_json::json;
 use validator::Validate;
 
 use crate::{
-	models::account::{ Account, AccountRequest },
-	services::db::Database,
-	utilities::{ claims::UserClaims, pagination_args::AdminPaginationArgs },
+	models::{
+		account::{ Account, AccountRequest },
+		application::{ AccountApplication, ReviewerNote },
+	},
+	services::{ db::Database, email::{ send_email, EmailTemplate } },
+	utilities::{
+		auth::{ get_signed_in_account, is_admin },
+		claims::{ Token, UserClaims },
+		pagination_args::AdminPaginationArgs,
+	},
 };
 
 // Define the AccountGiven struct
@@ -50,7 +56,15 @@ pub async fn get_account_by_username_or_email(
 #[get("/account/verify/{id}")]
 pub async fn verify_account(db: Data<Database>, id: web::Path<String>) -> HttpResponse {
 	match db.verify_account(id.to_string()).await {
-		Ok(_) => HttpResponse::Ok().json("Account verified."),
+		Ok(_) => {
+			// The account owns its email now, so link the applications sent from it
+			if let Ok(Some(acc)) = db.get_account_by_id(id.to_string()).await {
+				if let Err(err) = db.link_applications_to_account(&acc).await {
+					println!("Error linking applications: {}", err);
+				}
+			}
+			HttpResponse::Ok().json("Account verified.")
+		}
 		Err(_) => HttpResponse::Ok().json("Account not found."),
 	}
 }
@@ -178,24 +192,14 @@ pub async fn create_account(db: Data<Database>, request: Json<AccountRequest>) -
 				&object_id[10..object_id.len() - 2]
 			);
 
-			// Create the message
-			let message = MessageBuilder::new()
-				.from(("HB CyberTech".to_owned(), var("EMAIL_NAME").unwrap().to_string() + "@gmail.com"))
-				.to(request.email.clone())
-				.subject("Please verify your account!")
-				.html_body(
-					format!("<h1>Verify your account!</h1><a href=\"{}\">{}</a>", verify_url, verify_url)
-				)
-				.text_body(format!("Verify your account at the following link: {}", verify_url));
-
-			// Send the message
-			SmtpClientBuilder::new("smtp.gmail.com", 587)
-				.implicit_tls(false)
-				.credentials((var("EMAIL_NAME").unwrap().as_str(), var("EMAIL_PASSWORD").unwrap().as_str()))
-				.connect().await
-				.unwrap()
-				.send(message).await
-				.unwrap();
+			// Send the verification email
+			let email = EmailTemplate::Verification {
+				username: request.username.clone(),
+				verify_url,
+			};
+			if let Err(err) = send_email(request.email.clone(), &email).await {
+				println!("Error sending email: {}", err);
+			}
 
 			// Return the account
 			HttpResponse::Ok().json(acc)
@@ -203,3 +207,131 @@ pub async fn create_account(db: Data<Database>, request: Json<AccountRequest>) -
 		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
 	}
 }
+
+// Link every existing application to the verified account with the same email
+#[post("/account/link_applications")]
+pub async fn link_applications(db: Data<Database>, request: Json<Token>) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	match db.link_all_applications().await {
+		Ok((general_members, executive_members)) =>
+			HttpResponse::Ok().json(
+				json!({ "generalMembers": general_members, "executiveMembers": executive_members })
+			),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Get the applications of the signed-in account
+#[get("/account/applications")]
+pub async fn get_account_applications(db: Data<Database>, req: HttpRequest) -> HttpResponse {
+	let acc = match get_signed_in_account(&db, &req).await {
+		Some(acc) => acc,
+		None => {
+			return HttpResponse::Unauthorized().body("Unauthorized.");
+		}
+	};
+
+	let (general_members, executive_members) = match
+		(
+			db.get_general_members_by_account_id(acc._id).await,
+			db.get_executive_members_by_account_id(acc._id).await,
+		)
+	{
+		(Ok(general_members), Ok(executive_members)) => (general_members, executive_members),
+		(Err(err), _) | (_, Err(err)) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	let applications: Vec<AccountApplication> = general_members
+		.into_iter()
+		.map(|member| AccountApplication {
+			id: member._id.to_string(),
+			form: "general".to_string(),
+			exec_type: String::new(),
+			status: member.status,
+			term_ended: false,
+			date_created: member.date_created.to_string(),
+		})
+		.chain(
+			executive_members.into_iter().map(|member| AccountApplication {
+				id: member._id.to_string(),
+				form: "executive".to_string(),
+				exec_type: member.exec_type,
+				status: member.status,
+				term_ended: member.term_ended,
+				date_created: member.date_created.to_string(),
+			})
+		)
+		.collect();
+	HttpResponse::Ok().json(applications)
+}
+
+// Let the signed-in account withdraw one of its applications
+#[post("/account/applications/withdraw/{id}")]
+pub async fn withdraw_application(
+	db: Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	let acc = match get_signed_in_account(&db, &req).await {
+		Some(acc) => acc,
+		None => {
+			return HttpResponse::Unauthorized().body("Unauthorized.");
+		}
+	};
+
+	if ObjectId::parse_str(id.as_str()).is_err() {
+		return HttpResponse::BadRequest().body("Invalid ID.");
+	}
+
+	let note = ReviewerNote {
+		status: "withdrawn".to_string(),
+		note: "Withdrawn by the applicant.".to_string(),
+		date_created: DateTime::now(),
+	};
+
+	// Find the application in either form, as long as it belongs to the account
+	let general_member = match db.get_general_member_by_id(id.to_string()).await {
+		Ok(member) => member.filter(|member| member.account_id == Some(acc._id)),
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	let result = if let Some(member) = general_member {
+		if member.status == "withdrawn" {
+			return HttpResponse::BadRequest().body("The application has already been withdrawn.");
+		}
+		db.update_general_member_status(id.to_string(), note).await
+	} else {
+		let executive_member = match db.get_executive_member_by_id(id.to_string()).await {
+			Ok(member) => member.filter(|member| member.account_id == Some(acc._id)),
+			Err(err) => {
+				return HttpResponse::InternalServerError().body(err.to_string());
+			}
+		};
+		match executive_member {
+			Some(member) => {
+				if member.status == "withdrawn" {
+					return HttpResponse::BadRequest().body("The application has already been withdrawn.");
+				}
+				if member.term_ended {
+					return HttpResponse::BadRequest().body("The term of this application has ended.");
+				}
+				db.update_executive_member_status(id.to_string(), note).await
+			}
+			None => {
+				return HttpResponse::NotFound().body("Application not found.");
+			}
+		}
+	};
+
+	match result {
+		Ok(_) => HttpResponse::Ok().json("withdrawn"),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
@@ -1,12 +1,15 @@
 use std::env::var;
-use actix_web::{ post, web::{ self, Data }, HttpResponse };
+use actix_web::{ get, post, web::{ self, Data }, HttpRequest, HttpResponse };
 use bcrypt::{ hash, verify, DEFAULT_COST };
 use chrono::{ DateTime, Duration, Local, Utc };
 use jsonwebtoken::{ decode, encode, DecodingKey, EncodingKey, Header, Validation };
 use serde::{ Deserialize, Serialize };
 use serde_json::json;
 
-use crate::{ services::db::Database, utilities::claims::{ AdminClaims, Token } };
+use crate::{
+	services::{ db::Database, email::{ EmailTemplate, TEMPLATE_NAMES } },
+	utilities::{ auth::{ get_token, is_admin }, claims::{ AdminClaims, Token } },
+};
 
 // Define the Given struct
 #[derive(Serialize, Deserialize)]
@@ -14,6 +17,12 @@ struct Given {
 	password: String,
 }
 
+// Define the PreviewArgs struct
+#[derive(Deserialize)]
+struct PreviewArgs {
+	format: Option<String>,
+}
+
 // Verify the admin
 #[post("/admin/verify")]
 pub async fn verify_admin(db: Data<Database>, request: web::Json<Token>) -> HttpResponse {
@@ -94,3 +103,39 @@ pub async fn admin_sign_in(db: Data<Database>, request: web::Json<Given>) -> Htt
 		}
 	}
 }
+
+// List the email templates that can be previewed
+#[get("/admin/email/templates")]
+pub async fn get_email_templates(db: Data<Database>, req: HttpRequest) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	HttpResponse::Ok().json(TEMPLATE_NAMES)
+}
+
+// Preview an email template filled with sample data
+#[get("/admin/email/preview/{template}")]
+pub async fn preview_email(
+	db: Data<Database>,
+	template: web::Path<String>,
+	query: web::Query<PreviewArgs>,
+	req: HttpRequest
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	// Render the template as HTML or plain text
+	match EmailTemplate::sample(&template) {
+		Some(email) =>
+			match query.format.as_deref() {
+				Some("text") =>
+					HttpResponse::Ok().content_type("text/plain; charset=utf-8").body(email.text()),
+				_ => HttpResponse::Ok().content_type("text/html; charset=utf-8").body(email.html()),
+			}
+		None => HttpResponse::NotFound().body("Template not found."),
+	}
+}
@@ -1,5 +1,14 @@
 use std::env::var;
-use crate::models::announcement::AnnouncementRequestRequest;
+use crate::models::announcement::{
+	parse_optional_date,
+	validate_schedule,
+	AnnouncementEditRequest,
+	AnnouncementRequestRequest,
+};
+use crate::models::broadcast::{ Broadcast, BroadcastAudience, BroadcastRequest };
+use crate::services::email::EmailTemplate;
+use crate::services::mail_queue::{ MailJob, MailQueue };
+use crate::utilities::auth::{ get_token, is_admin };
 use crate::utilities::claims::AdminClaims;
 use crate::{ models::announcement::Announcement, services::db::Database };
 use crate::{
@@ -11,46 +20,109 @@ use actix_web::{ get, post, web::{ Data, Json }, HttpResponse };
 use bcrypt::{ hash, verify, DEFAULT_COST };
 use chrono::Utc;
 use jsonwebtoken::{ decode, DecodingKey, Validation };
-use mongodb::bson::oid::ObjectId;
-use std::cmp::Reverse;
+use mongodb::bson::{ oid::ObjectId, DateTime };
+use serde_json::json;
+use validator::Validate;
+
+// Validate the broadcast audience
+fn validate_audience(audience: &Option<BroadcastAudience>) -> Result<(), String> {
+	if let Some(audience) = audience {
+		audience.validate().map_err(|err| err.to_string())?;
+		if !audience.accounts && !audience.general_members && !audience.executive_members {
+			return Err("The broadcast must have an audience.".to_string());
+		}
+	}
+	Ok(())
+}
+
+// Create the delivery report of a broadcast and queue its emails
+async fn queue_broadcast(
+	db: &Database,
+	mail_queue: &MailQueue,
+	announcement: &Announcement,
+	audience: BroadcastAudience
+) -> Result<ObjectId, String> {
+	// Drafts and scheduled announcements cannot be emailed before everyone can see them
+	if !announcement.is_live() {
+		return Err("Only published announcements can be broadcast.".to_string());
+	}
+
+	// Find everyone in the audience
+	let recipients = db.get_broadcast_recipients(&audience).await.map_err(|err| err.to_string())?;
+
+	// Create the delivery report
+	let broadcast = Broadcast {
+		_id: ObjectId::new(),
+		announcement_id: announcement._id,
+		audience,
+		status: "queued".to_string(),
+		total: recipients.len() as u32,
+		sent: 0,
+		failed: 0,
+		failed_emails: Vec::new(),
+		date_created: DateTime::now(),
+		date_finished: None,
+	};
+	let broadcast_id = broadcast._id;
+	db.create_broadcast(broadcast).await.map_err(|err| err.to_string())?;
+
+	// Queue the emails to be sent in the background
+	let queued = mail_queue.enqueue(MailJob {
+		broadcast_id,
+		recipients,
+		template: EmailTemplate::AnnouncementBroadcast {
+			title: announcement.title.clone(),
+			body: announcement.body.clone(),
+		},
+	});
+	if !queued {
+		return Err("Failed to queue the broadcast.".to_string());
+	}
+
+	Ok(broadcast_id)
+}
 
-// Return the amount of announcements
+// Return the amount of announcements (admins also count drafts, scheduled and expired ones)
 #[get("/forum/announcements/get/amount")]
-pub async fn return_amount_of_announcements(db: Data<Database>) -> HttpResponse {
-	match db.get_amount_of_announcements().await {
+pub async fn return_amount_of_announcements(db: Data<Database>, req: HttpRequest) -> HttpResponse {
+	let include_hidden = match get_token(&req) {
+		Some(token) => is_admin(&db, &token).await,
+		None => false,
+	};
+
+	match db.get_amount_of_announcements(include_hidden).await {
 		Ok(amount) => HttpResponse::Ok().json(amount),
 		Err(_) => HttpResponse::Ok().json(0),
 	}
 }
 
-// Return the announcements with pagination
+// Return the announcements with pagination (admins also see drafts, scheduled and expired ones)
 #[post("/forum/announcements/get")]
 pub async fn return_announcements(
 	db: Data<Database>,
-	request: Json<PaginationArgs>
+	request: Json<PaginationArgs>,
+	req: HttpRequest
 ) -> HttpResponse {
+	let include_hidden = match get_token(&req) {
+		Some(token) => is_admin(&db, &token).await,
+		None => false,
+	};
+
 	// Get the paginated announcements
 	match
 		db.get_announcements(
 			request.page,
 			request.limit,
 			request.search.clone(),
-			request.field.clone()
+			request.field.clone(),
+			include_hidden
 		).await
 	{
-		Ok(mut posts) => {
-			// Sort the posts by date_created in descending order
-			posts.sort_by_key(|post| Reverse(post.date_created));
+		Ok(posts) => {
+			// The posts come sorted by the date they were published in descending order
 			let announcements: Vec<AnnouncementRequest> = posts
 				.into_iter()
-				.map(|post| AnnouncementRequest {
-					id: post._id.to_string(),
-					author: post.author.clone(),
-					email: post.email.clone(),
-					date_created: post.date_created.to_string(),
-					title: post.title.clone(),
-					body: post.body.clone(),
-				})
+				.map(AnnouncementRequest::from)
 				.collect();
 
 			HttpResponse::Ok().json(announcements)
@@ -63,6 +135,7 @@ pub async fn return_announcements(
 #[post("/forum/announcements/create")]
 pub async fn create_announcement(
 	db: Data<Database>,
+	mail_queue: Data<MailQueue>,
 	request: Json<AnnouncementRequestRequest>
 ) -> HttpResponse {
 	let claims = decode::<AdminClaims>(
@@ -96,20 +169,188 @@ pub async fn create_announcement(
 		return HttpResponse::BadRequest().body("Invalid token.");
 	}
 
+	// Validate the broadcast audience
+	if let Err(err) = validate_audience(&request.broadcast) {
+		return HttpResponse::BadRequest().body(err);
+	}
+
+	// Convert the request to an announcement
+	let announcement = match
+		Announcement::try_from(AnnouncementRequest {
+			id: ObjectId::new().to_string(),
+			author: "The Team".to_string(),
+			email: format!("{}@gmail.com", var("EMAIL_NAME").unwrap()),
+			date_created: request.date_created.clone(),
+			title: request.title.clone(),
+			body: request.body.clone(),
+			draft: request.draft,
+			publish_at: request.publish_at.clone(),
+			expires_at: request.expires_at.clone(),
+			date_updated: String::new(),
+		})
+	{
+		Ok(announcement) => announcement,
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	};
+
+	// Validate the schedule
+	if let Err(err) = validate_schedule(announcement.publish_at, announcement.expires_at) {
+		return HttpResponse::BadRequest().body(err);
+	}
+	if request.broadcast.is_some() && !announcement.is_live() {
+		return HttpResponse::BadRequest().body("Only published announcements can be broadcast.");
+	}
+
 	// Create the announcement
-	match
-		db.create_announcement(
-			Announcement::try_from(AnnouncementRequest {
-				id: ObjectId::new().to_string(),
-				author: "The Team".to_string(),
-				email: format!("{}@gmail.com", var("EMAIL_NAME").unwrap()),
-				date_created: request.date_created.clone(),
-				title: request.title.clone(),
-				body: request.body.clone(),
-			}).expect("Error converting PostRequest to Post.")
-		).await
+	match db.create_announcement(announcement.clone()).await {
+		Ok(post) => {
+			// Only broadcast the announcement if the admin asked for it
+			let audience = match &request.broadcast {
+				Some(audience) => audience.clone(),
+				None => {
+					return HttpResponse::Ok().json(post);
+				}
+			};
+
+			match queue_broadcast(&db, &mail_queue, &announcement, audience).await {
+				Ok(broadcast_id) =>
+					HttpResponse::Ok().json(
+						json!({ "insertedId": post.inserted_id, "broadcastId": broadcast_id.to_string() })
+					),
+				Err(err) => HttpResponse::InternalServerError().body(err),
+			}
+		}
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Edit an announcement before or after it goes live
+#[post("/forum/announcements/edit/{id}")]
+pub async fn edit_announcement(
+	db: Data<Database>,
+	mail_queue: Data<MailQueue>,
+	id: web::Path<String>,
+	request: Json<AnnouncementEditRequest>
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	// Validate the broadcast audience
+	if let Err(err) = validate_audience(&request.broadcast) {
+		return HttpResponse::BadRequest().body(err);
+	}
+
+	if ObjectId::parse_str(id.as_str()).is_err() {
+		return HttpResponse::BadRequest().body("Invalid ID.");
+	}
+
+	// Get the announcement
+	let mut announcement = match db.get_announcement_by_id(id.to_string()).await {
+		Ok(Some(announcement)) => announcement,
+		Ok(None) => {
+			return HttpResponse::NotFound().body("Announcement not found.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	// Apply the changes
+	let (publish_at, expires_at) = match
+		(parse_optional_date(&request.publish_at), parse_optional_date(&request.expires_at))
 	{
-		Ok(post) => HttpResponse::Ok().json(post),
+		(Ok(publish_at), Ok(expires_at)) => (publish_at, expires_at),
+		(Err(err), _) | (_, Err(err)) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	};
+	if let Err(err) = validate_schedule(publish_at, expires_at) {
+		return HttpResponse::BadRequest().body(err);
+	}
+	announcement.title = request.title.clone();
+	announcement.body = request.body.clone();
+	announcement.draft = request.draft;
+	announcement.publish_at = publish_at;
+	announcement.expires_at = expires_at;
+	announcement.date_updated = Some(DateTime::now());
+
+	if request.broadcast.is_some() && !announcement.is_live() {
+		return HttpResponse::BadRequest().body("Only published announcements can be broadcast.");
+	}
+
+	// Update the announcement
+	if let Err(err) = db.update_announcement(id.to_string(), &announcement).await {
+		return HttpResponse::InternalServerError().body(err.to_string());
+	}
+
+	// Broadcast the announcement if the admin asked for it
+	match &request.broadcast {
+		Some(audience) =>
+			match queue_broadcast(&db, &mail_queue, &announcement, audience.clone()).await {
+				Ok(broadcast_id) =>
+					HttpResponse::Ok().json(
+						json!({
+							"announcement": AnnouncementRequest::from(announcement),
+							"broadcastId": broadcast_id.to_string(),
+						})
+					),
+				Err(err) => HttpResponse::InternalServerError().body(err),
+			}
+		None => HttpResponse::Ok().json(AnnouncementRequest::from(announcement)),
+	}
+}
+
+// Get the delivery report of a broadcast
+#[get("/forum/announcements/broadcast/{id}")]
+pub async fn get_broadcast_report(
+	db: Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	if ObjectId::parse_str(id.as_str()).is_err() {
+		return HttpResponse::BadRequest().body("Invalid ID.");
+	}
+
+	match db.get_broadcast_by_id(id.to_string()).await {
+		Ok(Some(broadcast)) => HttpResponse::Ok().json(BroadcastRequest::from(broadcast)),
+		Ok(None) => HttpResponse::NotFound().body("Broadcast not found."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Get the delivery reports of every broadcast of an announcement
+#[get("/forum/announcements/{id}/broadcasts")]
+pub async fn get_announcement_broadcasts(
+	db: Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	if ObjectId::parse_str(id.as_str()).is_err() {
+		return HttpResponse::BadRequest().body("Invalid ID.");
+	}
+
+	match db.get_broadcasts_by_announcement_id(id.to_string()).await {
+		Ok(broadcasts) => {
+			let broadcasts: Vec<BroadcastRequest> = broadcasts
+				.into_iter()
+				.map(BroadcastRequest::from)
+				.collect();
+			HttpResponse::Ok().json(broadcasts)
+		}
 		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
 	}
 }
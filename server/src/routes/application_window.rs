@@ -0,0 +1,146 @@
+use actix_web::{ delete, get, post, web::{ self, Data, Json }, HttpRequest, HttpResponse };
+use serde::Deserialize;
+use validator::Validate;
+
+use crate::{
+	models::application_window::{ ApplicationWindow, ApplicationWindowRequest, FormStatus },
+	services::db::Database,
+	utilities::auth::{ get_token, is_admin },
+};
+
+// The forms that can have an application window, as (form, exec type)
+const FORMS: [(&str, &str); 5] = [
+	("general", ""),
+	("executive", ""),
+	("executive", "development"),
+	("executive", "marketing"),
+	("executive", "events"),
+];
+
+// Define the WindowArgs struct
+#[derive(Deserialize)]
+struct WindowArgs {
+	form: String,
+	#[serde(default)]
+	exec_type: String,
+}
+
+// Report whether each form is currently accepting responses
+#[get("/application_window/status")]
+pub async fn get_form_statuses(db: Data<Database>) -> HttpResponse {
+	let mut statuses: Vec<FormStatus> = Vec::new();
+
+	for (form, exec_type) in FORMS {
+		let window = match db.get_application_window(form, exec_type).await {
+			Ok(window) => window,
+			Err(err) => {
+				return HttpResponse::InternalServerError().body(err.to_string());
+			}
+		};
+
+		statuses.push(match window {
+			Some(window) =>
+				FormStatus {
+					form: form.to_string(),
+					exec_type: exec_type.to_string(),
+					open: window.is_open(),
+					opens_at: window.opens_at.to_string(),
+					closes_at: window.closes_at.to_string(),
+				},
+			None =>
+				FormStatus {
+					form: form.to_string(),
+					exec_type: exec_type.to_string(),
+					open: form == "general",
+					opens_at: String::new(),
+					closes_at: String::new(),
+				},
+		});
+	}
+
+	HttpResponse::Ok().json(statuses)
+}
+
+// Get every configured application window
+#[get("/application_window/get_all")]
+pub async fn get_application_windows(db: Data<Database>, req: HttpRequest) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	match db.get_application_windows().await {
+		Ok(windows) => {
+			let windows: Vec<FormStatus> = windows
+				.into_iter()
+				.map(|window| FormStatus {
+					open: window.is_open(),
+					form: window.form,
+					exec_type: window.exec_type,
+					opens_at: window.opens_at.to_string(),
+					closes_at: window.closes_at.to_string(),
+				})
+				.collect();
+			HttpResponse::Ok().json(windows)
+		}
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Open a form between two dates
+#[post("/application_window/set")]
+pub async fn set_application_window(
+	db: Data<Database>,
+	request: Json<ApplicationWindowRequest>
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	// Validate the request
+	match request.validate() {
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	}
+
+	// Create or replace the window of the form
+	match
+		ApplicationWindow::try_from(ApplicationWindowRequest {
+			form: request.form.clone(),
+			exec_type: request.exec_type.clone(),
+			opens_at: request.opens_at.clone(),
+			closes_at: request.closes_at.clone(),
+			token: String::new(),
+		})
+	{
+		Ok(window) =>
+			match db.set_application_window(window).await {
+				Ok(result) => HttpResponse::Ok().json(result),
+				Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+			}
+		Err(err) => HttpResponse::BadRequest().body(err.to_string()),
+	}
+}
+
+// Remove the window of a form so it goes back to its default
+#[delete("/application_window/delete")]
+pub async fn delete_application_window(
+	db: Data<Database>,
+	query: web::Query<WindowArgs>,
+	req: HttpRequest
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	match db.delete_application_window(&query.form, &query.exec_type).await {
+		Ok(result) if result.deleted_count > 0 =>
+			HttpResponse::Ok().body("Application window deleted successfully."),
+		Ok(_) => HttpResponse::NotFound().body("Application window not found."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
@@ -0,0 +1,331 @@
+use std::{ cmp::Reverse, collections::{ BTreeMap, HashSet } };
+use actix_web::{ get, post, web::{ self, Data, Json }, HttpRequest, HttpResponse };
+use mongodb::bson::{ oid::ObjectId, DateTime };
+use serde::Deserialize;
+
+use crate::{
+	models::{
+		attendance::{
+			Attendance,
+			AttendanceRequest,
+			CheckInCode,
+			CheckInRequest,
+			EventAttendance,
+			MemberAttendance,
+		},
+		event::Event,
+		school_year::{ current_school_year, school_year_bounds },
+	},
+	services::{ checkin::{ current_code, qr_svg, verify_code }, db::Database, email::client_url },
+	utilities::auth::{ get_token, get_verified_account, is_admin },
+};
+
+// Define the AttendanceArgs struct
+#[derive(Deserialize)]
+struct AttendanceArgs {
+	#[serde(default)]
+	school_year: String,
+}
+
+// Get an event for an admin route, checking the admin token and the ID
+async fn get_event_as_admin(db: &Database, id: &str, req: &HttpRequest) -> Result<Event, HttpResponse> {
+	// Verify the admin token
+	if !is_admin(db, &get_token(req).unwrap_or_default()).await {
+		return Err(HttpResponse::Unauthorized().body("Unauthorized."));
+	}
+
+	if ObjectId::parse_str(id).is_err() {
+		return Err(HttpResponse::BadRequest().body("Invalid ID."));
+	}
+
+	match db.get_event_by_id(id.to_string()).await {
+		Ok(Some(event)) => Ok(event),
+		Ok(None) => Err(HttpResponse::NotFound().body("Event not found.")),
+		Err(err) => Err(HttpResponse::InternalServerError().body(err.to_string())),
+	}
+}
+
+// Get the start and end of the school year asked for, defaulting to the current one
+fn get_school_year_bounds(school_year: &str) -> Option<(DateTime, DateTime)> {
+	if school_year.is_empty() {
+		school_year_bounds(&current_school_year())
+	} else {
+		school_year_bounds(school_year)
+	}
+}
+
+// Get the link that the check-in QR code of an event points to
+fn checkin_url(event_id: ObjectId, code: &str) -> String {
+	format!("{}/events/checkin/{}?code={}", client_url(), event_id, code)
+}
+
+// Get the current check-in code of an event, for screens that draw their own QR code
+#[get("/events/checkin/code/{id}")]
+pub async fn get_checkin_code(
+	db: Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	let event = match get_event_as_admin(&db, &id, &req).await {
+		Ok(event) => event,
+		Err(response) => {
+			return response;
+		}
+	};
+
+	let (code, expires_at) = current_code(event._id);
+	HttpResponse::Ok().json(CheckInCode {
+		url: checkin_url(event._id, &code),
+		code,
+		expires_at: expires_at.to_string(),
+	})
+}
+
+// Get the current check-in code of an event as a QR code
+#[get("/events/checkin/qr/{id}")]
+pub async fn get_checkin_qr(
+	db: Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	let event = match get_event_as_admin(&db, &id, &req).await {
+		Ok(event) => event,
+		Err(response) => {
+			return response;
+		}
+	};
+
+	let (code, _) = current_code(event._id);
+	match qr_svg(&checkin_url(event._id, &code)) {
+		Ok(svg) =>
+			HttpResponse::Ok()
+				.content_type("image/svg+xml")
+				// The code changes every minute, so it should never be cached
+				.insert_header(("Cache-Control", "no-store"))
+				.body(svg),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Check in to an event with the code shown at the event
+#[post("/events/checkin/{id}")]
+pub async fn check_in(
+	db: Data<Database>,
+	id: web::Path<String>,
+	request: Json<CheckInRequest>,
+	req: HttpRequest
+) -> HttpResponse {
+	let acc = match get_verified_account(&db, &req, "check in").await {
+		Ok(acc) => acc,
+		Err(response) => {
+			return response;
+		}
+	};
+
+	if ObjectId::parse_str(id.as_str()).is_err() {
+		return HttpResponse::BadRequest().body("Invalid ID.");
+	}
+
+	let event = match db.get_event_by_id(id.to_string()).await {
+		Ok(Some(event)) => event,
+		Ok(None) => {
+			return HttpResponse::NotFound().body("Event not found.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	if !event.is_checkin_open() {
+		return HttpResponse::BadRequest().body("Check-in is not open for this event.");
+	}
+	if !verify_code(event._id, &request.code) {
+		return HttpResponse::BadRequest().body("Invalid or expired check-in code.");
+	}
+
+	let attendance = Attendance {
+		_id: ObjectId::new(),
+		event_id: event._id,
+		title: event.title,
+		account_id: acc._id,
+		username: acc.username,
+		email: acc.email,
+		date_created: DateTime::now(),
+	};
+	match db.record_attendance(attendance).await {
+		Ok(true) => HttpResponse::Ok().json("Checked in."),
+		Ok(false) => HttpResponse::Ok().json("Already checked in."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Get who came to an event, and who said they were going but did not
+#[get("/events/attendance/{id}")]
+pub async fn get_event_attendance(
+	db: Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	let event = match get_event_as_admin(&db, &id, &req).await {
+		Ok(event) => event,
+		Err(response) => {
+			return response;
+		}
+	};
+
+	let attendance = match db.get_attendance_by_event_id(event._id).await {
+		Ok(attendance) => attendance,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	let rsvps = match db.get_rsvps(event._id).await {
+		Ok(rsvps) => rsvps,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	let attended: HashSet<ObjectId> = attendance
+		.iter()
+		.map(|attendance| attendance.account_id)
+		.collect();
+	let going: Vec<_> = rsvps
+		.into_iter()
+		.filter(|rsvp| rsvp.status == "going")
+		.collect();
+	let no_shows: Vec<String> = going
+		.iter()
+		.filter(|rsvp| !attended.contains(&rsvp.account_id))
+		.map(|rsvp| rsvp.username.clone())
+		.collect();
+
+	HttpResponse::Ok().json(EventAttendance {
+		event_id: event._id.to_string(),
+		title: event.title,
+		going: going.len() as u64,
+		attended: attendance.len() as u64,
+		attendees: attendance.into_iter().map(AttendanceRequest::from).collect(),
+		no_shows,
+	})
+}
+
+// Get how many of the school year's events each member came to
+#[get("/attendance/members")]
+pub async fn get_member_attendance(
+	db: Data<Database>,
+	query: web::Query<AttendanceArgs>,
+	req: HttpRequest
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	let (start, end) = match get_school_year_bounds(&query.school_year) {
+		Some(bounds) => bounds,
+		None => {
+			return HttpResponse::BadRequest().body("Invalid school year. Must be like 2025-2026.");
+		}
+	};
+
+	// Only count the events that have started so far
+	let events = match db.get_events_between(start, end).await {
+		Ok(events) => events,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	let held: HashSet<ObjectId> = events
+		.iter()
+		.filter(|event| event.starts_at <= DateTime::now())
+		.map(|event| event._id)
+		.collect();
+
+	let attendance = match db.get_attendance_between(start, end).await {
+		Ok(attendance) => attendance,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	// Members who never came are listed too: the club's members and everyone who RSVPed to the year's events
+	let mut account_ids: HashSet<ObjectId> = match db.get_member_account_ids().await {
+		Ok(ids) => ids.into_iter().collect(),
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	match db.get_rsvps_by_event_ids(held.iter().copied().collect()).await {
+		Ok(rsvps) => account_ids.extend(rsvps.into_iter().map(|rsvp| rsvp.account_id)),
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	}
+	let accounts = match db.get_accounts_by_ids(account_ids.into_iter().collect()).await {
+		Ok(accounts) => accounts,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	let mut members: BTreeMap<ObjectId, MemberAttendance> = accounts
+		.into_iter()
+		.map(|acc| {
+			(acc._id, MemberAttendance {
+				account_id: acc._id.to_string(),
+				username: acc.username,
+				email: acc.email,
+				attended: 0,
+				events: held.len() as u64,
+			})
+		})
+		.collect();
+	for attendance in attendance.into_iter().filter(|attendance| held.contains(&attendance.event_id)) {
+		members
+			.entry(attendance.account_id)
+			.or_insert(MemberAttendance {
+				account_id: attendance.account_id.to_string(),
+				username: attendance.username,
+				email: attendance.email,
+				attended: 0,
+				events: held.len() as u64,
+			}).attended += 1;
+	}
+
+	let mut members: Vec<MemberAttendance> = members.into_values().collect();
+	members.sort_by(|a, b| (Reverse(a.attended), &a.username).cmp(&(Reverse(b.attended), &b.username)));
+	HttpResponse::Ok().json(members)
+}
+
+// Get the events of the school year a member came to
+#[get("/attendance/member/{username}")]
+pub async fn get_attendance_by_member(
+	db: Data<Database>,
+	username: web::Path<String>,
+	query: web::Query<AttendanceArgs>,
+	req: HttpRequest
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	let (start, end) = match get_school_year_bounds(&query.school_year) {
+		Some(bounds) => bounds,
+		None => {
+			return HttpResponse::BadRequest().body("Invalid school year. Must be like 2025-2026.");
+		}
+	};
+
+	match db.get_attendance_between(start, end).await {
+		Ok(attendance) => {
+			let attendance: Vec<AttendanceRequest> = attendance
+				.into_iter()
+				.filter(|attendance| attendance.username == username.as_str())
+				.map(AttendanceRequest::from)
+				.collect();
+			HttpResponse::Ok().json(attendance)
+		}
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
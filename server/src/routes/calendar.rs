@@ -0,0 +1,145 @@
+use std::{ collections::{ hash_map::DefaultHasher, HashMap }, env::var, hash::{ Hash, Hasher } };
+use actix_web::{ get, http::header, post, web::{ self, Data }, HttpRequest, HttpResponse };
+use mongodb::bson::{ oid::ObjectId, DateTime };
+use sha2::{ Digest, Sha256 };
+
+use crate::{
+	models::event::Event,
+	services::{ calendar::{ calendar, event_to_calendar, CalendarEvent }, db::Database },
+	utilities::auth::{ generate_link_token, get_verified_account },
+};
+
+// How long past events stay in the feeds
+const PAST_EVENT_MILLIS: i64 = 30 * 24 * 60 * 60 * 1000;
+
+// Hash the secret of a private calendar link so it can be looked up without being stored
+fn hash_calendar_token(token: &str) -> String {
+	hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+// Send an iCalendar file, letting calendar apps skip downloading it again if it did not change
+fn calendar_response(req: &HttpRequest, body: String, filename: &str) -> HttpResponse {
+	let mut hasher = DefaultHasher::new();
+	body.hash(&mut hasher);
+	let etag = format!("\"{:x}\"", hasher.finish());
+
+	let not_modified = req
+		.headers()
+		.get(header::IF_NONE_MATCH)
+		.and_then(|value| value.to_str().ok())
+		.is_some_and(|if_none_match| if_none_match.split(',').any(|tag| tag.trim() == etag));
+	if not_modified {
+		return HttpResponse::NotModified().insert_header((header::ETAG, etag)).finish();
+	}
+
+	HttpResponse::Ok()
+		.content_type("text/calendar; charset=utf-8")
+		.insert_header((header::ETAG, etag))
+		.insert_header((header::CONTENT_DISPOSITION, format!("inline; filename=\"{}\"", filename)))
+		.body(body)
+}
+
+// Get when events must end after to be in the feeds
+fn feed_start() -> DateTime {
+	DateTime::from_millis(DateTime::now().timestamp_millis() - PAST_EVENT_MILLIS)
+}
+
+// Get the calendar of all upcoming club events
+#[get("/events/calendar")]
+pub async fn get_events_calendar(db: Data<Database>, req: HttpRequest) -> HttpResponse {
+	match db.get_calendar_events(feed_start()).await {
+		Ok(events) => {
+			let events: Vec<CalendarEvent> = events
+				.iter()
+				.map(|event| event_to_calendar(event, false))
+				.collect();
+			calendar_response(&req, calendar("HB CyberTech Events", &events), "hbcybertech.ics")
+		}
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Get the calendar file of one event
+#[get("/events/calendar/{id}")]
+pub async fn get_event_calendar(
+	db: Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	if ObjectId::parse_str(id.as_str()).is_err() {
+		return HttpResponse::BadRequest().body("Invalid ID.");
+	}
+
+	match db.get_event_by_id(id.to_string()).await {
+		Ok(Some(event)) => {
+			let body = calendar(&event.title, &[event_to_calendar(&event, false)]);
+			calendar_response(&req, body, &format!("event-{}.ics", event._id))
+		}
+		Ok(None) => HttpResponse::NotFound().body("Event not found."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Get a private calendar link of the events the signed-in account RSVP'd to, replacing the old link
+#[post("/account/calendar")]
+pub async fn create_calendar_link(db: Data<Database>, req: HttpRequest) -> HttpResponse {
+	let acc = match get_verified_account(&db, &req, "get a calendar link").await {
+		Ok(acc) => acc,
+		Err(response) => {
+			return response;
+		}
+	};
+
+	let token = generate_link_token();
+	if let Err(err) = db.set_calendar_token(acc._id, hash_calendar_token(&token)).await {
+		return HttpResponse::InternalServerError().body(err.to_string());
+	}
+
+	HttpResponse::Ok().json(
+		format!("http://{}/events/calendar/private/{}", var("SERVER_URL").unwrap_or_default(), token)
+	)
+}
+
+// Get the private calendar of the events an account RSVP'd to
+#[get("/events/calendar/private/{token}")]
+pub async fn get_private_calendar(
+	db: Data<Database>,
+	token: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	let acc = match db.get_account_by_calendar_token(&hash_calendar_token(&token)).await {
+		Ok(Some(acc)) => acc,
+		Ok(None) => {
+			return HttpResponse::NotFound().body("Calendar not found.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	let rsvps = match db.get_rsvps_by_account_id(acc._id).await {
+		Ok(rsvps) => rsvps,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	let waitlisted: HashMap<ObjectId, bool> = rsvps
+		.iter()
+		.map(|rsvp| (rsvp.event_id, rsvp.status == "waitlisted"))
+		.collect();
+
+	let events: Vec<Event> = match db.get_events_by_ids(waitlisted.keys().copied().collect()).await {
+		Ok(events) => events,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	let since = feed_start();
+	let events: Vec<CalendarEvent> = events
+		.iter()
+		.filter(|event| event.ends_at > since)
+		.map(|event| event_to_calendar(event, waitlisted[&event._id]))
+		.collect();
+
+	calendar_response(&req, calendar("My HB CyberTech Events", &events), "my-hbcybertech.ics")
+}
@@ -0,0 +1,786 @@
+use std::collections::{ HashMap, HashSet };
+use actix_web::{ delete, get, post, web::{ self, Data, Json }, HttpRequest, HttpResponse };
+use bcrypt::{ hash, DEFAULT_COST };
+use mongodb::bson::{ oid::ObjectId, DateTime };
+use validator::Validate;
+
+use crate::{
+	models::{
+		account::Account,
+		announcement::parse_optional_date,
+		ctf::{
+			Challenge,
+			ChallengeRequest,
+			Competition,
+			CompetitionArgs,
+			CompetitionRequest,
+			FlagSubmission,
+			FreezeRequest,
+			HintUnlock,
+			ScoreboardArgs,
+			Solve,
+			SolveRequest,
+			Submission,
+		},
+	},
+	services::{ db::Database, scoreboard::build_scoreboard },
+	utilities::auth::{ get_signed_in_account, get_token, get_verified_account, is_admin },
+};
+
+// How many flags an account can try on one challenge within the rate limit window
+const MAX_SUBMISSIONS: u64 = 5;
+const SUBMISSION_WINDOW_MILLIS: i64 = 60 * 1000;
+
+// Add the solve count, and whether the signed-in account solved it, to each challenge, leaving out the content
+// of the hints it has not unlocked (unless it is all shown to an admin). Solves from after a scoreboard freeze
+// are left out of the count and the value, like on the scoreboard.
+async fn challenges_with_solves(
+	db: &Database,
+	challenges: Vec<Challenge>,
+	solved: &HashSet<ObjectId>,
+	unlocked: Option<&HashSet<(ObjectId, u32)>>,
+	before: Option<DateTime>
+) -> Result<Vec<ChallengeRequest>, mongodb::error::Error> {
+	let mut result: Vec<ChallengeRequest> = Vec::new();
+	for challenge in challenges {
+		let solves = db.count_solves(challenge._id, before).await?;
+		let challenge_id = challenge._id;
+		let value = challenge.value(solves);
+		let mut challenge = ChallengeRequest::from(challenge);
+		challenge.value = value;
+		challenge.solves = solves;
+		challenge.solved = solved.contains(&challenge_id);
+		for hint in challenge.hints.iter_mut() {
+			hint.unlocked = unlocked.is_none_or(|unlocked| unlocked.contains(&(challenge_id, hint.index)));
+			if !hint.unlocked {
+				hint.content = String::new();
+			}
+		}
+		result.push(challenge);
+	}
+	Ok(result)
+}
+
+// Get the competition asked for, where no competition means the practice challenges
+async fn get_competition(db: &Database, competition: &str) -> Result<Option<Competition>, HttpResponse> {
+	if competition.is_empty() {
+		return Ok(None);
+	}
+	let competition_id = match ObjectId::parse_str(competition) {
+		Ok(competition_id) => competition_id,
+		Err(_) => {
+			return Err(HttpResponse::BadRequest().body("Invalid competition ID."));
+		}
+	};
+
+	match db.get_competition_by_id(competition_id).await {
+		Ok(Some(competition)) => Ok(Some(competition)),
+		Ok(None) => Err(HttpResponse::NotFound().body("Competition not found.")),
+		Err(err) => Err(HttpResponse::InternalServerError().body(err.to_string())),
+	}
+}
+
+// Get a challenge players can see, along with its competition, which must have started
+async fn get_open_challenge(
+	db: &Database,
+	id: &str
+) -> Result<(Challenge, Option<Competition>), HttpResponse> {
+	if ObjectId::parse_str(id).is_err() {
+		return Err(HttpResponse::BadRequest().body("Invalid ID."));
+	}
+
+	let challenge = match db.get_challenge_by_id(id.to_string()).await {
+		Ok(Some(challenge)) if !challenge.hidden => challenge,
+		Ok(_) => {
+			return Err(HttpResponse::NotFound().body("Challenge not found."));
+		}
+		Err(err) => {
+			return Err(HttpResponse::InternalServerError().body(err.to_string()));
+		}
+	};
+	let competition = match challenge.competition_id {
+		Some(competition_id) =>
+			match db.get_competition_by_id(competition_id).await {
+				Ok(competition) => competition,
+				Err(err) => {
+					return Err(HttpResponse::InternalServerError().body(err.to_string()));
+				}
+			}
+		None => None,
+	};
+	if competition.as_ref().is_some_and(|competition| !competition.has_started()) {
+		return Err(HttpResponse::NotFound().body("Challenge not found."));
+	}
+	Ok((challenge, competition))
+}
+
+// Check that the competition of a challenge still takes flags, and that the team fits its size limit
+async fn check_can_play(
+	db: &Database,
+	competition: Option<&Competition>,
+	acc: &Account
+) -> Result<Option<ObjectId>, HttpResponse> {
+	if competition.is_some_and(|competition| competition.has_ended()) {
+		return Err(HttpResponse::BadRequest().body("The competition has ended."));
+	}
+
+	let team = match db.get_team_by_account_id(acc._id).await {
+		Ok(team) => team,
+		Err(err) => {
+			return Err(HttpResponse::InternalServerError().body(err.to_string()));
+		}
+	};
+	// Joining a team already checks the limit, which only catches teams formed before the competition was set up
+	let max_team_size = competition.map(|competition| competition.max_team_size).unwrap_or(0);
+	if let Some(team) = &team {
+		if max_team_size > 0 && team.members.len() > (max_team_size as usize) {
+			return Err(
+				HttpResponse::Forbidden().body(
+					format!("Teams in this competition can have at most {} members.", max_team_size)
+				)
+			);
+		}
+	}
+	Ok(team.map(|team| team._id))
+}
+
+// Get when the scoreboard freezes, and whether the requester only sees it as it was then (admins see it live)
+async fn get_scoreboard_freeze(
+	db: &Database,
+	req: &HttpRequest,
+	competition: Option<&Competition>
+) -> Result<(Option<DateTime>, bool), mongodb::error::Error> {
+	let freeze_at = match competition {
+		Some(competition) => competition.freeze_at,
+		None => db.get_scoreboard_freeze().await?,
+	};
+	let admin = match get_token(req) {
+		Some(token) => is_admin(db, &token).await,
+		None => false,
+	};
+	Ok((freeze_at, !admin && freeze_at.is_some_and(|freeze_at| freeze_at <= DateTime::now())))
+}
+
+// Get the visible challenges of a competition, or the practice challenges
+#[get("/ctf/challenges")]
+pub async fn get_challenges(
+	db: Data<Database>,
+	query: web::Query<CompetitionArgs>,
+	req: HttpRequest
+) -> HttpResponse {
+	let competition = match get_competition(&db, &query.competition).await {
+		Ok(competition) => competition,
+		Err(response) => {
+			return response;
+		}
+	};
+	if competition.as_ref().is_some_and(|competition| !competition.has_started()) {
+		return HttpResponse::Forbidden().body("The competition has not started yet.");
+	}
+	let before = match get_scoreboard_freeze(&db, &req, competition.as_ref()).await {
+		Ok((freeze_at, true)) => freeze_at,
+		Ok(_) => None,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	let mut solved: HashSet<ObjectId> = HashSet::new();
+	let mut unlocked: HashSet<(ObjectId, u32)> = HashSet::new();
+	if let Some(acc) = get_signed_in_account(&db, &req).await {
+		match db.get_solves_by_account_id(acc._id).await {
+			Ok(solves) => solved.extend(solves.into_iter().map(|solve| solve.challenge_id)),
+			Err(err) => {
+				return HttpResponse::InternalServerError().body(err.to_string());
+			}
+		}
+		match db.get_hint_unlocks_by_account_id(acc._id).await {
+			Ok(unlocks) =>
+				unlocked.extend(unlocks.into_iter().map(|unlock| (unlock.challenge_id, unlock.index))),
+			Err(err) => {
+				return HttpResponse::InternalServerError().body(err.to_string());
+			}
+		}
+	}
+
+	let challenges = match db.get_challenges(competition.map(|competition| competition._id)).await {
+		Ok(challenges) => challenges,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	match challenges_with_solves(&db, challenges, &solved, Some(&unlocked), before).await {
+		Ok(challenges) => HttpResponse::Ok().json(challenges),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Get every challenge, hidden ones included
+#[get("/ctf/challenges/all")]
+pub async fn get_all_challenges(db: Data<Database>, req: HttpRequest) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	let challenges = match db.get_all_challenges().await {
+		Ok(challenges) => challenges,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	match challenges_with_solves(&db, challenges, &HashSet::new(), None, None).await {
+		Ok(challenges) => HttpResponse::Ok().json(challenges),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Create a challenge
+#[post("/ctf/challenges/create")]
+pub async fn create_challenge(db: Data<Database>, request: Json<ChallengeRequest>) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	// Validate the request
+	match request.validate() {
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	}
+	if request.flag.trim().is_empty() && request.flag_regex.is_empty() {
+		return HttpResponse::BadRequest().body("A flag or a flag regex is required.");
+	}
+
+	let flag = request.flag.trim().to_string();
+	let mut challenge = match Challenge::try_from(request.into_inner()) {
+		Ok(challenge) => challenge,
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	};
+	if let Some(competition_id) = challenge.competition_id {
+		match db.get_competition_by_id(competition_id).await {
+			Ok(Some(_)) => (),
+			Ok(None) => {
+				return HttpResponse::BadRequest().body("Competition not found.");
+			}
+			Err(err) => {
+				return HttpResponse::InternalServerError().body(err.to_string());
+			}
+		}
+	}
+	match db.challenge_title_is_taken(&challenge.title, challenge._id).await {
+		Ok(true) => {
+			return HttpResponse::Conflict().body("A challenge with this title already exists.");
+		}
+		Ok(false) => (),
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	}
+	if !flag.is_empty() {
+		challenge.flag_hash = hash(&flag, DEFAULT_COST).unwrap();
+	}
+
+	match db.create_challenge(challenge).await {
+		Ok(result) => HttpResponse::Ok().json(result),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Edit a challenge, keeping its flag unless a new one is given
+#[post("/ctf/challenges/edit/{id}")]
+pub async fn edit_challenge(
+	db: Data<Database>,
+	id: web::Path<String>,
+	request: Json<ChallengeRequest>
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	// Validate the request
+	match request.validate() {
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	}
+
+	if ObjectId::parse_str(id.as_str()).is_err() {
+		return HttpResponse::BadRequest().body("Invalid ID.");
+	}
+
+	let existing = match db.get_challenge_by_id(id.to_string()).await {
+		Ok(Some(challenge)) => challenge,
+		Ok(None) => {
+			return HttpResponse::NotFound().body("Challenge not found.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	let flag = request.flag.trim().to_string();
+	let mut challenge = match Challenge::try_from(request.into_inner()) {
+		Ok(challenge) => challenge,
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	};
+	challenge._id = existing._id;
+	if let Some(competition_id) = challenge.competition_id {
+		match db.get_competition_by_id(competition_id).await {
+			Ok(Some(_)) => (),
+			Ok(None) => {
+				return HttpResponse::BadRequest().body("Competition not found.");
+			}
+			Err(err) => {
+				return HttpResponse::InternalServerError().body(err.to_string());
+			}
+		}
+	}
+	match db.challenge_title_is_taken(&challenge.title, challenge._id).await {
+		Ok(true) => {
+			return HttpResponse::Conflict().body("A challenge with this title already exists.");
+		}
+		Ok(false) => (),
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	}
+
+	// A new flag replaces the old one, a regex takes over from a hash, and giving neither keeps the old flag
+	if !flag.is_empty() {
+		challenge.flag_hash = hash(&flag, DEFAULT_COST).unwrap();
+	} else if challenge.flag_regex.is_empty() {
+		challenge.flag_hash = existing.flag_hash;
+		challenge.flag_regex = existing.flag_regex;
+	}
+	if challenge.flag_hash.is_empty() && challenge.flag_regex.is_empty() {
+		return HttpResponse::BadRequest().body("A flag or a flag regex is required.");
+	}
+
+	match db.update_challenge(&challenge).await {
+		Ok(_) => HttpResponse::Ok().json("Challenge updated."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Delete a challenge along with its solves
+#[delete("/ctf/challenges/delete/{id}")]
+pub async fn delete_challenge(
+	db: Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	let challenge_id = match ObjectId::parse_str(id.as_str()) {
+		Ok(challenge_id) => challenge_id,
+		Err(_) => {
+			return HttpResponse::BadRequest().body("Invalid ID.");
+		}
+	};
+
+	match db.delete_challenge(challenge_id).await {
+		Ok(result) if result.deleted_count == 0 =>
+			HttpResponse::NotFound().body("Challenge not found."),
+		Ok(result) => HttpResponse::Ok().json(result),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Submit a flag for a challenge
+#[post("/ctf/submit/{id}")]
+pub async fn submit_flag(
+	db: Data<Database>,
+	id: web::Path<String>,
+	request: Json<FlagSubmission>,
+	req: HttpRequest
+) -> HttpResponse {
+	let acc = match get_verified_account(&db, &req, "submit flags").await {
+		Ok(acc) => acc,
+		Err(response) => {
+			return response;
+		}
+	};
+
+	let (challenge, competition) = match get_open_challenge(&db, &id).await {
+		Ok(challenge) => challenge,
+		Err(response) => {
+			return response;
+		}
+	};
+	let team_id = match check_can_play(&db, competition.as_ref(), &acc).await {
+		Ok(team_id) => team_id,
+		Err(response) => {
+			return response;
+		}
+	};
+
+	let flag = request.flag.trim();
+	if flag.is_empty() || flag.len() > 200 {
+		return HttpResponse::BadRequest().body("Flag should be from 1 to 200 characters.");
+	}
+
+	match db.get_solves_by_account_id(acc._id).await {
+		Ok(solves) if solves.iter().any(|solve| solve.challenge_id == challenge._id) => {
+			return HttpResponse::Ok().json("Already solved.");
+		}
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	}
+
+	// Limit how fast flags can be guessed
+	let since = DateTime::from_millis(DateTime::now().timestamp_millis() - SUBMISSION_WINDOW_MILLIS);
+	match db.count_recent_submissions(challenge._id, acc._id, since).await {
+		Ok(count) if count >= MAX_SUBMISSIONS => {
+			return HttpResponse::TooManyRequests().body(
+				"Too many submissions. Wait a minute before trying again."
+			);
+		}
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	}
+
+	let correct = challenge.check_flag(flag);
+	let submission = Submission {
+		_id: ObjectId::new(),
+		challenge_id: challenge._id,
+		account_id: acc._id,
+		correct,
+		date_created: DateTime::now(),
+	};
+	if let Err(err) = db.create_submission(submission).await {
+		return HttpResponse::InternalServerError().body(err.to_string());
+	}
+	if !correct {
+		return HttpResponse::BadRequest().body("Incorrect flag.");
+	}
+
+	// Credit the solve to the account's team too
+	let solve = Solve {
+		_id: ObjectId::new(),
+		challenge_id: challenge._id,
+		account_id: acc._id,
+		username: acc.username,
+		team_id,
+		date_created: DateTime::now(),
+	};
+	match db.record_solve(solve).await {
+		Ok(true) => HttpResponse::Ok().json("Correct flag."),
+		Ok(false) => HttpResponse::Ok().json("Already solved."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Unlock the next hint of a challenge, taking its cost off the score
+#[post("/ctf/hints/{id}/{index}")]
+pub async fn unlock_hint(
+	db: Data<Database>,
+	path: web::Path<(String, u32)>,
+	req: HttpRequest
+) -> HttpResponse {
+	let (id, index) = path.into_inner();
+	let acc = match get_verified_account(&db, &req, "unlock hints").await {
+		Ok(acc) => acc,
+		Err(response) => {
+			return response;
+		}
+	};
+
+	let (challenge, competition) = match get_open_challenge(&db, &id).await {
+		Ok(challenge) => challenge,
+		Err(response) => {
+			return response;
+		}
+	};
+	let team_id = match check_can_play(&db, competition.as_ref(), &acc).await {
+		Ok(team_id) => team_id,
+		Err(response) => {
+			return response;
+		}
+	};
+	let hint = match challenge.hints.get(index as usize) {
+		Some(hint) => hint,
+		None => {
+			return HttpResponse::NotFound().body("Hint not found.");
+		}
+	};
+
+	// Hints are unlocked in order
+	let unlocks = match db.get_hint_unlocks(challenge._id, acc._id).await {
+		Ok(unlocks) => unlocks,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	if unlocks.iter().any(|unlock| unlock.index == index) {
+		return HttpResponse::Ok().json(&hint.content);
+	}
+	if index > 0 && !unlocks.iter().any(|unlock| unlock.index == index - 1) {
+		return HttpResponse::BadRequest().body("Unlock the previous hint first.");
+	}
+
+	let unlock = HintUnlock {
+		_id: ObjectId::new(),
+		challenge_id: challenge._id,
+		account_id: acc._id,
+		username: acc.username,
+		team_id,
+		index,
+		cost: hint.cost,
+		date_created: DateTime::now(),
+	};
+	match db.unlock_hint(unlock).await {
+		Ok(_) => HttpResponse::Ok().json(&hint.content),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Get who solved a challenge, first solve first
+#[get("/ctf/solves/{id}")]
+pub async fn get_challenge_solves(
+	db: Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	let (challenge, competition) = match get_open_challenge(&db, &id).await {
+		Ok(challenge) => challenge,
+		Err(response) => {
+			return response;
+		}
+	};
+
+	let (freeze_at, frozen) = match get_scoreboard_freeze(&db, &req, competition.as_ref()).await {
+		Ok(freeze) => freeze,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	match db.get_solves(challenge._id).await {
+		Ok(solves) => {
+			// Solves after the freeze stay hidden along with the scoreboard
+			let solves: Vec<SolveRequest> = solves
+				.into_iter()
+				.filter(|solve| !frozen || Some(solve.date_created) < freeze_at)
+				.map(SolveRequest::from)
+				.collect();
+			HttpResponse::Ok().json(solves)
+		}
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Get the individual or team scoreboard, which stops updating for everyone but admins once it is frozen
+#[get("/ctf/scoreboard")]
+pub async fn get_scoreboard(
+	db: Data<Database>,
+	query: web::Query<ScoreboardArgs>,
+	req: HttpRequest
+) -> HttpResponse {
+	let competition = match get_competition(&db, &query.competition).await {
+		Ok(competition) => competition,
+		Err(response) => {
+			return response;
+		}
+	};
+	let (freeze_at, frozen) = match get_scoreboard_freeze(&db, &req, competition.as_ref()).await {
+		Ok(freeze) => freeze,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	let challenges = match db.get_challenges(competition.map(|competition| competition._id)).await {
+		Ok(challenges) => challenges,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	let solves = match db.get_all_solves().await {
+		Ok(solves) => solves,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	let unlocks = match db.get_all_hint_unlocks().await {
+		Ok(unlocks) => unlocks,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	let teams: Option<HashMap<ObjectId, String>> = if query.teams {
+		match db.get_teams().await {
+			Ok(teams) => Some(teams.into_iter().map(|team| (team._id, team.name)).collect()),
+			Err(err) => {
+				return HttpResponse::InternalServerError().body(err.to_string());
+			}
+		}
+	} else {
+		None
+	};
+
+	HttpResponse::Ok().json(build_scoreboard(&challenges, solves, unlocks, teams.as_ref(), frozen, freeze_at))
+}
+
+// Set when the public scoreboard of the practice challenges freezes, or unfreeze it
+#[post("/ctf/scoreboard/freeze")]
+pub async fn set_scoreboard_freeze(db: Data<Database>, request: Json<FreezeRequest>) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	let freeze_at = match parse_optional_date(&request.freeze_at) {
+		Ok(freeze_at) => freeze_at,
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	};
+
+	match db.set_scoreboard_freeze(freeze_at).await {
+		Ok(_) if freeze_at.is_some() => HttpResponse::Ok().json("Scoreboard freeze set."),
+		Ok(_) => HttpResponse::Ok().json("Scoreboard unfrozen."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Get every competition
+#[get("/ctf/competitions")]
+pub async fn get_competitions(db: Data<Database>) -> HttpResponse {
+	match db.get_competitions().await {
+		Ok(competitions) => {
+			let competitions: Vec<CompetitionRequest> = competitions
+				.into_iter()
+				.map(CompetitionRequest::from)
+				.collect();
+			HttpResponse::Ok().json(competitions)
+		}
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Create a competition
+#[post("/ctf/competitions/create")]
+pub async fn create_competition(
+	db: Data<Database>,
+	request: Json<CompetitionRequest>
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	// Validate the request
+	match request.validate() {
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	}
+
+	match Competition::try_from(request.into_inner()) {
+		Ok(competition) =>
+			match db.create_competition(competition).await {
+				Ok(result) => HttpResponse::Ok().json(result),
+				Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+			}
+		Err(err) => HttpResponse::BadRequest().body(err.to_string()),
+	}
+}
+
+// Edit a competition
+#[post("/ctf/competitions/edit/{id}")]
+pub async fn edit_competition(
+	db: Data<Database>,
+	id: web::Path<String>,
+	request: Json<CompetitionRequest>
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	// Validate the request
+	match request.validate() {
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	}
+
+	let competition_id = match ObjectId::parse_str(id.as_str()) {
+		Ok(competition_id) => competition_id,
+		Err(_) => {
+			return HttpResponse::BadRequest().body("Invalid ID.");
+		}
+	};
+	let mut competition = match Competition::try_from(request.into_inner()) {
+		Ok(competition) => competition,
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	};
+	competition._id = competition_id;
+
+	match db.update_competition(&competition).await {
+		Ok(result) if result.matched_count == 0 =>
+			HttpResponse::NotFound().body("Competition not found."),
+		Ok(_) => HttpResponse::Ok().json("Competition updated."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Delete a competition that has no challenges left
+#[delete("/ctf/competitions/delete/{id}")]
+pub async fn delete_competition(
+	db: Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	let competition_id = match ObjectId::parse_str(id.as_str()) {
+		Ok(competition_id) => competition_id,
+		Err(_) => {
+			return HttpResponse::BadRequest().body("Invalid ID.");
+		}
+	};
+
+	match db.competition_has_challenges(competition_id).await {
+		Ok(true) => {
+			return HttpResponse::BadRequest().body(
+				"Move or delete the competition's challenges first."
+			);
+		}
+		Ok(false) => (),
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	}
+
+	match db.delete_competition(competition_id).await {
+		Ok(result) if result.deleted_count == 0 =>
+			HttpResponse::NotFound().body("Competition not found."),
+		Ok(result) => HttpResponse::Ok().json(result),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+use actix_web::{ get, post, web::{ self, Data, Json }, HttpRequest, HttpResponse };
+use mongodb::bson::{ oid::ObjectId, DateTime };
+use serde::Deserialize;
+use validator::Validate;
+
+use crate::{
+	models::{
+		evaluation::{ CriterionAverage, Evaluation, EvaluationRequest, RankedApplication },
+		rubric::{ Rubric, RubricRequest },
+	},
+	services::db::Database,
+	utilities::auth::{ get_token, is_admin },
+};
+
+// Define the RankingArgs struct
+#[derive(Deserialize)]
+struct RankingArgs {
+	token: String,
+	exec_type: String,
+}
+
+// Get the rubric of an exec type
+#[get("/executive_member/rubric/{exec_type}")]
+pub async fn get_rubric(
+	db: Data<Database>,
+	exec_type: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	match db.get_rubric(&exec_type).await {
+		Ok(Some(rubric)) => HttpResponse::Ok().json(RubricRequest::from(rubric)),
+		Ok(None) => HttpResponse::NotFound().body("Rubric not found."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Create or replace the rubric of an exec type
+#[post("/executive_member/rubric/set")]
+pub async fn set_rubric(db: Data<Database>, request: Json<RubricRequest>) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	// Validate the request
+	match request.validate() {
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	}
+
+	match db.set_rubric(Rubric::from(request.into_inner())).await {
+		Ok(result) => HttpResponse::Ok().json(result),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Score an executive application with the rubric of its exec type
+#[post("/executive_member/evaluate/{id}")]
+pub async fn evaluate_executive_member(
+	db: Data<Database>,
+	id: web::Path<String>,
+	request: Json<EvaluationRequest>
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	// Validate the request
+	match request.validate() {
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	}
+
+	if ObjectId::parse_str(id.as_str()).is_err() {
+		return HttpResponse::BadRequest().body("Invalid ID.");
+	}
+
+	// Get the application
+	let member = match db.get_executive_member_by_id(id.to_string()).await {
+		Ok(Some(member)) => member,
+		Ok(None) => {
+			return HttpResponse::NotFound().body("Application not found.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	// Check the scores against the rubric
+	let rubric = match db.get_rubric(&member.exec_type).await {
+		Ok(Some(rubric)) => rubric,
+		Ok(None) => {
+			return HttpResponse::BadRequest().body("There is no rubric for this exec type.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	if let Err(err) = rubric.check_scores(&request.scores) {
+		return HttpResponse::BadRequest().body(err);
+	}
+
+	// Save the evaluation
+	let evaluation = Evaluation {
+		_id: ObjectId::new(),
+		application_id: member._id,
+		exec_type: member.exec_type,
+		reviewer: request.reviewer.clone(),
+		scores: request.scores.clone(),
+		comment: request.comment.clone(),
+		date_created: DateTime::now(),
+	};
+	match db.save_evaluation(evaluation).await {
+		Ok(result) => HttpResponse::Ok().json(result),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Get every reviewer's evaluation of an executive application
+#[get("/executive_member/evaluations/{id}")]
+pub async fn get_evaluations(
+	db: Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	if ObjectId::parse_str(id.as_str()).is_err() {
+		return HttpResponse::BadRequest().body("Invalid ID.");
+	}
+
+	match db.get_evaluations_by_application_id(id.to_string()).await {
+		Ok(evaluations) => {
+			let evaluations: Vec<EvaluationRequest> = evaluations
+				.into_iter()
+				.map(EvaluationRequest::from)
+				.collect();
+			HttpResponse::Ok().json(evaluations)
+		}
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Rank the executive applications of an exec type by their average weighted score
+#[post("/executive_member/ranked")]
+pub async fn get_ranked_executive_members(
+	db: Data<Database>,
+	request: Json<RankingArgs>
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	let rubric = match db.get_rubric(&request.exec_type).await {
+		Ok(Some(rubric)) => rubric,
+		Ok(None) => {
+			return HttpResponse::NotFound().body("Rubric not found.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	let (members, evaluations) = match
+		(
+			db.get_executive_members_by_exec_type(&request.exec_type).await,
+			db.get_evaluations_by_exec_type(&request.exec_type).await,
+		)
+	{
+		(Ok(members), Ok(evaluations)) => (members, evaluations),
+		(Err(err), _) | (_, Err(err)) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	// Group the evaluations by application
+	let mut evaluations_by_application: HashMap<ObjectId, Vec<Evaluation>> = HashMap::new();
+	for evaluation in evaluations {
+		evaluations_by_application.entry(evaluation.application_id).or_default().push(evaluation);
+	}
+
+	// Aggregate the scores of every application
+	let mut ranked: Vec<RankedApplication> = members
+		.into_iter()
+		.map(|member| {
+			let evaluations = evaluations_by_application.remove(&member._id).unwrap_or_default();
+			let scores: Vec<f64> = evaluations
+				.iter()
+				.filter_map(|evaluation| evaluation.weighted_score(&rubric))
+				.collect();
+			let score = if scores.is_empty() {
+				0.0
+			} else {
+				scores.iter().sum::<f64>() / (scores.len() as f64)
+			};
+
+			// Average each criterion separately so reviewers can see where an applicant stands out
+			let criteria = rubric.criteria
+				.iter()
+				.map(|criterion| {
+					let criterion_scores: Vec<f64> = evaluations
+						.iter()
+						.filter_map(|evaluation| {
+							evaluation.scores
+								.iter()
+								.find(|score| score.criterion == criterion.name)
+								.map(|score| score.score as f64)
+						})
+						.collect();
+					CriterionAverage {
+						criterion: criterion.name.clone(),
+						average: if criterion_scores.is_empty() {
+							0.0
+						} else {
+							criterion_scores.iter().sum::<f64>() / (criterion_scores.len() as f64)
+						},
+						max_score: criterion.max_score,
+					}
+				})
+				.collect();
+
+			RankedApplication {
+				rank: 0,
+				id: member._id.to_string(),
+				full_name: member.full_name,
+				email: member.email,
+				grade: member.grade,
+				exec_type: member.exec_type,
+				status: member.status,
+				score,
+				review_count: scores.len() as u32,
+				criteria,
+			}
+		})
+		.collect();
+
+	// Sort by score, putting applications nobody has reviewed yet last
+	ranked.sort_by(|a, b| {
+		(b.review_count > 0)
+			.cmp(&(a.review_count > 0))
+			.then(b.score.total_cmp(&a.score))
+			.then(b.review_count.cmp(&a.review_count))
+	});
+	for (index, application) in ranked.iter_mut().enumerate() {
+		application.rank = (index as u32) + 1;
+	}
+
+	HttpResponse::Ok().json(ranked)
+}
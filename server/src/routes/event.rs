@@ -0,0 +1,363 @@
+use actix_web::{ delete, get, post, web::{ self, Data, Json }, HttpRequest, HttpResponse };
+use mongodb::{ bson::{ oid::ObjectId, DateTime }, error::Error };
+use validator::Validate;
+
+use crate::{
+	models::{
+		account::Account,
+		event::{ Event, EventRequest, Rsvp, RsvpRequest },
+	},
+	services::{ calendar::local_time, db::Database, email::{ send_email, EmailTemplate } },
+	utilities::{
+		auth::{ get_signed_in_account, get_token, get_verified_account, is_admin },
+		claims::Token,
+		pagination_args::EventPaginationArgs,
+	},
+};
+
+// Add the RSVP counts, and the RSVP of the signed-in account, to an event
+async fn event_with_rsvps(
+	db: &Database,
+	event: Event,
+	acc: Option<&Account>
+) -> Result<EventRequest, Error> {
+	let going = db.count_rsvps(event._id, "going").await?;
+	let waitlisted = db.count_rsvps(event._id, "waitlisted").await?;
+	let rsvp = match acc {
+		Some(acc) => db.get_rsvp(event._id, acc._id).await?,
+		None => None,
+	};
+
+	let mut event = EventRequest::from(event);
+	event.going = going;
+	event.waitlisted = waitlisted;
+	event.rsvp = rsvp.map(|rsvp| rsvp.status).unwrap_or_default();
+	Ok(event)
+}
+
+// Fill the free spots of an event from its waitlist and let the promoted people know
+async fn promote_waitlist(db: &Database, event: &Event) {
+	let promoted = match db.promote_waitlist(event).await {
+		Ok(promoted) => promoted,
+		Err(err) => {
+			println!("Error promoting the waitlist: {}", err);
+			return;
+		}
+	};
+
+	for rsvp in promoted {
+		let acc = match db.get_account_by_id(rsvp.account_id.to_hex()).await {
+			Ok(Some(acc)) => acc,
+			_ => {
+				continue;
+			}
+		};
+		let email = EmailTemplate::WaitlistPromoted {
+			username: acc.username.clone(),
+			title: event.title.clone(),
+			time: local_time(event.starts_at),
+			location: event.location.clone(),
+		};
+		if let Err(err) = send_email(acc.email.clone(), &email).await {
+			println!("Error sending email: {}", err);
+		}
+	}
+}
+
+// Get the upcoming or past events with pagination
+#[post("/events/get")]
+pub async fn get_events(
+	db: Data<Database>,
+	request: Json<EventPaginationArgs>,
+	req: HttpRequest
+) -> HttpResponse {
+	let acc = get_signed_in_account(&db, &req).await;
+
+	let events = match db.get_events(request.page, request.limit, request.past).await {
+		Ok(events) => events,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	let mut result: Vec<EventRequest> = Vec::new();
+	for event in events {
+		match event_with_rsvps(&db, event, acc.as_ref()).await {
+			Ok(event) => result.push(event),
+			Err(err) => {
+				return HttpResponse::InternalServerError().body(err.to_string());
+			}
+		}
+	}
+	HttpResponse::Ok().json(result)
+}
+
+// Get the amount of upcoming or past events
+#[post("/events/get/amount")]
+pub async fn get_amount_of_events(
+	db: Data<Database>,
+	request: Json<EventPaginationArgs>
+) -> HttpResponse {
+	match db.get_amount_of_events(request.past).await {
+		Ok(count) => HttpResponse::Ok().json(count),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Get an event by its ID
+#[get("/events/get/{id}")]
+pub async fn get_event_by_id(
+	db: Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	if ObjectId::parse_str(id.as_str()).is_err() {
+		return HttpResponse::BadRequest().body("Invalid ID.");
+	}
+
+	let acc = get_signed_in_account(&db, &req).await;
+	match db.get_event_by_id(id.to_string()).await {
+		Ok(Some(event)) =>
+			match event_with_rsvps(&db, event, acc.as_ref()).await {
+				Ok(event) => HttpResponse::Ok().json(event),
+				Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+			}
+		Ok(None) => HttpResponse::NotFound().body("Event not found."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Create an event
+#[post("/events/create")]
+pub async fn create_event(db: Data<Database>, request: Json<EventRequest>) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	// Validate the request
+	match request.validate() {
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	}
+
+	match Event::try_from(request.into_inner()) {
+		Ok(event) =>
+			match db.create_event(event).await {
+				Ok(result) => HttpResponse::Ok().json(result),
+				Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+			}
+		Err(err) => HttpResponse::BadRequest().body(err.to_string()),
+	}
+}
+
+// Edit an event
+#[post("/events/edit/{id}")]
+pub async fn edit_event(
+	db: Data<Database>,
+	id: web::Path<String>,
+	request: Json<EventRequest>
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	// Validate the request
+	match request.validate() {
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	}
+
+	if ObjectId::parse_str(id.as_str()).is_err() {
+		return HttpResponse::BadRequest().body("Invalid ID.");
+	}
+
+	let existing = match db.get_event_by_id(id.to_string()).await {
+		Ok(Some(event)) => event,
+		Ok(None) => {
+			return HttpResponse::NotFound().body("Event not found.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	if existing.cancelled {
+		return HttpResponse::BadRequest().body("The event has been cancelled.");
+	}
+
+	let mut event = match Event::try_from(request.into_inner()) {
+		Ok(event) => event,
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	};
+	event._id = existing._id;
+	if let Err(err) = db.update_event(existing._id, &event).await {
+		return HttpResponse::InternalServerError().body(err.to_string());
+	}
+
+	// A bigger capacity lets people off the waitlist
+	promote_waitlist(&db, &event).await;
+
+	HttpResponse::Ok().json("Event updated.")
+}
+
+// Cancel an event, keeping it listed so people know it is not happening
+#[post("/events/cancel/{id}")]
+pub async fn cancel_event(
+	db: Data<Database>,
+	id: web::Path<String>,
+	request: Json<Token>
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	let event_id = match ObjectId::parse_str(id.as_str()) {
+		Ok(event_id) => event_id,
+		Err(_) => {
+			return HttpResponse::BadRequest().body("Invalid ID.");
+		}
+	};
+
+	match db.cancel_event(event_id).await {
+		Ok(result) if result.matched_count == 0 => HttpResponse::NotFound().body("Event not found."),
+		Ok(_) => HttpResponse::Ok().json("Event cancelled."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Get everyone who RSVP'd to an event
+#[get("/events/rsvps/{id}")]
+pub async fn get_event_rsvps(
+	db: Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	let event_id = match ObjectId::parse_str(id.as_str()) {
+		Ok(event_id) => event_id,
+		Err(_) => {
+			return HttpResponse::BadRequest().body("Invalid ID.");
+		}
+	};
+
+	match db.get_rsvps(event_id).await {
+		Ok(rsvps) => {
+			let rsvps: Vec<RsvpRequest> = rsvps.into_iter().map(RsvpRequest::from).collect();
+			HttpResponse::Ok().json(rsvps)
+		}
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// RSVP to an event, joining the waitlist if it is full
+#[post("/events/rsvp/{id}")]
+pub async fn rsvp_to_event(
+	db: Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	let acc = match get_verified_account(&db, &req, "RSVP").await {
+		Ok(acc) => acc,
+		Err(response) => {
+			return response;
+		}
+	};
+
+	if ObjectId::parse_str(id.as_str()).is_err() {
+		return HttpResponse::BadRequest().body("Invalid ID.");
+	}
+
+	let event = match db.get_event_by_id(id.to_string()).await {
+		Ok(Some(event)) => event,
+		Ok(None) => {
+			return HttpResponse::NotFound().body("Event not found.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	if !event.is_open() {
+		return HttpResponse::BadRequest().body("The event is not taking RSVPs.");
+	}
+
+	match db.get_rsvp(event._id, acc._id).await {
+		Ok(Some(rsvp)) => {
+			return HttpResponse::Ok().json(rsvp.status);
+		}
+		Ok(None) => (),
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	}
+
+	// The spot is taken when the RSVP is saved, so two people can't get the last one
+	let rsvp = Rsvp {
+		_id: ObjectId::new(),
+		event_id: event._id,
+		account_id: acc._id,
+		username: acc.username,
+		status: String::new(),
+		date_created: DateTime::now(),
+	};
+	match db.create_rsvp(rsvp).await {
+		Ok(rsvp) => HttpResponse::Ok().json(rsvp.status),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Cancel an RSVP, giving the spot to the next person on the waitlist
+#[delete("/events/rsvp/{id}")]
+pub async fn cancel_rsvp(
+	db: Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	let acc = match get_signed_in_account(&db, &req).await {
+		Some(acc) => acc,
+		None => {
+			return HttpResponse::Unauthorized().body("Unauthorized.");
+		}
+	};
+
+	if ObjectId::parse_str(id.as_str()).is_err() {
+		return HttpResponse::BadRequest().body("Invalid ID.");
+	}
+
+	let event = match db.get_event_by_id(id.to_string()).await {
+		Ok(Some(event)) => event,
+		Ok(None) => {
+			return HttpResponse::NotFound().body("Event not found.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	match db.delete_rsvp(event._id, acc._id).await {
+		Ok(None) => {
+			return HttpResponse::NotFound().body("RSVP not found.");
+		}
+		Ok(Some(_)) => (),
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	}
+
+	if event.is_open() {
+		promote_waitlist(&db, &event).await;
+	}
+
+	HttpResponse::Ok().json("RSVP cancelled.")
+}
@@ -1,10 +1,26 @@
 use crate::{
-	models::executive_member::{ ExecutiveMember, ExecutiveMemberRequest },
-	services::db::Database,
-	utilities::pagination_args::AdminPaginationArgs,
+	models::{
+		application::{
+			default_status,
+			ApplicationEdit,
+			ApplicationEditRequest,
+			EditArgs,
+			FieldChange,
+			ReviewerNote,
+			ReviewerNoteRequest,
+			StatusChangeRequest,
+		},
+		executive_member::{ ExecutiveMember, ExecutiveMemberRequest },
+	},
+	services::{ db::Database, email::{ client_url, send_email, EmailTemplate } },
+	utilities::{
+		auth::{ generate_link_token, get_application_editor, get_signed_in_account, is_admin },
+		pagination_args::AdminPaginationArgs,
+	},
 };
-use actix_web::{ get, post, web::{ self, Data, Json }, HttpResponse };
+use actix_web::{ get, post, web::{ self, Data, Json }, HttpRequest, HttpResponse };
 use bcrypt::{ hash, verify, DEFAULT_COST };
+use mongodb::bson::{ doc, oid::ObjectId, DateTime };
 use validator::Validate;
 
 // Get the executive member by full name or email
@@ -54,6 +70,7 @@ pub async fn get_all_executive_members(
 				.into_iter()
 				.rev()
 				.map(|member| ExecutiveMemberRequest {
+					id: member._id.to_string(),
 					full_name: member.full_name.clone(),
 					email: member.email.clone(),
 					grade: member.grade.clone(),
@@ -63,6 +80,16 @@ pub async fn get_all_executive_members(
 					portfolio: member.portfolio.clone(),
 					extra: member.extra.clone(),
 					date_created: member.date_created.to_string(),
+					status: member.status.clone(),
+					reviewer_notes: member.reviewer_notes
+						.into_iter()
+						.map(ReviewerNoteRequest::from)
+						.collect(),
+					edit_history: member.edit_history
+						.into_iter()
+						.map(ApplicationEditRequest::from)
+						.collect(),
+					term_ended: member.term_ended,
 				})
 				.collect();
 			HttpResponse::Ok().json(executive_members)
@@ -75,7 +102,8 @@ pub async fn get_all_executive_members(
 #[post("/executive_member/post")]
 pub async fn create_executive_member(
 	db: Data<Database>,
-	request: Json<ExecutiveMemberRequest>
+	request: Json<ExecutiveMemberRequest>,
+	req: HttpRequest
 ) -> HttpResponse {
 	// Validate the request
 	match request.validate() {
@@ -85,23 +113,277 @@ pub async fn create_executive_member(
 		}
 	}
 
-	// Create the executive member
-	match
-		db.create_executive_member(
-			ExecutiveMember::try_from(ExecutiveMemberRequest {
+	// Check if the form is accepting responses
+	match db.is_form_open("executive", &request.exec_type).await {
+		Ok(true) => (),
+		Ok(false) => {
+			return HttpResponse::Forbidden().body("The form is not accepting responses.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	}
+
+	// Create the executive member, linked to the account that submitted it
+	let mut member = ExecutiveMember::try_from(ExecutiveMemberRequest {
+		id: String::new(),
+		full_name: request.full_name.clone(),
+		email: request.email.clone(),
+		grade: request.grade,
+		exec_type: request.exec_type.clone(),
+		why: request.why.clone(),
+		experience: request.experience.clone(),
+		portfolio: request.portfolio.clone(),
+		extra: request.extra.clone(),
+		date_created: request.date_created.clone(),
+		status: default_status(),
+		reviewer_notes: Vec::new(),
+		edit_history: Vec::new(),
+		term_ended: false,
+	}).expect("Error converting ExecutiveMemberRequest to ExecutiveMember.");
+	// Only link an account that has proven it owns the application's email, the rest are linked when the
+	// owner of the email verifies their account
+	member.account_id = get_signed_in_account(&db, &req).await
+		.filter(|acc| acc.verified && acc.email == member.email)
+		.map(|acc| acc._id);
+
+	// Only the hash of the edit link's secret is kept
+	let edit_token = generate_link_token();
+	member.edit_token = hash(&edit_token, DEFAULT_COST).unwrap();
+	let edit_url = format!("{}/apply/edit/executive/{}?token={}", client_url(), member._id, edit_token);
+
+	match db.create_executive_member(member).await {
+		Ok(exec_mem) => {
+			// Let the applicant know their application was received and how to change it
+			let email = EmailTemplate::ApplicationReceived {
 				full_name: request.full_name.clone(),
-				email: request.email.clone(),
-				grade: request.grade.clone(),
-				exec_type: request.exec_type.clone(),
-				why: request.why.clone(),
-				experience: request.experience.clone(),
-				portfolio: request.portfolio.clone(),
-				extra: request.extra.clone(),
-				date_created: request.date_created.clone(),
-			}).expect("Error converting ExecutiveMemberRequest to ExecutiveMember.")
-		).await
+				form: "executive".to_string(),
+				edit_url,
+			};
+			if let Err(err) = send_email(request.email.clone(), &email).await {
+				println!("Error sending email: {}", err);
+			}
+
+			HttpResponse::Ok().json(exec_mem)
+		}
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Get an application for its applicant to edit
+#[get("/executive_member/edit/{id}")]
+pub async fn get_executive_member_for_edit(
+	db: Data<Database>,
+	id: web::Path<String>,
+	query: web::Query<EditArgs>,
+	req: HttpRequest
+) -> HttpResponse {
+	if ObjectId::parse_str(id.as_str()).is_err() {
+		return HttpResponse::BadRequest().body("Invalid ID.");
+	}
+
+	let member = match db.get_executive_member_by_id(id.to_string()).await {
+		Ok(Some(member)) => member,
+		Ok(None) => {
+			return HttpResponse::NotFound().body("Application not found.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	if
+		get_application_editor(&db, &req, member.account_id, &member.edit_token, &query.token).await
+			.is_none()
+	{
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	// The reviewers' notes stay private
+	HttpResponse::Ok().json(ExecutiveMemberRequest {
+		id: member._id.to_string(),
+		full_name: member.full_name,
+		email: member.email,
+		grade: member.grade,
+		exec_type: member.exec_type,
+		why: member.why,
+		experience: member.experience,
+		portfolio: member.portfolio,
+		extra: member.extra,
+		date_created: member.date_created.to_string(),
+		status: member.status,
+		reviewer_notes: Vec::new(),
+		edit_history: member.edit_history
+			.into_iter()
+			.map(ApplicationEditRequest::from)
+			.collect(),
+		term_ended: member.term_ended,
+	})
+}
+
+// Let an applicant edit their application while the form is open
+#[post("/executive_member/edit/{id}")]
+pub async fn edit_executive_member(
+	db: Data<Database>,
+	id: web::Path<String>,
+	query: web::Query<EditArgs>,
+	request: Json<ExecutiveMemberRequest>,
+	req: HttpRequest
+) -> HttpResponse {
+	// Validate the request
+	match request.validate() {
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	}
+
+	if ObjectId::parse_str(id.as_str()).is_err() {
+		return HttpResponse::BadRequest().body("Invalid ID.");
+	}
+
+	// Get the application and check who is editing it
+	let member = match db.get_executive_member_by_id(id.to_string()).await {
+		Ok(Some(member)) => member,
+		Ok(None) => {
+			return HttpResponse::NotFound().body("Application not found.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	let editor = match
+		get_application_editor(&db, &req, member.account_id, &member.edit_token, &query.token).await
 	{
-		Ok(exec_mem) => HttpResponse::Ok().json(exec_mem),
+		Some(editor) => editor,
+		None => {
+			return HttpResponse::Unauthorized().body("Unauthorized.");
+		}
+	};
+
+	// Decided applications and past terms can't change
+	if member.term_ended || ["accepted", "rejected", "withdrawn"].contains(&member.status.as_str()) {
+		return HttpResponse::BadRequest().body("The application can no longer be changed.");
+	}
+	match db.is_form_open("executive", &member.exec_type).await {
+		Ok(true) => (),
+		Ok(false) => {
+			return HttpResponse::Forbidden().body("The form is not accepting responses.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	}
+	if request.email != member.email {
+		return HttpResponse::BadRequest().body("The email of an application can't be changed.");
+	}
+	if request.exec_type != member.exec_type {
+		return HttpResponse::BadRequest().body("The exec type of an application can't be changed.");
+	}
+	if
+		request.full_name != member.full_name &&
+		db.exec_mem_name_is_taken(&request.full_name, member._id).await
+	{
+		return HttpResponse::BadRequest().body("Member already exists.");
+	}
+
+	// Keep what changed for the reviewers
+	let changes: Vec<FieldChange> = [
+		FieldChange::between("full_name", member.full_name, request.full_name.clone()),
+		FieldChange::between("grade", member.grade.to_string(), request.grade.to_string()),
+		FieldChange::between("why", member.why, request.why.clone()),
+		FieldChange::between("experience", member.experience, request.experience.clone()),
+		FieldChange::between("portfolio", member.portfolio, request.portfolio.clone()),
+		FieldChange::between("extra", member.extra, request.extra.clone()),
+	]
+		.into_iter()
+		.flatten()
+		.collect();
+	if changes.is_empty() {
+		return HttpResponse::Ok().json("No changes.");
+	}
+
+	let fields = doc! {
+		"full_name": &request.full_name,
+		"grade": request.grade as i32,
+		"why": &request.why,
+		"experience": &request.experience,
+		"portfolio": &request.portfolio,
+		"extra": &request.extra,
+	};
+	let edit = ApplicationEdit {
+		editor,
+		changes,
+		date_created: DateTime::now(),
+	};
+	match db.edit_executive_member(member._id, fields, edit.clone()).await {
+		Ok(_) => HttpResponse::Ok().json(ApplicationEditRequest::from(edit)),
 		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
 	}
 }
+
+// Change the status of a executive member's application
+#[post("/executive_member/status/{id}")]
+pub async fn change_executive_member_status(
+	db: Data<Database>,
+	id: web::Path<String>,
+	request: Json<StatusChangeRequest>
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	// Validate the request
+	match request.validate() {
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	}
+
+	if ObjectId::parse_str(id.as_str()).is_err() {
+		return HttpResponse::BadRequest().body("Invalid ID.");
+	}
+
+	// Get the application
+	let member = match db.get_executive_member_by_id(id.to_string()).await {
+		Ok(Some(member)) => member,
+		Ok(None) => {
+			return HttpResponse::NotFound().body("Application not found.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	// Withdrawn applications are final
+	if member.status == "withdrawn" {
+		return HttpResponse::BadRequest().body("The application has been withdrawn.");
+	}
+
+	// Record the status change and the reviewer's note
+	let status_changed = member.status != request.status;
+	let note = ReviewerNote {
+		status: request.status.clone(),
+		note: request.note.clone(),
+		date_created: DateTime::now(),
+	};
+	if let Err(err) = db.update_executive_member_status(id.to_string(), note).await {
+		return HttpResponse::InternalServerError().body(err.to_string());
+	}
+
+	// Let the applicant know their status changed
+	if status_changed {
+		let email = EmailTemplate::ApplicationDecision {
+			full_name: member.full_name.clone(),
+			form: "executive".to_string(),
+			status: request.status.clone(),
+			note: request.message.clone(),
+		};
+		if let Err(err) = send_email(member.email.clone(), &email).await {
+			println!("Error sending email: {}", err);
+		}
+	}
+
+	HttpResponse::Ok().json(request.status.clone())
+}
@@ -0,0 +1,123 @@
+use actix_web::{ http::header, post, web::{ self, Data, Json }, HttpResponse };
+use futures_util::{ stream, StreamExt, TryStreamExt };
+use mongodb::{ error::Error, Cursor };
+use serde::{ de::DeserializeOwned, Deserialize };
+
+use crate::{
+	models::{
+		account::Account,
+		executive_member::ExecutiveMember,
+		general_member::GeneralMember,
+	},
+	services::{ db::Database, export::{ csv_line, row, select_columns, xlsx, Exportable } },
+	utilities::auth::is_admin,
+};
+
+// Define the ExportArgs struct (the same search and field as the paginated admin lists)
+#[derive(Deserialize)]
+struct ExportArgs {
+	token: String,
+	#[serde(default)]
+	search: String,
+	#[serde(default)]
+	field: String,
+	#[serde(default)]
+	columns: Vec<String>,
+}
+
+// Send the records of a cursor as a CSV or XLSX file
+async fn export_cursor<T>(
+	cursor: Result<Cursor<T>, Error>,
+	columns: &[String],
+	name: &str,
+	format: &str
+) -> HttpResponse
+	where T: Exportable + DeserializeOwned + Send + Sync + Unpin + 'static
+{
+	// Get the columns to export
+	let columns = match select_columns::<T>(columns) {
+		Ok(columns) => columns,
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err);
+		}
+	};
+	let cursor = match cursor {
+		Ok(cursor) => cursor,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	let disposition = format!("attachment; filename=\"{}.{}\"", name, format);
+
+	if format == "csv" {
+		// Stream the rows as they come out of the database
+		let header = csv_line(&columns);
+		let header = stream::once(async move { Ok::<_, Error>(header) });
+		let rows = cursor.map_ok(move |item| csv_line(&row(&item, &columns)));
+
+		return HttpResponse::Ok()
+			.content_type("text/csv; charset=utf-8")
+			.insert_header((header::CONTENT_DISPOSITION, disposition))
+			.streaming(header.chain(rows));
+	}
+
+	// XLSX files have to be built in memory
+	let items: Vec<T> = match cursor.try_collect().await {
+		Ok(items) => items,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	let rows = items
+		.iter()
+		.map(|item| row(item, &columns))
+		.collect();
+
+	match xlsx(name, &columns, rows) {
+		Ok(buffer) =>
+			HttpResponse::Ok()
+				.content_type("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
+				.insert_header((header::CONTENT_DISPOSITION, disposition))
+				.body(buffer),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Export every general member, executive member, or account matching the search as CSV or XLSX
+#[post("/export/{collection}/{format}")]
+pub async fn export_records(
+	db: Data<Database>,
+	path: web::Path<(String, String)>,
+	request: Json<ExportArgs>
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	let (collection, format) = path.into_inner();
+	if format != "csv" && format != "xlsx" {
+		return HttpResponse::BadRequest().body("Invalid format. Must be csv or xlsx.");
+	}
+
+	let ExportArgs { search, field, columns, .. } = request.into_inner();
+	match collection.as_str() {
+		"general_members" => {
+			let cursor = db.export_general_members(search, field).await;
+			export_cursor::<GeneralMember>(cursor, &columns, "general_members", &format).await
+		}
+		"executive_members" => {
+			let cursor = db.export_executive_members(search, field).await;
+			export_cursor::<ExecutiveMember>(cursor, &columns, "executive_members", &format).await
+		}
+		"accounts" => {
+			// Password hashes are never exported, so they can't be searched either
+			if field == "password" {
+				return HttpResponse::BadRequest().body("Accounts can't be searched by password.");
+			}
+			let cursor = db.export_accounts(search, field).await;
+			export_cursor::<Account>(cursor, &columns, "accounts", &format).await
+		}
+		_ => HttpResponse::NotFound().body("Collection not found."),
+	}
+}
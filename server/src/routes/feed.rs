@@ -0,0 +1,186 @@
+use std::{
+	cmp::Reverse,
+	collections::hash_map::DefaultHasher,
+	env::var,
+	hash::{ Hash, Hasher },
+	time::{ Duration, SystemTime, UNIX_EPOCH },
+};
+use actix_web::{
+	get,
+	http::header::{ self, HttpDate },
+	web::{ self, Data },
+	HttpRequest,
+	HttpResponse,
+};
+use mongodb::bson::Bson;
+
+use crate::services::{ db::Database, feed::{ to_chrono, Feed, FeedEntry } };
+
+// The maximum amount of entries in a feed
+const FEED_SIZE: usize = 50;
+
+// Create the feed entries of the live announcements
+async fn announcement_entries(db: &Database) -> Result<Vec<FeedEntry>, mongodb::error::Error> {
+	let announcements = db.get_live_announcements().await?;
+	let link = format!("{}/forum/announcements", var("CLIENT_URL").unwrap_or_default());
+
+	Ok(
+		announcements
+			.into_iter()
+			.map(|announcement| {
+				let published = to_chrono(announcement.date_published());
+				let updated = announcement.date_updated.map(to_chrono).unwrap_or(published);
+				FeedEntry {
+					id: format!("urn:hbcybertech:announcement:{}", announcement._id),
+					title: announcement.title,
+					link: link.clone(),
+					author: announcement.author,
+					content: announcement.body,
+					published,
+					updated: updated.max(published),
+				}
+			})
+			.collect()
+	)
+}
+
+// Create the feed entries of the most recent forum posts
+async fn forum_post_entries(db: &Database) -> Result<Vec<FeedEntry>, mongodb::error::Error> {
+	let posts = db.get_recent_forum_posts(FEED_SIZE as i64).await?;
+	let client_url = var("CLIENT_URL").unwrap_or_default();
+
+	Ok(
+		posts
+			.into_iter()
+			.map(|post| {
+				let published = to_chrono(post.date_created);
+
+				// A post changes whenever someone comments on it
+				let updated = post.comments
+					.iter()
+					.filter_map(|comment| {
+						comment
+							.as_document()
+							.and_then(|doc| doc.get("date_created"))
+							.and_then(Bson::as_datetime)
+							.map(|date| to_chrono(*date))
+					})
+					.max()
+					.unwrap_or(published)
+					.max(published);
+
+				FeedEntry {
+					id: format!("urn:hbcybertech:forum_post:{}", post._id),
+					title: post.title,
+					link: format!("{}/forum/general/{}", client_url, post._id),
+					author: post.author,
+					content: post.body,
+					published,
+					updated,
+				}
+			})
+			.collect()
+	)
+}
+
+// Get the Atom or RSS feed of the announcements, the general forum, or both
+#[get("/feeds/{category}/{format}")]
+pub async fn get_feed(
+	db: Data<Database>,
+	path: web::Path<(String, String)>,
+	req: HttpRequest
+) -> HttpResponse {
+	let (category, format) = path.into_inner();
+	if format != "atom" && format != "rss" {
+		return HttpResponse::NotFound().body("Feed format not found.");
+	}
+
+	// Get the entries of the category
+	let (title, link_path, entries) = match category.as_str() {
+		"announcements" =>
+			("HB CyberTech Announcements", "/forum/announcements", announcement_entries(&db).await),
+		"general" =>
+			("HB CyberTech General Discussion", "/forum/general", forum_post_entries(&db).await),
+		"all" =>
+			match (announcement_entries(&db).await, forum_post_entries(&db).await) {
+				(Ok(mut announcements), Ok(posts)) => {
+					announcements.extend(posts);
+					("HB CyberTech Forum", "/forum", Ok(announcements))
+				}
+				(Err(err), _) | (_, Err(err)) => ("", "", Err(err)),
+			}
+		_ => {
+			return HttpResponse::NotFound().body("Feed category not found.");
+		}
+	};
+	let mut entries = match entries {
+		Ok(entries) => entries,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	// Keep the most recent entries
+	entries.sort_by_key(|entry| Reverse(entry.published));
+	entries.truncate(FEED_SIZE);
+
+	let feed = Feed {
+		id: format!("urn:hbcybertech:feed:{}", category),
+		title: title.to_string(),
+		link: format!("{}{}", var("CLIENT_URL").unwrap_or_default(), link_path),
+		self_link: format!(
+			"http://{}/feeds/{}/{}",
+			var("SERVER_URL").unwrap_or_default(),
+			category,
+			format
+		),
+		entries,
+	};
+	let (body, content_type) = if format == "atom" {
+		(feed.to_atom(), "application/atom+xml; charset=utf-8")
+	} else {
+		(feed.to_rss(), "application/rss+xml; charset=utf-8")
+	};
+
+	// Create the cache validators
+	let mut hasher = DefaultHasher::new();
+	body.hash(&mut hasher);
+	let etag = format!("\"{:x}\"", hasher.finish());
+	let last_modified = feed.updated().timestamp().max(0) as u64;
+	let last_modified_header = HttpDate::from(
+		UNIX_EPOCH + Duration::from_secs(last_modified)
+	).to_string();
+
+	// Check if the reader already has the latest version of the feed
+	let headers = req.headers();
+	let if_none_match = headers.get(header::IF_NONE_MATCH).and_then(|value| value.to_str().ok());
+	let not_modified = match if_none_match {
+		Some(if_none_match) =>
+			if_none_match
+				.split(',')
+				.any(|tag| {
+					let tag = tag.trim();
+					tag == "*" || tag == etag || tag.strip_prefix("W/") == Some(etag.as_str())
+				}),
+		None =>
+			headers
+				.get(header::IF_MODIFIED_SINCE)
+				.and_then(|value| value.to_str().ok())
+				.and_then(|value| value.parse::<HttpDate>().ok())
+				.and_then(|since| SystemTime::from(since).duration_since(UNIX_EPOCH).ok())
+				.is_some_and(|since| since.as_secs() >= last_modified),
+	};
+
+	if not_modified {
+		return HttpResponse::NotModified()
+			.insert_header((header::ETAG, etag))
+			.insert_header((header::LAST_MODIFIED, last_modified_header))
+			.finish();
+	}
+
+	HttpResponse::Ok()
+		.content_type(content_type)
+		.insert_header((header::ETAG, etag))
+		.insert_header((header::LAST_MODIFIED, last_modified_header))
+		.body(body)
+}
@@ -1,13 +1,46 @@
-use actix_web::{ get, post, web::{ self, Data, Json }, HttpResponse };
+use std::collections::HashSet;
+use actix_web::{ get, post, web::{ self, Data, Json }, HttpRequest, HttpResponse };
 use bcrypt::{ hash, verify, DEFAULT_COST };
+use mongodb::bson::{ doc, oid::ObjectId, DateTime };
+use serde::Deserialize;
 use validator::Validate;
 
 use crate::{
-	models::general_member::{ GeneralMember, GeneralMemberRequest },
-	services::db::Database,
-	utilities::pagination_args::AdminPaginationArgs,
+	models::{
+		application::{
+			default_status,
+			ApplicationEdit,
+			ApplicationEditRequest,
+			EditArgs,
+			FieldChange,
+			ReviewerNote,
+			ReviewerNoteRequest,
+			StatusChangeRequest,
+		},
+		general_member::{
+			GeneralMember,
+			GeneralMemberImportRow,
+			GeneralMemberRequest,
+			ImportReport,
+			ImportRowResult,
+		},
+	},
+	services::{ db::Database, email::{ client_url, send_email, EmailTemplate } },
+	utilities::{
+		auth::{ generate_link_token, get_application_editor, get_signed_in_account, is_admin },
+		pagination_args::AdminPaginationArgs,
+	},
 };
 
+// Define the ImportArgs struct
+#[derive(Deserialize)]
+struct ImportArgs {
+	token: String,
+	csv: String,
+	#[serde(default)]
+	dry_run: bool,
+}
+
 // Get the general member by full name or email
 #[get("/general_member/get/{full_name_or_email}")]
 pub async fn get_general_member_by_full_name_or_email(
@@ -55,12 +88,22 @@ pub async fn get_all_general_members(
 				.into_iter()
 				.rev()
 				.map(|member| GeneralMemberRequest {
+					id: member._id.to_string(),
 					full_name: member.full_name.clone(),
 					email: member.email.clone(),
 					grade: member.grade.clone(),
 					skills: member.skills.clone(),
 					extra: member.extra.clone(),
 					date_created: member.date_created.to_string(),
+					status: member.status.clone(),
+					reviewer_notes: member.reviewer_notes
+						.into_iter()
+						.map(ReviewerNoteRequest::from)
+						.collect(),
+					edit_history: member.edit_history
+						.into_iter()
+						.map(ApplicationEditRequest::from)
+						.collect(),
 				})
 				.collect();
 			HttpResponse::Ok().json(general_members)
@@ -73,7 +116,8 @@ pub async fn get_all_general_members(
 #[post("/general_member/post")]
 pub async fn create_general_member(
 	db: Data<Database>,
-	request: Json<GeneralMemberRequest>
+	request: Json<GeneralMemberRequest>,
+	req: HttpRequest
 ) -> HttpResponse {
 	// Validate the request
 	match request.validate() {
@@ -83,20 +127,402 @@ pub async fn create_general_member(
 		}
 	}
 
-	// Create the general member
-	match
-		db.create_general_member(
-			GeneralMember::try_from(GeneralMemberRequest {
+	// Check if the form is accepting responses
+	match db.is_form_open("general", "").await {
+		Ok(true) => (),
+		Ok(false) => {
+			return HttpResponse::Forbidden().body("The form is not accepting responses.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	}
+
+	// Create the general member, linked to the account that submitted it
+	let mut member = GeneralMember::try_from(GeneralMemberRequest {
+		id: String::new(),
+		full_name: request.full_name.clone(),
+		email: request.email.clone(),
+		grade: request.grade,
+		skills: request.skills,
+		extra: request.extra.clone(),
+		date_created: request.date_created.clone(),
+		status: default_status(),
+		reviewer_notes: Vec::new(),
+		edit_history: Vec::new(),
+	}).expect("Error converting GeneralMemberRequest to GeneralMember.");
+	// Only link an account that has proven it owns the application's email, the rest are linked when the
+	// owner of the email verifies their account
+	member.account_id = get_signed_in_account(&db, &req).await
+		.filter(|acc| acc.verified && acc.email == member.email)
+		.map(|acc| acc._id);
+
+	// Only the hash of the edit link's secret is kept
+	let edit_token = generate_link_token();
+	member.edit_token = hash(&edit_token, DEFAULT_COST).unwrap();
+	let edit_url = format!("{}/apply/edit/general/{}?token={}", client_url(), member._id, edit_token);
+
+	match db.create_general_member(member).await {
+		Ok(gen_mem) => {
+			// Let the applicant know their application was received and how to change it
+			let email = EmailTemplate::ApplicationReceived {
 				full_name: request.full_name.clone(),
-				email: request.email.clone(),
-				grade: request.grade.clone(),
-				skills: request.skills.clone(),
-				extra: request.extra.clone(),
-				date_created: request.date_created.clone(),
-			}).expect("Error converting GeneralMemberRequest to GeneralMember.")
-		).await
+				form: "general membership".to_string(),
+				edit_url,
+			};
+			if let Err(err) = send_email(request.email.clone(), &email).await {
+				println!("Error sending email: {}", err);
+			}
+
+			HttpResponse::Ok().json(gen_mem)
+		}
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Get an application for its applicant to edit
+#[get("/general_member/edit/{id}")]
+pub async fn get_general_member_for_edit(
+	db: Data<Database>,
+	id: web::Path<String>,
+	query: web::Query<EditArgs>,
+	req: HttpRequest
+) -> HttpResponse {
+	if ObjectId::parse_str(id.as_str()).is_err() {
+		return HttpResponse::BadRequest().body("Invalid ID.");
+	}
+
+	let member = match db.get_general_member_by_id(id.to_string()).await {
+		Ok(Some(member)) => member,
+		Ok(None) => {
+			return HttpResponse::NotFound().body("Application not found.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	if
+		get_application_editor(&db, &req, member.account_id, &member.edit_token, &query.token).await
+			.is_none()
 	{
-		Ok(gen_mem) => HttpResponse::Ok().json(gen_mem),
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	// The reviewers' notes stay private
+	HttpResponse::Ok().json(GeneralMemberRequest {
+		id: member._id.to_string(),
+		full_name: member.full_name,
+		email: member.email,
+		grade: member.grade,
+		skills: member.skills,
+		extra: member.extra,
+		date_created: member.date_created.to_string(),
+		status: member.status,
+		reviewer_notes: Vec::new(),
+		edit_history: member.edit_history
+			.into_iter()
+			.map(ApplicationEditRequest::from)
+			.collect(),
+	})
+}
+
+// Let an applicant edit their application while the form is open
+#[post("/general_member/edit/{id}")]
+pub async fn edit_general_member(
+	db: Data<Database>,
+	id: web::Path<String>,
+	query: web::Query<EditArgs>,
+	request: Json<GeneralMemberRequest>,
+	req: HttpRequest
+) -> HttpResponse {
+	// Validate the request
+	match request.validate() {
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	}
+
+	if ObjectId::parse_str(id.as_str()).is_err() {
+		return HttpResponse::BadRequest().body("Invalid ID.");
+	}
+
+	// Get the application and check who is editing it
+	let member = match db.get_general_member_by_id(id.to_string()).await {
+		Ok(Some(member)) => member,
+		Ok(None) => {
+			return HttpResponse::NotFound().body("Application not found.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	let editor = match
+		get_application_editor(&db, &req, member.account_id, &member.edit_token, &query.token).await
+	{
+		Some(editor) => editor,
+		None => {
+			return HttpResponse::Unauthorized().body("Unauthorized.");
+		}
+	};
+
+	// Decided applications can't change
+	if ["accepted", "rejected", "withdrawn"].contains(&member.status.as_str()) {
+		return HttpResponse::BadRequest().body("The application can no longer be changed.");
+	}
+	match db.is_form_open("general", "").await {
+		Ok(true) => (),
+		Ok(false) => {
+			return HttpResponse::Forbidden().body("The form is not accepting responses.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	}
+	if request.email != member.email {
+		return HttpResponse::BadRequest().body("The email of an application can't be changed.");
+	}
+	if
+		request.full_name != member.full_name &&
+		db.gen_mem_name_is_taken(&request.full_name, member._id).await
+	{
+		return HttpResponse::BadRequest().body("Member already exists.");
+	}
+
+	// Keep what changed for the reviewers
+	let changes: Vec<FieldChange> = [
+		FieldChange::between("full_name", member.full_name, request.full_name.clone()),
+		FieldChange::between("grade", member.grade.to_string(), request.grade.to_string()),
+		FieldChange::between("skills", member.skills.to_string(), request.skills.to_string()),
+		FieldChange::between("extra", member.extra, request.extra.clone()),
+	]
+		.into_iter()
+		.flatten()
+		.collect();
+	if changes.is_empty() {
+		return HttpResponse::Ok().json("No changes.");
+	}
+
+	let fields = doc! {
+		"full_name": &request.full_name,
+		"grade": request.grade as i32,
+		"skills": request.skills as i32,
+		"extra": &request.extra,
+	};
+	let edit = ApplicationEdit {
+		editor,
+		changes,
+		date_created: DateTime::now(),
+	};
+	match db.edit_general_member(member._id, fields, edit.clone()).await {
+		Ok(_) => HttpResponse::Ok().json(ApplicationEditRequest::from(edit)),
 		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
 	}
 }
+
+// Change the status of a general member's application
+#[post("/general_member/status/{id}")]
+pub async fn change_general_member_status(
+	db: Data<Database>,
+	id: web::Path<String>,
+	request: Json<StatusChangeRequest>
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	// Validate the request
+	match request.validate() {
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	}
+
+	if ObjectId::parse_str(id.as_str()).is_err() {
+		return HttpResponse::BadRequest().body("Invalid ID.");
+	}
+
+	// Get the application
+	let member = match db.get_general_member_by_id(id.to_string()).await {
+		Ok(Some(member)) => member,
+		Ok(None) => {
+			return HttpResponse::NotFound().body("Application not found.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	// Withdrawn applications are final
+	if member.status == "withdrawn" {
+		return HttpResponse::BadRequest().body("The application has been withdrawn.");
+	}
+
+	// Record the status change and the reviewer's note
+	let status_changed = member.status != request.status;
+	let note = ReviewerNote {
+		status: request.status.clone(),
+		note: request.note.clone(),
+		date_created: DateTime::now(),
+	};
+	if let Err(err) = db.update_general_member_status(id.to_string(), note).await {
+		return HttpResponse::InternalServerError().body(err.to_string());
+	}
+
+	// Let the applicant know their status changed
+	if status_changed {
+		let email = EmailTemplate::ApplicationDecision {
+			full_name: member.full_name.clone(),
+			form: "general membership".to_string(),
+			status: request.status.clone(),
+			note: request.message.clone(),
+		};
+		if let Err(err) = send_email(member.email.clone(), &email).await {
+			println!("Error sending email: {}", err);
+		}
+	}
+
+	HttpResponse::Ok().json(request.status.clone())
+}
+
+// Import general members from a CSV with full_name, email, grade, and optional skills and extra columns
+#[post("/general_member/import")]
+pub async fn import_general_members(
+	db: Data<Database>,
+	request: Json<ImportArgs>
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	let mut reader = csv::ReaderBuilder
+		::new()
+		.trim(csv::Trim::All)
+		.flexible(true)
+		.from_reader(request.csv.as_bytes());
+
+	// Match the headers regardless of case
+	let headers: csv::StringRecord = match reader.headers() {
+		Ok(headers) =>
+			headers
+				.iter()
+				.map(|header| header.to_lowercase())
+				.collect(),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	};
+	for column in ["full_name", "email", "grade"] {
+		if !headers.iter().any(|header| header == column) {
+			return HttpResponse::BadRequest().body(format!("Missing column: {}.", column));
+		}
+	}
+
+	let mut report = ImportReport {
+		dry_run: request.dry_run,
+		total: 0,
+		imported: 0,
+		duplicates: 0,
+		invalid: 0,
+		rows: Vec::new(),
+	};
+	let mut seen_names: HashSet<String> = HashSet::new();
+	let mut seen_emails: HashSet<String> = HashSet::new();
+
+	for record in reader.records() {
+		report.total += 1;
+		let (row, record) = match record {
+			Ok(record) => (
+				record
+					.position()
+					.map(|position| position.line())
+					.unwrap_or_default(),
+				record,
+			),
+			Err(err) => {
+				report.invalid += 1;
+				report.rows.push(ImportRowResult {
+					row: err
+						.position()
+						.map(|position| position.line())
+						.unwrap_or_default(),
+					full_name: String::new(),
+					email: String::new(),
+					result: "invalid".to_string(),
+					errors: vec![err.to_string()],
+				});
+				continue;
+			}
+		};
+		if record.iter().all(|value| value.is_empty()) {
+			report.total -= 1;
+			continue;
+		}
+
+		let mut result = ImportRowResult {
+			row,
+			full_name: String::new(),
+			email: String::new(),
+			result: "invalid".to_string(),
+			errors: Vec::new(),
+		};
+		let import_row = match record.deserialize::<GeneralMemberImportRow>(Some(&headers)) {
+			Ok(import_row) => import_row,
+			Err(err) => {
+				result.errors.push(err.to_string());
+				report.invalid += 1;
+				report.rows.push(result);
+				continue;
+			}
+		};
+		result.full_name = import_row.full_name.clone();
+		result.email = import_row.email.clone();
+
+		let mut member = match import_row.into_member() {
+			Ok(member) => member,
+			Err(errors) => {
+				result.errors = errors;
+				report.invalid += 1;
+				report.rows.push(result);
+				continue;
+			}
+		};
+
+		// Imported members are already in the club, so they skip the review
+		member.status = "accepted".to_string();
+
+		// Skip members who already signed up, either before or earlier in the file
+		let new_name = seen_names.insert(member.full_name.to_lowercase());
+		let new_email = seen_emails.insert(member.email.to_lowercase());
+		if !new_name || !new_email || db.gen_mem_does_exist(&member).await {
+			result.result = "duplicate".to_string();
+			report.duplicates += 1;
+			report.rows.push(result);
+			continue;
+		}
+
+		if request.dry_run {
+			result.result = "valid".to_string();
+			report.imported += 1;
+			report.rows.push(result);
+			continue;
+		}
+		match db.create_general_member(member).await {
+			Ok(_) => {
+				result.result = "imported".to_string();
+				report.imported += 1;
+			}
+			Err(err) => {
+				result.result = "invalid".to_string();
+				result.errors.push(err.to_string());
+				report.invalid += 1;
+			}
+		}
+		report.rows.push(result);
+	}
+
+	HttpResponse::Ok().json(report)
+}
@@ -0,0 +1,354 @@
+use actix_web::{ delete, get, post, web::{ self, Data, Json }, HttpRequest, HttpResponse };
+use bcrypt::{ hash, verify, DEFAULT_COST };
+use mongodb::bson::oid::ObjectId;
+use serde::Deserialize;
+use validator::Validate;
+
+use crate::{
+	models::{
+		application::EditArgs,
+		executive_member::ExecutiveMember,
+		interview::{
+			AvailableSlot,
+			BookingRequest,
+			InterviewSlot,
+			InterviewSlotRequest,
+			ScheduledInterview,
+		},
+	},
+	services::{
+		calendar::local_time,
+		db::Database,
+		email::{ client_url, send_email, send_email_with_calendar, EmailTemplate },
+		interview::{ interview_calendar, interview_cancellation },
+	},
+	utilities::{
+		auth::{ generate_link_token, get_token, is_admin },
+		claims::Token,
+	},
+};
+
+// Define the ScheduleArgs struct
+#[derive(Deserialize)]
+struct ScheduleArgs {
+	#[serde(default)]
+	exec_type: String,
+}
+
+// Get the shortlisted candidate that a booking link belongs to
+async fn get_candidate(db: &Database, id: &str, token: &str) -> Result<ExecutiveMember, HttpResponse> {
+	if ObjectId::parse_str(id).is_err() {
+		return Err(HttpResponse::BadRequest().body("Invalid ID."));
+	}
+
+	let member = match db.get_executive_member_by_id(id.to_string()).await {
+		Ok(Some(member)) => member,
+		Ok(None) => {
+			return Err(HttpResponse::NotFound().body("Application not found."));
+		}
+		Err(err) => {
+			return Err(HttpResponse::InternalServerError().body(err.to_string()));
+		}
+	};
+	if
+		token.is_empty() ||
+		member.interview_token.is_empty() ||
+		!verify(token, &member.interview_token).unwrap_or(false)
+	{
+		return Err(HttpResponse::Unauthorized().body("Unauthorized."));
+	}
+
+	// Only shortlisted candidates can book an interview
+	if member.term_ended || member.status != "interview" {
+		return Err(HttpResponse::BadRequest().body("The application is not shortlisted for an interview."));
+	}
+	Ok(member)
+}
+
+// Publish an interview slot
+#[post("/interview/slots/create")]
+pub async fn create_interview_slot(
+	db: Data<Database>,
+	request: Json<InterviewSlotRequest>
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	// Validate the request
+	match request.validate() {
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	}
+	let slot = match InterviewSlot::try_from(request.into_inner()) {
+		Ok(slot) => slot,
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	};
+
+	// Keep the schedule free of conflicts
+	match db.get_overlapping_interview_slots(&slot).await {
+		Ok(slots) => {
+			if let Some(conflict) = slots.iter().find_map(|other| slot.conflict_with(other)) {
+				return HttpResponse::Conflict().body(conflict);
+			}
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	}
+
+	match db.create_interview_slot(slot).await {
+		Ok(result) => HttpResponse::Ok().json(result),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Delete an interview slot nobody has booked
+#[delete("/interview/slots/delete/{id}")]
+pub async fn delete_interview_slot(
+	db: Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	if ObjectId::parse_str(id.as_str()).is_err() {
+		return HttpResponse::BadRequest().body("Invalid ID.");
+	}
+
+	match db.get_interview_slot_by_id(id.to_string()).await {
+		Ok(Some(slot)) => {
+			if slot.application_id.is_some() {
+				return HttpResponse::BadRequest().body("The slot has been booked.");
+			}
+		}
+		Ok(None) => {
+			return HttpResponse::NotFound().body("Slot not found.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	}
+
+	match db.delete_interview_slot(id.to_string()).await {
+		Ok(result) => HttpResponse::Ok().json(result),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Get the interview schedule with the candidates and anything that clashes
+#[get("/interview/schedule")]
+pub async fn get_interview_schedule(
+	db: Data<Database>,
+	query: web::Query<ScheduleArgs>,
+	req: HttpRequest
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	let slots = match db.get_interview_slots(&query.exec_type).await {
+		Ok(slots) => slots,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	let mut schedule: Vec<ScheduledInterview> = Vec::new();
+	for slot in &slots {
+		let mut conflicts: Vec<String> = slots
+			.iter()
+			.filter_map(|other| slot.conflict_with(other))
+			.collect();
+		if slot.application_id.is_some() {
+			let double_booked = slots
+				.iter()
+				.any(|other| other._id != slot._id && other.application_id == slot.application_id);
+			if double_booked {
+				conflicts.push("The candidate has booked another slot.".to_string());
+			}
+		}
+
+		let member = match slot.application_id {
+			Some(id) =>
+				match db.get_executive_member_by_id(id.to_hex()).await {
+					Ok(member) => member,
+					Err(err) => {
+						return HttpResponse::InternalServerError().body(err.to_string());
+					}
+				}
+			None => None,
+		};
+		let (full_name, email) = member
+			.map(|member| (member.full_name, member.email))
+			.unwrap_or_default();
+
+		schedule.push(ScheduledInterview {
+			slot: InterviewSlotRequest::from(slot.clone()),
+			full_name,
+			email,
+			conflicts,
+		});
+	}
+
+	HttpResponse::Ok().json(schedule)
+}
+
+// Send a shortlisted candidate the link to book an interview
+#[post("/interview/invite/{id}")]
+pub async fn invite_to_interview(
+	db: Data<Database>,
+	id: web::Path<String>,
+	request: Json<Token>
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	if ObjectId::parse_str(id.as_str()).is_err() {
+		return HttpResponse::BadRequest().body("Invalid ID.");
+	}
+
+	let member = match db.get_executive_member_by_id(id.to_string()).await {
+		Ok(Some(member)) => member,
+		Ok(None) => {
+			return HttpResponse::NotFound().body("Application not found.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	if member.term_ended || member.status != "interview" {
+		return HttpResponse::BadRequest().body("The application is not shortlisted for an interview.");
+	}
+
+	// Only the hash of the booking link's secret is kept, so inviting again replaces the old link
+	let token = generate_link_token();
+	if let Err(err) = db.set_interview_token(member._id, hash(&token, DEFAULT_COST).unwrap()).await {
+		return HttpResponse::InternalServerError().body(err.to_string());
+	}
+
+	let email = EmailTemplate::InterviewInvite {
+		full_name: member.full_name.clone(),
+		exec_type: member.exec_type.clone(),
+		book_url: format!("{}/interview/book/{}?token={}", client_url(), member._id, token),
+	};
+	match send_email(member.email.clone(), &email).await {
+		Ok(_) => HttpResponse::Ok().json("Invitation sent."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Get the slots a candidate can book
+#[get("/interview/slots/{id}")]
+pub async fn get_available_interview_slots(
+	db: Data<Database>,
+	id: web::Path<String>,
+	query: web::Query<EditArgs>
+) -> HttpResponse {
+	let member = match get_candidate(&db, &id, &query.token).await {
+		Ok(member) => member,
+		Err(response) => {
+			return response;
+		}
+	};
+
+	match db.get_available_interview_slots(&member.exec_type, member._id).await {
+		Ok(slots) => {
+			let slots: Vec<AvailableSlot> = slots
+				.into_iter()
+				.map(|slot| AvailableSlot {
+					id: slot._id.to_string(),
+					starts_at: slot.starts_at.to_string(),
+					ends_at: slot.ends_at.to_string(),
+					location: slot.location,
+					booked: slot.application_id == Some(member._id),
+				})
+				.collect();
+			HttpResponse::Ok().json(slots)
+		}
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Book an interview slot, replacing the candidate's previous booking
+#[post("/interview/book/{id}")]
+pub async fn book_interview_slot(
+	db: Data<Database>,
+	id: web::Path<String>,
+	query: web::Query<EditArgs>,
+	request: Json<BookingRequest>
+) -> HttpResponse {
+	let member = match get_candidate(&db, &id, &query.token).await {
+		Ok(member) => member,
+		Err(response) => {
+			return response;
+		}
+	};
+
+	if ObjectId::parse_str(&request.slot_id).is_err() {
+		return HttpResponse::BadRequest().body("Invalid slot ID.");
+	}
+	let slot = match db.get_interview_slot_by_id(request.slot_id.clone()).await {
+		Ok(Some(slot)) => slot,
+		Ok(None) => {
+			return HttpResponse::NotFound().body("Slot not found.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	if !slot.exec_type.is_empty() && slot.exec_type != member.exec_type {
+		return HttpResponse::BadRequest().body("The slot is for another exec type.");
+	}
+	if slot.application_id == Some(member._id) {
+		return HttpResponse::Ok().json("The slot is already booked for you.");
+	}
+
+	let previous = match db.book_interview_slot(slot._id, member._id).await {
+		Ok(Some(previous)) => previous,
+		Ok(None) => {
+			return HttpResponse::Conflict().body("The slot is no longer available.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	// Remove the interview the candidate had before from their calendar
+	for old_slot in previous {
+		let email = EmailTemplate::InterviewCancelled {
+			full_name: member.full_name.clone(),
+			exec_type: member.exec_type.clone(),
+			time: local_time(old_slot.starts_at),
+		};
+		let calendar = interview_cancellation(&old_slot, &member.exec_type);
+		if let Err(err) = send_email_with_calendar(member.email.clone(), &email, calendar).await {
+			println!("Error sending email: {}", err);
+		}
+	}
+
+	// Confirm the booking with a calendar invite
+	let email = EmailTemplate::InterviewConfirmation {
+		full_name: member.full_name.clone(),
+		exec_type: member.exec_type.clone(),
+		time: local_time(slot.starts_at),
+		location: slot.location.clone(),
+	};
+	let calendar = interview_calendar(&slot, &member.exec_type);
+	if let Err(err) = send_email_with_calendar(member.email.clone(), &email, calendar).await {
+		println!("Error sending email: {}", err);
+	}
+
+	HttpResponse::Ok().json("Interview booked.")
+}
@@ -0,0 +1,394 @@
+use std::{ cmp::Reverse, collections::{ HashMap, HashSet } };
+use actix_web::{ delete, get, post, web::{ self, Data, Json }, HttpRequest, HttpResponse };
+use mongodb::bson::{ oid::ObjectId, DateTime };
+use validator::Validate;
+
+use crate::{
+	models::{
+		account::Account,
+		learning_path::{
+			LearningPath,
+			LearningPathArgs,
+			LearningPathDetails,
+			LearningPathRequest,
+			PathModuleDetails,
+			PathProgress,
+			PathProgressSummary,
+			PathStep,
+		},
+		resource::Resource,
+	},
+	services::db::Database,
+	utilities::auth::{ get_signed_in_account, get_token, is_admin },
+};
+
+// Get the account tracking its progress
+async fn get_learner(db: &Database, req: &HttpRequest) -> Result<Account, HttpResponse> {
+	match get_signed_in_account(db, req).await {
+		Some(acc) => Ok(acc),
+		None => Err(HttpResponse::Unauthorized().body("Sign in to track your progress.")),
+	}
+}
+
+// Get a learning path by the ID in the URL
+async fn get_path(db: &Database, id: &str) -> Result<LearningPath, HttpResponse> {
+	let path_id = match ObjectId::parse_str(id) {
+		Ok(path_id) => path_id,
+		Err(_) => {
+			return Err(HttpResponse::BadRequest().body("Invalid ID."));
+		}
+	};
+	match db.get_learning_path_by_id(path_id).await {
+		Ok(Some(path)) => Ok(path),
+		Ok(None) => Err(HttpResponse::NotFound().body("Learning path not found.")),
+		Err(err) => Err(HttpResponse::InternalServerError().body(err.to_string())),
+	}
+}
+
+// Make sure every resource of a learning path exists
+async fn check_resources(db: &Database, path: &LearningPath) -> Result<(), HttpResponse> {
+	let ids: HashSet<ObjectId> = path.resource_ids().into_iter().collect();
+	match db.get_resources_by_ids(ids.iter().copied().collect()).await {
+		Ok(resources) if resources.len() == ids.len() => Ok(()),
+		Ok(_) => Err(HttpResponse::BadRequest().body("Some of the resources do not exist.")),
+		Err(err) => Err(HttpResponse::InternalServerError().body(err.to_string())),
+	}
+}
+
+// Get every learning path, optionally of one difficulty
+#[get("/paths")]
+pub async fn get_learning_paths(db: Data<Database>, query: web::Query<LearningPathArgs>) -> HttpResponse {
+	match db.get_learning_paths(&query.difficulty).await {
+		Ok(paths) => {
+			let paths: Vec<LearningPathRequest> = paths
+				.into_iter()
+				.map(LearningPathRequest::from)
+				.collect();
+			HttpResponse::Ok().json(paths)
+		}
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Get how far the signed-in account is through each learning path it has started
+#[get("/paths/progress")]
+pub async fn get_path_progress(db: Data<Database>, req: HttpRequest) -> HttpResponse {
+	let acc = match get_learner(&db, &req).await {
+		Ok(acc) => acc,
+		Err(response) => {
+			return response;
+		}
+	};
+
+	let progress = match db.get_path_progress(acc._id, None).await {
+		Ok(progress) => progress,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	let paths = match db.get_learning_paths("").await {
+		Ok(paths) => paths,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	let mut completed: HashMap<ObjectId, (HashSet<ObjectId>, DateTime)> = HashMap::new();
+	for item in progress {
+		let (resources, last) = completed
+			.entry(item.path_id)
+			.or_insert((HashSet::new(), item.date_completed));
+		resources.insert(item.resource_id);
+		*last = (*last).max(item.date_completed);
+	}
+
+	let mut summaries: Vec<(DateTime, PathProgressSummary)> = Vec::new();
+	for path in paths {
+		let (resources, last) = match completed.get(&path._id) {
+			Some(completed) => completed,
+			None => {
+				continue;
+			}
+		};
+		let total = path.resource_ids().len() as u64;
+		let done = path
+			.resource_ids()
+			.iter()
+			.filter(|id| resources.contains(id))
+			.count() as u64;
+		let completed_modules = path.modules
+			.iter()
+			.filter(|module| module.resource_ids.iter().all(|id| resources.contains(id)))
+			.count() as u64;
+		summaries.push((*last, PathProgressSummary {
+			id: path._id.to_string(),
+			title: path.title,
+			difficulty: path.difficulty,
+			completed: done,
+			total,
+			percent: (done * 100).checked_div(total).unwrap_or(0) as u32,
+			completed_modules,
+			total_modules: path.modules.len() as u64,
+			last_completed: last.to_string(),
+		}));
+	}
+
+	// The most recently worked on paths come first
+	summaries.sort_by_key(|(last, _)| Reverse(*last));
+	let summaries: Vec<PathProgressSummary> = summaries
+		.into_iter()
+		.map(|(_, summary)| summary)
+		.collect();
+	HttpResponse::Ok().json(summaries)
+}
+
+// Get a learning path with its resources, and which ones the signed-in account has completed
+#[get("/paths/{id}")]
+pub async fn get_learning_path(
+	db: Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	let path = match get_path(&db, &id).await {
+		Ok(path) => path,
+		Err(response) => {
+			return response;
+		}
+	};
+
+	let resources: HashMap<ObjectId, Resource> = match db.get_resources_by_ids(path.resource_ids()).await {
+		Ok(resources) => resources
+			.into_iter()
+			.map(|resource| (resource._id, resource))
+			.collect(),
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	let completed: HashSet<ObjectId> = match get_signed_in_account(&db, &req).await {
+		Some(acc) =>
+			match db.get_path_progress(acc._id, Some(path._id)).await {
+				Ok(progress) => progress.into_iter().map(|item| item.resource_id).collect(),
+				Err(err) => {
+					return HttpResponse::InternalServerError().body(err.to_string());
+				}
+			}
+		None => HashSet::new(),
+	};
+
+	let modules: Vec<PathModuleDetails> = path.modules
+		.iter()
+		.map(|module| {
+			let steps: Vec<PathStep> = module.resource_ids
+				.iter()
+				.filter_map(|id| resources.get(id))
+				.map(|resource| PathStep {
+					id: resource._id.to_string(),
+					title: resource.title.clone(),
+					link: resource.link.clone(),
+					description: resource.description.clone(),
+					tags: resource.tags.clone(),
+					completed: completed.contains(&resource._id),
+				})
+				.collect();
+			PathModuleDetails {
+				title: module.title.clone(),
+				description: module.description.clone(),
+				completed: !steps.is_empty() && steps.iter().all(|step| step.completed),
+				steps,
+			}
+		})
+		.collect();
+	let total = modules.iter().map(|module| module.steps.len() as u64).sum();
+	let done = modules
+		.iter()
+		.flat_map(|module| module.steps.iter())
+		.filter(|step| step.completed)
+		.count() as u64;
+
+	HttpResponse::Ok().json(LearningPathDetails {
+		id: path._id.to_string(),
+		title: path.title,
+		description: path.description,
+		difficulty: path.difficulty,
+		completed: done,
+		total,
+		modules,
+	})
+}
+
+// Create a learning path
+#[post("/paths/create")]
+pub async fn create_learning_path(
+	db: Data<Database>,
+	request: Json<LearningPathRequest>
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	// Validate the request
+	match request.validate() {
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	}
+
+	let path = match LearningPath::try_from(request.into_inner()) {
+		Ok(path) => path,
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	};
+	if let Err(response) = check_resources(&db, &path).await {
+		return response;
+	}
+
+	match db.create_learning_path(path).await {
+		Ok(result) => HttpResponse::Ok().json(result),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Edit a learning path
+#[post("/paths/edit/{id}")]
+pub async fn edit_learning_path(
+	db: Data<Database>,
+	id: web::Path<String>,
+	request: Json<LearningPathRequest>
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	// Validate the request
+	match request.validate() {
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	}
+
+	let path_id = match ObjectId::parse_str(id.as_str()) {
+		Ok(path_id) => path_id,
+		Err(_) => {
+			return HttpResponse::BadRequest().body("Invalid ID.");
+		}
+	};
+	let path = match LearningPath::try_from(request.into_inner()) {
+		Ok(path) => path,
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	};
+	if let Err(response) = check_resources(&db, &path).await {
+		return response;
+	}
+
+	match db.update_learning_path(path_id, &path).await {
+		Ok(result) if result.matched_count == 0 =>
+			HttpResponse::NotFound().body("Learning path not found."),
+		Ok(_) => HttpResponse::Ok().json("Learning path updated."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Delete a learning path along with everyone's progress on it
+#[delete("/paths/delete/{id}")]
+pub async fn delete_learning_path(
+	db: Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	let path_id = match ObjectId::parse_str(id.as_str()) {
+		Ok(path_id) => path_id,
+		Err(_) => {
+			return HttpResponse::BadRequest().body("Invalid ID.");
+		}
+	};
+
+	match db.delete_learning_path(path_id).await {
+		Ok(result) if result.deleted_count == 0 =>
+			HttpResponse::NotFound().body("Learning path not found."),
+		Ok(_) => HttpResponse::Ok().json("Learning path deleted."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Mark a resource of a learning path as completed by the signed-in account
+#[post("/paths/{id}/complete/{resource_id}")]
+pub async fn complete_path_resource(
+	db: Data<Database>,
+	path: web::Path<(String, String)>,
+	req: HttpRequest
+) -> HttpResponse {
+	let (id, resource_id) = path.into_inner();
+	let acc = match get_learner(&db, &req).await {
+		Ok(acc) => acc,
+		Err(response) => {
+			return response;
+		}
+	};
+	let path = match get_path(&db, &id).await {
+		Ok(path) => path,
+		Err(response) => {
+			return response;
+		}
+	};
+
+	let resource_id = match ObjectId::parse_str(&resource_id) {
+		Ok(resource_id) if path.resource_ids().contains(&resource_id) => resource_id,
+		_ => {
+			return HttpResponse::NotFound().body("This resource is not part of the learning path.");
+		}
+	};
+
+	let progress = PathProgress {
+		_id: ObjectId::new(),
+		account_id: acc._id,
+		path_id: path._id,
+		resource_id,
+		date_completed: DateTime::now(),
+	};
+	match db.complete_path_resource(progress).await {
+		Ok(true) => HttpResponse::Ok().json("Marked as completed."),
+		Ok(false) => HttpResponse::Ok().json("Already completed."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Mark a resource of a learning path as not completed by the signed-in account
+#[delete("/paths/{id}/complete/{resource_id}")]
+pub async fn uncomplete_path_resource(
+	db: Data<Database>,
+	path: web::Path<(String, String)>,
+	req: HttpRequest
+) -> HttpResponse {
+	let (id, resource_id) = path.into_inner();
+	let acc = match get_learner(&db, &req).await {
+		Ok(acc) => acc,
+		Err(response) => {
+			return response;
+		}
+	};
+
+	let (path_id, resource_id) = match (ObjectId::parse_str(&id), ObjectId::parse_str(&resource_id)) {
+		(Ok(path_id), Ok(resource_id)) => (path_id, resource_id),
+		_ => {
+			return HttpResponse::BadRequest().body("Invalid ID.");
+		}
+	};
+
+	match db.uncomplete_path_resource(acc._id, path_id, resource_id).await {
+		Ok(_) => HttpResponse::Ok().json("Marked as not completed."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
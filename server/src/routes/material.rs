@@ -0,0 +1,300 @@
+use std::collections::HashMap;
+use actix_files::{ file_extension_to_mime, NamedFile };
+use actix_multipart::Multipart;
+use actix_web::{
+	delete,
+	get,
+	http::header::{ ContentDisposition, DispositionParam, DispositionType },
+	post,
+	web::{ self, Data, Json },
+	HttpRequest,
+	HttpResponse,
+};
+use futures_util::TryStreamExt;
+use mongodb::bson::oid::ObjectId;
+use validator::Validate;
+
+use crate::{
+	models::material::{ Material, MaterialArgs, MaterialRequest },
+	services::{
+		db::Database,
+		storage::{ allowed_extension, delete_upload, save_upload, stored_path, ALLOWED_EXTENSIONS },
+	},
+	utilities::auth::{ get_signed_in_account, get_token, is_admin },
+};
+
+// The longest value a text field of an upload can have
+const MAX_FIELD_BYTES: usize = 1024;
+
+// Get the session materials, optionally of one session
+#[get("/materials")]
+pub async fn get_materials(db: Data<Database>, query: web::Query<MaterialArgs>) -> HttpResponse {
+	match db.get_materials(&query.session).await {
+		Ok(materials) => {
+			let materials: Vec<MaterialRequest> = materials
+				.into_iter()
+				.map(MaterialRequest::from)
+				.collect();
+			HttpResponse::Ok().json(materials)
+		}
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Download a session material, which members-only materials need a verified account for
+#[get("/materials/file/{id}")]
+pub async fn get_material_file(
+	db: Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	if ObjectId::parse_str(id.as_str()).is_err() {
+		return HttpResponse::BadRequest().body("Invalid ID.");
+	}
+
+	let material = match db.get_material_by_id(id.to_string()).await {
+		Ok(Some(material)) => material,
+		Ok(None) => {
+			return HttpResponse::NotFound().body("Material not found.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	if material.members_only {
+		let member = match get_signed_in_account(&db, &req).await {
+			Some(acc) => acc.verified,
+			None => is_admin(&db, &get_token(&req).unwrap_or_default()).await,
+		};
+		if !member {
+			return HttpResponse::Unauthorized().body("This material is for members only.");
+		}
+	}
+
+	let path = match stored_path(&material.stored_name) {
+		Some(path) => path,
+		None => {
+			return HttpResponse::NotFound().body("File not found.");
+		}
+	};
+	let file = match NamedFile::open_async(path).await {
+		Ok(file) => file,
+		Err(_) => {
+			return HttpResponse::NotFound().body("File not found.");
+		}
+	};
+
+	// Slides open in the browser, and everything else is downloaded
+	let disposition = if material.content_type == "application/pdf" {
+		DispositionType::Inline
+	} else {
+		DispositionType::Attachment
+	};
+	let extension = material.stored_name.rsplit_once('.').map(|(_, extension)| extension).unwrap_or_default();
+
+	// Range requests and cache validators are handled by NamedFile
+	file
+		.set_content_type(file_extension_to_mime(extension))
+		.set_content_disposition(ContentDisposition {
+			disposition,
+			parameters: vec![DispositionParam::Filename(material.filename)],
+		})
+		.into_response(&req)
+}
+
+// Upload a session material as multipart form data with title, session, session_date, presenter,
+// members_only and file fields
+#[post("/materials/upload")]
+pub async fn upload_material(
+	db: Data<Database>,
+	mut payload: Multipart,
+	req: HttpRequest
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	let id = ObjectId::new();
+	let mut fields: HashMap<String, String> = HashMap::new();
+	let mut file: Option<(String, String, u64)> = None;
+	loop {
+		let mut field = match payload.try_next().await {
+			Ok(Some(field)) => field,
+			Ok(None) => {
+				break;
+			}
+			Err(err) => {
+				if let Some((_, stored_name, _)) = &file {
+					let _ = delete_upload(stored_name).await;
+				}
+				return HttpResponse::BadRequest().body(err.to_string());
+			}
+		};
+		let name = field.name().unwrap_or_default().to_string();
+
+		if name == "file" {
+			if file.is_some() {
+				continue;
+			}
+			let filename = field
+				.content_disposition()
+				.and_then(|disposition| disposition.get_filename())
+				.unwrap_or_default()
+				.to_string();
+			let extension = match allowed_extension(&filename) {
+				Some(extension) => extension,
+				None => {
+					return HttpResponse::BadRequest().body(
+						format!("The file should be one of: {}.", ALLOWED_EXTENSIONS.join(", "))
+					);
+				}
+			};
+			let stored_name = format!("{}.{}", id, extension);
+			match save_upload(&mut field, &stored_name).await {
+				Ok(size) => {
+					file = Some((filename, stored_name, size));
+				}
+				Err(err) => {
+					return HttpResponse::BadRequest().body(err.to_string());
+				}
+			}
+		} else {
+			let value = match field.bytes(MAX_FIELD_BYTES).await {
+				Ok(Ok(value)) => String::from_utf8_lossy(&value).trim().to_string(),
+				_ => {
+					if let Some((_, stored_name, _)) = &file {
+						let _ = delete_upload(stored_name).await;
+					}
+					return HttpResponse::BadRequest().body(format!("The {} field is too long.", name));
+				}
+			};
+			fields.insert(name, value);
+		}
+	}
+
+	let (filename, stored_name, size) = match file {
+		Some(file) => file,
+		None => {
+			return HttpResponse::BadRequest().body("A file is required.");
+		}
+	};
+	let field = |name: &str| fields.get(name).cloned().unwrap_or_default();
+	let request = MaterialRequest {
+		id: String::new(),
+		title: field("title"),
+		session: field("session"),
+		session_date: field("session_date"),
+		presenter: field("presenter"),
+		filename: String::new(),
+		content_type: String::new(),
+		size: 0,
+		members_only: field("members_only") == "true",
+		date_created: String::new(),
+		token: String::new(),
+	};
+
+	// Validate the request, throwing away the file if it is invalid
+	if let Err(err) = request.validate() {
+		let _ = delete_upload(&stored_name).await;
+		return HttpResponse::BadRequest().body(err.to_string());
+	}
+	let mut material = match Material::try_from(request) {
+		Ok(material) => material,
+		Err(err) => {
+			let _ = delete_upload(&stored_name).await;
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	};
+	let extension = stored_name.rsplit_once('.').map(|(_, extension)| extension).unwrap_or_default();
+	material._id = id;
+	material.content_type = file_extension_to_mime(extension).to_string();
+	material.filename = filename;
+	material.stored_name = stored_name.clone();
+	material.size = size;
+
+	// Throw away the file if the material can't be saved
+	match db.create_material(material).await {
+		Ok(result) => HttpResponse::Ok().json(result),
+		Err(err) => {
+			let _ = delete_upload(&stored_name).await;
+			HttpResponse::InternalServerError().body(err.to_string())
+		}
+	}
+}
+
+// Edit the details of a session material
+#[post("/materials/edit/{id}")]
+pub async fn edit_material(
+	db: Data<Database>,
+	id: web::Path<String>,
+	request: Json<MaterialRequest>
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	// Validate the request
+	match request.validate() {
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	}
+
+	let material_id = match ObjectId::parse_str(id.as_str()) {
+		Ok(material_id) => material_id,
+		Err(_) => {
+			return HttpResponse::BadRequest().body("Invalid ID.");
+		}
+	};
+
+	match Material::try_from(request.into_inner()) {
+		Ok(material) =>
+			match db.update_material(material_id, &material).await {
+				Ok(result) if result.matched_count == 0 =>
+					HttpResponse::NotFound().body("Material not found."),
+				Ok(_) => HttpResponse::Ok().json("Material updated."),
+				Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+			}
+		Err(err) => HttpResponse::BadRequest().body(err.to_string()),
+	}
+}
+
+// Delete a session material along with its file
+#[delete("/materials/delete/{id}")]
+pub async fn delete_material(
+	db: Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	if ObjectId::parse_str(id.as_str()).is_err() {
+		return HttpResponse::BadRequest().body("Invalid ID.");
+	}
+
+	let material = match db.get_material_by_id(id.to_string()).await {
+		Ok(Some(material)) => material,
+		Ok(None) => {
+			return HttpResponse::NotFound().body("Material not found.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	if let Err(err) = db.delete_material(material._id).await {
+		return HttpResponse::InternalServerError().body(err.to_string());
+	}
+	if let Err(err) = delete_upload(&material.stored_name).await {
+		println!("Error deleting file: {}", err);
+	}
+
+	HttpResponse::Ok().json("Material deleted.")
+}
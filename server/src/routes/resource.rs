@@ -1,25 +1,104 @@
-use actix_web::{ get, web, HttpResponse };
+use std::collections::{ HashMap, HashSet };
+use actix_web::{
+	delete,
+	get,
+	http::header::LOCATION,
+	post,
+	web::{ self, Json },
+	HttpRequest,
+	HttpResponse,
+};
+use mongodb::bson::{ oid::ObjectId, DateTime };
+use validator::Validate;
 use crate::Database;
-use crate::utilities::pagination_args::ResourcePaginationArgs;
-use crate::models::resource::ResourceRequest;
+use crate::services::link_checker::start_link_check;
+use crate::utilities::{
+	auth::{ get_signed_in_account, get_token, get_verified_account, is_admin },
+	pagination_args::ResourcePaginationArgs,
+};
+use crate::models::resource::{
+	LinkReportArgs,
+	LinkReportEntry,
+	RatingRequest,
+	Resource,
+	ResourceBookmark,
+	ResourceRating,
+	ResourceRequest,
+	ResourceTag,
+	ResourceTagRequest,
+	TagCount,
+};
+
+// Make sure every tag of a resource is in the tag list
+async fn check_tags(db: &Database, tags: &[String]) -> Result<(), HttpResponse> {
+	let known = match db.get_resource_tags().await {
+		Ok(known) => known,
+		Err(err) => {
+			return Err(HttpResponse::InternalServerError().body(err.to_string()));
+		}
+	};
+	let unknown: Vec<&str> = tags
+		.iter()
+		.filter(|tag| !known.iter().any(|known| &known.name == *tag))
+		.map(String::as_str)
+		.collect();
+	if !unknown.is_empty() {
+		return Err(HttpResponse::BadRequest().body(format!("Unknown tags: {}.", unknown.join(", "))));
+	}
+	Ok(())
+}
 
 #[get("/resources")]
 async fn get_resources(
 	db: web::Data<Database>,
-	query: web::Query<ResourcePaginationArgs>
+	query: web::Query<ResourcePaginationArgs>,
+	req: HttpRequest
 ) -> HttpResponse {
-	let ResourcePaginationArgs { page, limit, search, field, tag } = query.into_inner();
-	match db.get_resources(page, limit, search, field, tag).await {
+	let args = query.into_inner();
+	if !matches!(args.sort.as_str(), "" | "newest" | "top_rated" | "most_used") {
+		return HttpResponse::BadRequest().body("Invalid sort. Must be newest, top_rated or most_used.");
+	}
+
+	// Get the signed-in account's ratings and bookmarks to show on the resources
+	let acc = get_signed_in_account(&db, &req).await;
+	if args.bookmarked && acc.is_none() {
+		return HttpResponse::Unauthorized().body("Sign in to see your bookmarks.");
+	}
+	let (ratings, bookmarks) = match &acc {
+		Some(acc) => {
+			let ratings = db.get_resource_ratings_by_account_id(acc._id).await;
+			let bookmarks = db.get_resource_bookmarks_by_account_id(acc._id).await;
+			match (ratings, bookmarks) {
+				(Ok(ratings), Ok(bookmarks)) => {
+					let ratings: HashMap<ObjectId, u32> = ratings
+						.into_iter()
+						.map(|rating| (rating.resource_id, rating.rating))
+						.collect();
+					let bookmarks: HashSet<ObjectId> = bookmarks
+						.into_iter()
+						.map(|bookmark| bookmark.resource_id)
+						.collect();
+					(ratings, bookmarks)
+				}
+				(Err(err), _) | (_, Err(err)) => {
+					return HttpResponse::InternalServerError().body(err.to_string());
+				}
+			}
+		}
+		None => (HashMap::new(), HashSet::new()),
+	};
+	let ids = args.bookmarked.then(|| bookmarks.iter().copied().collect());
+
+	match db.get_resources(args, ids).await {
 		Ok(resources) => {
 			let resources = resources
 				.into_iter()
 				.map(|resource| {
-					ResourceRequest {
-						title: resource.title,
-						link: resource.link,
-						tags: resource.tags,
-						description: resource.description,
-					}
+					let id = resource._id;
+					let mut resource = ResourceRequest::from(resource);
+					resource.my_rating = ratings.get(&id).copied().unwrap_or(0);
+					resource.bookmarked = bookmarks.contains(&id);
+					resource
 				})
 				.collect::<Vec<ResourceRequest>>();
 			HttpResponse::Ok().json(resources)
@@ -27,3 +106,401 @@ async fn get_resources(
 		Err(_) => HttpResponse::InternalServerError().finish(),
 	}
 }
+
+// Create a resource
+#[post("/resources/create")]
+async fn create_resource(db: web::Data<Database>, request: Json<ResourceRequest>) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	// Validate the request
+	match request.validate() {
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	}
+
+	let resource = match Resource::try_from(request.into_inner()) {
+		Ok(resource) => resource,
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	};
+	if let Err(response) = check_tags(&db, &resource.tags).await {
+		return response;
+	}
+
+	match db.create_resource(resource).await {
+		Ok(result) => HttpResponse::Ok().json(result),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Edit a resource
+#[post("/resources/edit/{id}")]
+async fn edit_resource(
+	db: web::Data<Database>,
+	id: web::Path<String>,
+	request: Json<ResourceRequest>
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	// Validate the request
+	match request.validate() {
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	}
+
+	let resource_id = match ObjectId::parse_str(id.as_str()) {
+		Ok(resource_id) => resource_id,
+		Err(_) => {
+			return HttpResponse::BadRequest().body("Invalid ID.");
+		}
+	};
+	let resource = match Resource::try_from(request.into_inner()) {
+		Ok(resource) => resource,
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	};
+	if let Err(response) = check_tags(&db, &resource.tags).await {
+		return response;
+	}
+
+	match db.update_resource(resource_id, &resource).await {
+		Ok(result) if result.matched_count == 0 => HttpResponse::NotFound().body("Resource not found."),
+		Ok(_) => HttpResponse::Ok().json("Resource updated."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Delete a resource
+#[delete("/resources/delete/{id}")]
+async fn delete_resource(
+	db: web::Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	let resource_id = match ObjectId::parse_str(id.as_str()) {
+		Ok(resource_id) => resource_id,
+		Err(_) => {
+			return HttpResponse::BadRequest().body("Invalid ID.");
+		}
+	};
+
+	match db.delete_resource(resource_id).await {
+		Ok(result) if result.deleted_count == 0 => HttpResponse::NotFound().body("Resource not found."),
+		Ok(_) => HttpResponse::Ok().json("Resource deleted."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Get every tag in the tag list with how many resources have it
+#[get("/resources/tags")]
+async fn get_resource_tags(db: web::Data<Database>) -> HttpResponse {
+	let tags = match db.get_resource_tags().await {
+		Ok(tags) => tags,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	let mut counts: Vec<TagCount> = Vec::new();
+	for tag in tags {
+		match db.count_resources_with_tag(&tag.name).await {
+			Ok(count) => counts.push(TagCount { name: tag.name, count }),
+			Err(err) => {
+				return HttpResponse::InternalServerError().body(err.to_string());
+			}
+		}
+	}
+	HttpResponse::Ok().json(counts)
+}
+
+// Add a tag to the tag list
+#[post("/resources/tags/create")]
+async fn create_resource_tag(
+	db: web::Data<Database>,
+	request: Json<ResourceTagRequest>
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	// Validate the request
+	match request.validate() {
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	}
+
+	let tag = ResourceTag {
+		_id: ObjectId::new(),
+		name: request.name.trim().to_string(),
+		date_created: DateTime::now(),
+	};
+	match db.create_resource_tag(tag).await {
+		Ok(true) => HttpResponse::Ok().json("Tag created."),
+		Ok(false) => HttpResponse::Conflict().body("This tag already exists."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Remove a tag from the tag list once no resources have it
+#[delete("/resources/tags/delete/{name}")]
+async fn delete_resource_tag(
+	db: web::Data<Database>,
+	name: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	match db.count_resources_with_tag(&name).await {
+		Ok(0) => (),
+		Ok(count) => {
+			return HttpResponse::BadRequest().body(
+				format!("Remove this tag from its {} resources before deleting it.", count)
+			);
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	}
+
+	match db.delete_resource_tag(&name).await {
+		Ok(result) if result.deleted_count == 0 => HttpResponse::NotFound().body("Tag not found."),
+		Ok(_) => HttpResponse::Ok().json("Tag deleted."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Get the resources whose links are failing, dead links first
+#[get("/resources/links/report")]
+async fn get_link_report(
+	db: web::Data<Database>,
+	query: web::Query<LinkReportArgs>,
+	req: HttpRequest
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	match db.get_link_report(query.dead).await {
+		Ok(resources) => {
+			let report: Vec<LinkReportEntry> = resources
+				.into_iter()
+				.map(LinkReportEntry::from)
+				.collect();
+			HttpResponse::Ok().json(report)
+		}
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Check every resource's link now instead of waiting for the next round
+#[post("/resources/links/check")]
+async fn check_links(db: web::Data<Database>, req: HttpRequest) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	if !start_link_check(db) {
+		return HttpResponse::Conflict().body("The links are already being checked.");
+	}
+	HttpResponse::Accepted().json("Link check started.")
+}
+
+// Go to a resource's link, counting the click
+#[get("/resources/go/{id}")]
+async fn go_to_resource(
+	db: web::Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	let resource_id = match ObjectId::parse_str(id.as_str()) {
+		Ok(resource_id) => resource_id,
+		Err(_) => {
+			return HttpResponse::BadRequest().body("Invalid ID.");
+		}
+	};
+
+	// Clicks are counted once per signed-in account, or per IP address for everyone else
+	let visitor = match get_signed_in_account(&db, &req).await {
+		Some(acc) => acc._id.to_string(),
+		None => req.connection_info().realip_remote_addr().unwrap_or_default().to_string(),
+	};
+	match db.record_resource_click(resource_id, &visitor).await {
+		Ok(Some(resource)) => HttpResponse::Found().insert_header((LOCATION, resource.link)).finish(),
+		Ok(None) => HttpResponse::NotFound().body("Resource not found."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Rate a resource from 1 to 5, replacing the signed-in account's earlier rating
+#[post("/resources/rate/{id}")]
+async fn rate_resource(
+	db: web::Data<Database>,
+	id: web::Path<String>,
+	request: Json<RatingRequest>,
+	req: HttpRequest
+) -> HttpResponse {
+	let acc = match get_verified_account(&db, &req, "rate and bookmark resources").await {
+		Ok(acc) => acc,
+		Err(response) => {
+			return response;
+		}
+	};
+
+	// Validate the request
+	match request.validate() {
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	}
+
+	let resource_id = match ObjectId::parse_str(id.as_str()) {
+		Ok(resource_id) => resource_id,
+		Err(_) => {
+			return HttpResponse::BadRequest().body("Invalid ID.");
+		}
+	};
+	match db.get_resources_by_ids(vec![resource_id]).await {
+		Ok(resources) if resources.is_empty() => {
+			return HttpResponse::NotFound().body("Resource not found.");
+		}
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	}
+
+	let rating = ResourceRating {
+		_id: ObjectId::new(),
+		resource_id,
+		account_id: acc._id,
+		rating: request.rating,
+		date_updated: DateTime::now(),
+	};
+	match db.rate_resource(rating).await {
+		Ok(_) => HttpResponse::Ok().json("Resource rated."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Take back the signed-in account's rating of a resource
+#[delete("/resources/rate/{id}")]
+async fn delete_resource_rating(
+	db: web::Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	let acc = match get_verified_account(&db, &req, "rate and bookmark resources").await {
+		Ok(acc) => acc,
+		Err(response) => {
+			return response;
+		}
+	};
+
+	let resource_id = match ObjectId::parse_str(id.as_str()) {
+		Ok(resource_id) => resource_id,
+		Err(_) => {
+			return HttpResponse::BadRequest().body("Invalid ID.");
+		}
+	};
+
+	match db.delete_resource_rating(resource_id, acc._id).await {
+		Ok(_) => HttpResponse::Ok().json("Rating removed."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Bookmark a resource for the signed-in account
+#[post("/resources/bookmark/{id}")]
+async fn bookmark_resource(
+	db: web::Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	let acc = match get_verified_account(&db, &req, "rate and bookmark resources").await {
+		Ok(acc) => acc,
+		Err(response) => {
+			return response;
+		}
+	};
+
+	let resource_id = match ObjectId::parse_str(id.as_str()) {
+		Ok(resource_id) => resource_id,
+		Err(_) => {
+			return HttpResponse::BadRequest().body("Invalid ID.");
+		}
+	};
+	match db.get_resources_by_ids(vec![resource_id]).await {
+		Ok(resources) if resources.is_empty() => {
+			return HttpResponse::NotFound().body("Resource not found.");
+		}
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	}
+
+	let bookmark = ResourceBookmark {
+		_id: ObjectId::new(),
+		resource_id,
+		account_id: acc._id,
+		date_created: DateTime::now(),
+	};
+	match db.bookmark_resource(bookmark).await {
+		Ok(true) => HttpResponse::Ok().json("Resource bookmarked."),
+		Ok(false) => HttpResponse::Ok().json("Already bookmarked."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Remove a resource from the signed-in account's bookmarks
+#[delete("/resources/bookmark/{id}")]
+async fn delete_resource_bookmark(
+	db: web::Data<Database>,
+	id: web::Path<String>,
+	req: HttpRequest
+) -> HttpResponse {
+	let acc = match get_verified_account(&db, &req, "rate and bookmark resources").await {
+		Ok(acc) => acc,
+		Err(response) => {
+			return response;
+		}
+	};
+
+	let resource_id = match ObjectId::parse_str(id.as_str()) {
+		Ok(resource_id) => resource_id,
+		Err(_) => {
+			return HttpResponse::BadRequest().body("Invalid ID.");
+		}
+	};
+
+	match db.delete_resource_bookmark(resource_id, acc._id).await {
+		Ok(_) => HttpResponse::Ok().json("Bookmark removed."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
@@ -0,0 +1,153 @@
+use actix_web::{ get, post, web::{ self, Data, Json }, HttpRequest, HttpResponse };
+use serde::Deserialize;
+use validator::Validate;
+
+use crate::{
+	models::school_year::{ AlumnusRequest, RosterEntryRequest, SchoolYearRequest },
+	services::db::Database,
+	utilities::auth::{ get_token, is_admin },
+};
+
+// Define the RosterArgs struct
+#[derive(Deserialize)]
+struct RosterArgs {
+	#[serde(default)]
+	roster: String,
+}
+
+// Define the AlumniArgs struct
+#[derive(Deserialize)]
+struct AlumniArgs {
+	#[serde(default)]
+	school_year: String,
+}
+
+// Start a new school year: keep the roster of the year that ended, archive graduates, bump grades, and end exec terms
+#[post("/school_year/rollover")]
+pub async fn roll_over_school_year(
+	db: Data<Database>,
+	request: Json<SchoolYearRequest>
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	// Validate the request
+	match request.validate() {
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	}
+
+	// School years end in order, since every rollover moves the members up a grade
+	match db.get_latest_school_year().await {
+		Ok(Some(latest)) if request.school_year < latest => {
+			return HttpResponse::BadRequest().body(
+				format!("The school year can't be before {}, which has already been rolled over.", latest)
+			);
+		}
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	}
+
+	// A school year can only end once, and only one rollover of it can run at a time
+	match db.claim_school_year(&request.school_year).await {
+		Ok(true) => (),
+		Ok(false) => {
+			return HttpResponse::Conflict().body(
+				"This school year has already been rolled over, or is being rolled over."
+			);
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	}
+
+	match db.roll_over_school_year(&request.school_year).await {
+		Ok(school_year) => HttpResponse::Ok().json(SchoolYearRequest::from(school_year)),
+		Err(err) => {
+			// The rollover can be retried, which finishes what this attempt started
+			if let Err(err) = db.release_school_year(&request.school_year).await {
+				println!("Error releasing school year: {}", err);
+			}
+			HttpResponse::InternalServerError().body(err.to_string())
+		}
+	}
+}
+
+// Get every school year that has been rolled over
+#[get("/school_year/get_all")]
+pub async fn get_school_years(db: Data<Database>, req: HttpRequest) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	match db.get_school_years().await {
+		Ok(school_years) => {
+			let school_years: Vec<SchoolYearRequest> = school_years
+				.into_iter()
+				.map(SchoolYearRequest::from)
+				.collect();
+			HttpResponse::Ok().json(school_years)
+		}
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Get the general and/or executive roster of a past school year
+#[get("/school_year/roster/{school_year}")]
+pub async fn get_roster(
+	db: Data<Database>,
+	school_year: web::Path<String>,
+	query: web::Query<RosterArgs>,
+	req: HttpRequest
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	if !["", "general", "executive"].contains(&query.roster.as_str()) {
+		return HttpResponse::BadRequest().body("Invalid roster. Must be general or executive.");
+	}
+	if !db.school_year_does_exist(&school_year).await {
+		return HttpResponse::NotFound().body("School year not found.");
+	}
+
+	match db.get_roster(&school_year, &query.roster).await {
+		Ok(entries) => {
+			let entries: Vec<RosterEntryRequest> = entries
+				.into_iter()
+				.map(RosterEntryRequest::from)
+				.collect();
+			HttpResponse::Ok().json(entries)
+		}
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Get the alumni, optionally only those who graduated in a given school year
+#[get("/school_year/alumni")]
+pub async fn get_alumni(
+	db: Data<Database>,
+	query: web::Query<AlumniArgs>,
+	req: HttpRequest
+) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &get_token(&req).unwrap_or_default()).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	match db.get_alumni(&query.school_year).await {
+		Ok(alumni) => {
+			let alumni: Vec<AlumnusRequest> = alumni.into_iter().map(AlumnusRequest::from).collect();
+			HttpResponse::Ok().json(alumni)
+		}
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
@@ -0,0 +1,286 @@
+use actix_web::{ get, post, web::{ Data, Json }, HttpRequest, HttpResponse };
+use mongodb::bson::{ oid::ObjectId, DateTime };
+use validator::Validate;
+
+use crate::{
+	models::{
+		account::Account,
+		ctf::{ JoinTeamRequest, Team, TeamMember, TeamRequest, TeamSizeRequest, TransferRequest },
+	},
+	services::db::{ is_duplicate_key, Database },
+	utilities::auth::{ generate_invite_code, get_verified_account, is_admin },
+};
+
+// Get the team of the account making a team request
+async fn get_own_team(db: &Database, acc: &Account) -> Result<Team, HttpResponse> {
+	match db.get_team_by_account_id(acc._id).await {
+		Ok(Some(team)) => Ok(team),
+		Ok(None) => Err(HttpResponse::NotFound().body("You are not on a team.")),
+		Err(err) => Err(HttpResponse::InternalServerError().body(err.to_string())),
+	}
+}
+
+// Get every team and its members
+#[get("/ctf/teams")]
+pub async fn get_teams(db: Data<Database>) -> HttpResponse {
+	match db.get_teams().await {
+		Ok(teams) => {
+			let teams: Vec<TeamRequest> = teams.into_iter().map(TeamRequest::from).collect();
+			HttpResponse::Ok().json(teams)
+		}
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Get the signed-in account's team, with its invite code
+#[get("/ctf/teams/mine")]
+pub async fn get_my_team(db: Data<Database>, req: HttpRequest) -> HttpResponse {
+	let acc = match get_verified_account(&db, &req, "play on a team").await {
+		Ok(acc) => acc,
+		Err(response) => {
+			return response;
+		}
+	};
+
+	match get_own_team(&db, &acc).await {
+		Ok(team) => {
+			let invite_code = team.invite_code.clone();
+			let mut team = TeamRequest::from(team);
+			team.invite_code = invite_code;
+			HttpResponse::Ok().json(team)
+		}
+		Err(response) => response,
+	}
+}
+
+// Create a team with the signed-in account as its captain
+#[post("/ctf/teams/create")]
+pub async fn create_team(
+	db: Data<Database>,
+	request: Json<TeamRequest>,
+	req: HttpRequest
+) -> HttpResponse {
+	let acc = match get_verified_account(&db, &req, "play on a team").await {
+		Ok(acc) => acc,
+		Err(response) => {
+			return response;
+		}
+	};
+
+	// Validate the request
+	match request.validate() {
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	}
+	let name = request.name.trim().to_string();
+
+	match db.get_team_by_account_id(acc._id).await {
+		Ok(Some(_)) => {
+			return HttpResponse::Conflict().body("You are already on a team.");
+		}
+		Ok(None) => (),
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	}
+	match db.team_name_is_taken(&name).await {
+		Ok(true) => {
+			return HttpResponse::Conflict().body("A team with this name already exists.");
+		}
+		Ok(false) => (),
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	}
+
+	let team = Team {
+		_id: ObjectId::new(),
+		name,
+		invite_code: generate_invite_code(),
+		captain_id: acc._id,
+		members: vec![TeamMember {
+			account_id: acc._id,
+			username: acc.username,
+			date_joined: DateTime::now(),
+		}],
+		date_created: DateTime::now(),
+	};
+	match db.create_team(team).await {
+		Ok(Some(result)) => HttpResponse::Ok().json(result),
+		Ok(None) => HttpResponse::Conflict().body("You are already on a team."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Join a team with its invite code
+#[post("/ctf/teams/join")]
+pub async fn join_team(
+	db: Data<Database>,
+	request: Json<JoinTeamRequest>,
+	req: HttpRequest
+) -> HttpResponse {
+	let acc = match get_verified_account(&db, &req, "play on a team").await {
+		Ok(acc) => acc,
+		Err(response) => {
+			return response;
+		}
+	};
+
+	match db.get_team_by_account_id(acc._id).await {
+		Ok(Some(_)) => {
+			return HttpResponse::Conflict().body("You are already on a team.");
+		}
+		Ok(None) => (),
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	}
+
+	let invite_code = request.invite_code.trim().to_uppercase();
+	let team = match db.get_team_by_invite_code(&invite_code).await {
+		Ok(Some(team)) => team,
+		Ok(None) => {
+			return HttpResponse::NotFound().body("Invalid invite code.");
+		}
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+	let max_team_size = match db.get_team_size_limit().await {
+		Ok(max_team_size) => max_team_size,
+		Err(err) => {
+			return HttpResponse::InternalServerError().body(err.to_string());
+		}
+	};
+
+	let member = TeamMember {
+		account_id: acc._id,
+		username: acc.username,
+		date_joined: DateTime::now(),
+	};
+	match db.join_team(team._id, member, max_team_size).await {
+		Ok(true) => HttpResponse::Ok().json("Joined the team."),
+		Ok(false) => HttpResponse::Conflict().body("The team is full."),
+		// The account joined another team at the same time
+		Err(err) if is_duplicate_key(&err) => HttpResponse::Conflict().body("You are already on a team."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Leave the signed-in account's team, deleting it if nobody is left
+#[post("/ctf/teams/leave")]
+pub async fn leave_team(db: Data<Database>, req: HttpRequest) -> HttpResponse {
+	let acc = match get_verified_account(&db, &req, "play on a team").await {
+		Ok(acc) => acc,
+		Err(response) => {
+			return response;
+		}
+	};
+	let team = match get_own_team(&db, &acc).await {
+		Ok(team) => team,
+		Err(response) => {
+			return response;
+		}
+	};
+
+	if team.members.len() <= 1 {
+		return match db.delete_team(team._id).await {
+			Ok(_) => HttpResponse::Ok().json("Left the team, which has been deleted."),
+			Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+		};
+	}
+	if team.captain_id == acc._id {
+		return HttpResponse::BadRequest().body("Make someone else the captain before leaving.");
+	}
+
+	// The solves made while on the team stay with the team
+	match db.leave_team(team._id, acc._id).await {
+		Ok(_) => HttpResponse::Ok().json("Left the team."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Make another member the captain of the team
+#[post("/ctf/teams/transfer")]
+pub async fn transfer_captain(
+	db: Data<Database>,
+	request: Json<TransferRequest>,
+	req: HttpRequest
+) -> HttpResponse {
+	let acc = match get_verified_account(&db, &req, "play on a team").await {
+		Ok(acc) => acc,
+		Err(response) => {
+			return response;
+		}
+	};
+	let team = match get_own_team(&db, &acc).await {
+		Ok(team) => team,
+		Err(response) => {
+			return response;
+		}
+	};
+	if team.captain_id != acc._id {
+		return HttpResponse::Forbidden().body("Only the captain can do this.");
+	}
+
+	let member = match team.members.iter().find(|member| member.username == request.username) {
+		Some(member) => member,
+		None => {
+			return HttpResponse::NotFound().body("Member not found.");
+		}
+	};
+	match db.set_team_captain(team._id, member.account_id).await {
+		Ok(_) => HttpResponse::Ok().json("Captain changed."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Replace the team's invite code so the old one stops working
+#[post("/ctf/teams/invite/reset")]
+pub async fn reset_invite_code(db: Data<Database>, req: HttpRequest) -> HttpResponse {
+	let acc = match get_verified_account(&db, &req, "play on a team").await {
+		Ok(acc) => acc,
+		Err(response) => {
+			return response;
+		}
+	};
+	let team = match get_own_team(&db, &acc).await {
+		Ok(team) => team,
+		Err(response) => {
+			return response;
+		}
+	};
+	if team.captain_id != acc._id {
+		return HttpResponse::Forbidden().body("Only the captain can do this.");
+	}
+
+	let invite_code = generate_invite_code();
+	match db.set_team_invite_code(team._id, invite_code.clone()).await {
+		Ok(_) => HttpResponse::Ok().json(invite_code),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
+
+// Set how many people can be on a team (teams already over the limit keep their members)
+#[post("/ctf/teams/size")]
+pub async fn set_max_team_size(db: Data<Database>, request: Json<TeamSizeRequest>) -> HttpResponse {
+	// Verify the admin token
+	if !is_admin(&db, &request.token).await {
+		return HttpResponse::Unauthorized().body("Unauthorized.");
+	}
+
+	// Validate the request
+	match request.validate() {
+		Ok(_) => (),
+		Err(err) => {
+			return HttpResponse::BadRequest().body(err.to_string());
+		}
+	}
+
+	match db.set_max_team_size(request.max_team_size).await {
+		Ok(_) => HttpResponse::Ok().json("Team size set."),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
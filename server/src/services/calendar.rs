@@ -0,0 +1,152 @@
+use chrono::{ DateTime, Utc };
+use chrono_tz::America::Toronto;
+
+use crate::{ models::event::Event, services::{ email::client_url, feed::to_chrono } };
+
+// Define the CalendarEvent struct (one VEVENT of an iCalendar file, with a status of CONFIRMED, TENTATIVE or CANCELLED)
+pub struct CalendarEvent {
+	pub uid: String,
+	pub summary: String,
+	pub description: String,
+	pub location: String,
+	pub url: String,
+	pub starts_at: DateTime<Utc>,
+	pub ends_at: DateTime<Utc>,
+	pub date_updated: DateTime<Utc>,
+	pub sequence: u32,
+	pub status: String,
+}
+
+// The time zone the club's events happen in
+pub const TIME_ZONE: &str = "America/Toronto";
+
+// The daylight saving rules of America/Toronto (in effect since 2007)
+const VTIMEZONE: [&str; 17] = [
+	"BEGIN:VTIMEZONE",
+	"TZID:America/Toronto",
+	"BEGIN:DAYLIGHT",
+	"TZOFFSETFROM:-0500",
+	"TZOFFSETTO:-0400",
+	"TZNAME:EDT",
+	"DTSTART:19700308T020000",
+	"RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=2SU",
+	"END:DAYLIGHT",
+	"BEGIN:STANDARD",
+	"TZOFFSETFROM:-0400",
+	"TZOFFSETTO:-0500",
+	"TZNAME:EST",
+	"DTSTART:19701101T020000",
+	"RRULE:FREQ=YEARLY;BYMONTH=11;BYDAY=1SU",
+	"END:STANDARD",
+	"END:VTIMEZONE",
+];
+
+// Format a date in the club's time zone for people to read
+pub fn local_time(date: mongodb::bson::DateTime) -> String {
+	to_chrono(date).with_timezone(&Toronto).format("%A, %B %-d at %-I:%M %p %Z").to_string()
+}
+
+// Escape the special characters of a text value
+fn escape_text(text: &str) -> String {
+	text.replace('\\', "\\\\")
+		.replace(';', "\\;")
+		.replace(',', "\\,")
+		.replace("\r\n", "\\n")
+		.replace('\n', "\\n")
+}
+
+// Fold a content line so that no line is longer than 75 octets
+fn fold(line: &str) -> String {
+	let mut folded = String::new();
+	let mut length = 0;
+	for character in line.chars() {
+		if length + character.len_utf8() > 75 {
+			folded.push_str("\r\n ");
+			length = 1;
+		}
+		folded.push(character);
+		length += character.len_utf8();
+	}
+	folded.push_str("\r\n");
+	folded
+}
+
+// Format a date as a UTC date-time
+fn format_date(date: &DateTime<Utc>) -> String {
+	date.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+// Format a date as a local date-time in the club's time zone
+fn format_local_date(date: &DateTime<Utc>) -> String {
+	format!("TZID={}:{}", TIME_ZONE, date.with_timezone(&Toronto).format("%Y%m%dT%H%M%S"))
+}
+
+// Write an iCalendar file with the given events
+pub fn calendar(name: &str, events: &[CalendarEvent]) -> String {
+	calendar_with_method(name, "PUBLISH", events)
+}
+
+// Write an iCalendar file with the given method (PUBLISH to add or update events, CANCEL to remove them)
+pub fn calendar_with_method(name: &str, method: &str, events: &[CalendarEvent]) -> String {
+	let mut lines = vec![
+		"BEGIN:VCALENDAR".to_string(),
+		"VERSION:2.0".to_string(),
+		"PRODID:-//HB CyberTech//Website//EN".to_string(),
+		"CALSCALE:GREGORIAN".to_string(),
+		format!("METHOD:{}", method),
+		format!("X-WR-CALNAME:{}", escape_text(name)),
+		format!("X-WR-TIMEZONE:{}", TIME_ZONE),
+	];
+	lines.extend(VTIMEZONE.iter().map(|line| line.to_string()));
+
+	for event in events {
+		lines.push("BEGIN:VEVENT".to_string());
+		lines.push(format!("UID:{}", event.uid));
+		lines.push(format!("DTSTAMP:{}", format_date(&event.date_updated)));
+		lines.push(format!("DTSTART;{}", format_local_date(&event.starts_at)));
+		lines.push(format!("DTEND;{}", format_local_date(&event.ends_at)));
+		lines.push(format!("LAST-MODIFIED:{}", format_date(&event.date_updated)));
+		lines.push(format!("SEQUENCE:{}", event.sequence));
+		lines.push(format!("SUMMARY:{}", escape_text(&event.summary)));
+		if !event.description.is_empty() {
+			lines.push(format!("DESCRIPTION:{}", escape_text(&event.description)));
+		}
+		if !event.location.is_empty() {
+			lines.push(format!("LOCATION:{}", escape_text(&event.location)));
+		}
+		if !event.url.is_empty() {
+			lines.push(format!("URL:{}", event.url));
+		}
+		lines.push(format!("STATUS:{}", event.status));
+		lines.push("END:VEVENT".to_string());
+	}
+	lines.push("END:VCALENDAR".to_string());
+
+	lines
+		.iter()
+		.map(|line| fold(line))
+		.collect()
+}
+
+// Turn a club event into a calendar event, marking it tentative for people still on the waitlist
+pub fn event_to_calendar(event: &Event, waitlisted: bool) -> CalendarEvent {
+	let status = if event.cancelled {
+		"CANCELLED"
+	} else if waitlisted {
+		"TENTATIVE"
+	} else {
+		"CONFIRMED"
+	};
+	CalendarEvent {
+		uid: format!("event-{}@hbcybertech", event._id),
+		summary: event.title.clone(),
+		description: event.description.clone(),
+		location: event.location.clone(),
+		url: format!("{}/events/{}", client_url(), event._id),
+		starts_at: to_chrono(event.starts_at),
+		ends_at: to_chrono(event.ends_at),
+		date_updated: to_chrono(event.date_updated),
+		sequence: event.sequence,
+		status: status.to_string(),
+	}
+}
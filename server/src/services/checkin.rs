@@ -0,0 +1,36 @@
+use std::env::var;
+use hmac::{ Hmac, Mac };
+use mongodb::bson::{ oid::ObjectId, DateTime };
+use qrcode::{ render::svg, QrCode };
+use sha2::Sha256;
+
+// How long a check-in code stays on screen before it changes
+const CODE_PERIOD_MILLIS: i64 = 60 * 1000;
+
+// Get the code of an event for one period, signed with the server's secret so nobody can guess it
+fn code_for_period(event_id: ObjectId, period: i64) -> String {
+	let mut mac = Hmac::<Sha256>
+		::new_from_slice(var("SECRET").unwrap().as_bytes())
+		.expect("Error creating HMAC.");
+	mac.update(format!("{}:{}", event_id, period).as_bytes());
+	hex::encode_upper(&mac.finalize().into_bytes()[..4])
+}
+
+// Get the current check-in code of an event and when it expires
+pub fn current_code(event_id: ObjectId) -> (String, DateTime) {
+	let period = DateTime::now().timestamp_millis() / CODE_PERIOD_MILLIS;
+	(code_for_period(event_id, period), DateTime::from_millis((period + 1) * CODE_PERIOD_MILLIS))
+}
+
+// Check a check-in code, also accepting the previous one for people who scanned it just before it changed
+pub fn verify_code(event_id: ObjectId, code: &str) -> bool {
+	let period = DateTime::now().timestamp_millis() / CODE_PERIOD_MILLIS;
+	let code = code.trim().to_uppercase();
+	code == code_for_period(event_id, period) || code == code_for_period(event_id, period - 1)
+}
+
+// Draw a QR code as an SVG image
+pub fn qr_svg(data: &str) -> Result<String, qrcode::types::QrError> {
+	let code = QrCode::new(data.as_bytes())?;
+	Ok(code.render::<svg::Color>().min_dimensions(256, 256).quiet_zone(true).build())
+}
@@ -1,23 +1,86 @@
 use futures_util::TryStreamExt;
 use mongodb::{
-	bson::{ doc, oid::ObjectId, Bson, DateTime, Uuid },
-	error::Error,
+	bson::{ doc, oid::ObjectId, to_bson, Bson, DateTime, Document, Uuid },
+	error::{ Error, ErrorKind, WriteFailure },
+	options::{ IndexOptions, ReturnDocument },
 	results::{ DeleteResult, InsertOneResult, UpdateResult },
 	Collection,
+	Cursor,
+	IndexModel,
 };
 use passwords::{ analyzer, scorer, PasswordGenerator };
 use rand::Rng;
-use std::env;
+use std::{ env, time::Duration };
 
 use crate::models::{
 	account::Account,
 	admin::Admin,
 	announcement::Announcement,
+	application::{ ApplicationEdit, ReviewerNote },
+	application_window::ApplicationWindow,
+	attendance::Attendance,
+	broadcast::{ Broadcast, BroadcastAudience },
+	ctf::{
+		Challenge,
+		Competition,
+		CtfSettings,
+		HintUnlock,
+		Solve,
+		Submission,
+		Team,
+		TeamMember,
+	},
+	evaluation::Evaluation,
+	event::{ Event, Rsvp },
 	executive_member::ExecutiveMember,
 	forum_post::Post,
 	general_member::GeneralMember,
-	resource::Resource,
+	material::Material,
+	interview::InterviewSlot,
+	learning_path::{ LearningPath, PathProgress },
+	resource::{ LinkHealth, Resource, ResourceBookmark, ResourceClick, ResourceRating, ResourceTag },
+	rubric::Rubric,
+	school_year::{ Alumnus, RosterEntry, SchoolYear },
 };
+use crate::utilities::pagination_args::ResourcePaginationArgs;
+
+// How many people can be on a CTF team unless an admin changes it
+const DEFAULT_MAX_TEAM_SIZE: u32 = 4;
+
+// How long a visitor's clicks on a resource count as one
+const CLICK_WINDOW_MILLIS: i64 = 60 * 60 * 1000;
+
+// How long a school year rollover can run before another request may take it over
+const ROLLOVER_LEASE_MILLIS: i64 = 10 * 60 * 1000;
+
+// Check if a write failed because it broke a unique index
+pub fn is_duplicate_key(err: &Error) -> bool {
+	matches!(
+		*err.kind,
+		ErrorKind::Write(WriteFailure::WriteError(ref write_error)) if write_error.code == 11000
+	)
+}
+
+// Build a unique index on the given keys
+fn unique_index(keys: Document) -> IndexModel {
+	IndexModel::builder()
+		.keys(keys)
+		.options(IndexOptions::builder().unique(true).build())
+		.build()
+}
+
+// Build a unique index on the given keys that leaves out documents without the field
+fn sparse_unique_index(keys: Document, field: &str) -> IndexModel {
+	IndexModel::builder()
+		.keys(keys)
+		.options(
+			IndexOptions::builder()
+				.unique(true)
+				.partial_filter_expression(doc! { field: { "$exists": true } })
+				.build()
+		)
+		.build()
+}
 
 // Define the Database struct
 pub struct Database {
@@ -28,6 +91,31 @@ pub struct Database {
 	account: Collection<Account>,
 	admin: Collection<Admin>,
 	resource: Collection<Resource>,
+	broadcast: Collection<Broadcast>,
+	application_window: Collection<ApplicationWindow>,
+	rubric: Collection<Rubric>,
+	evaluation: Collection<Evaluation>,
+	school_year: Collection<SchoolYear>,
+	roster: Collection<RosterEntry>,
+	alumni: Collection<Alumnus>,
+	interview_slot: Collection<InterviewSlot>,
+	event: Collection<Event>,
+	rsvp: Collection<Rsvp>,
+	attendance: Collection<Attendance>,
+	challenge: Collection<Challenge>,
+	submission: Collection<Submission>,
+	solve: Collection<Solve>,
+	ctf_settings: Collection<CtfSettings>,
+	team: Collection<Team>,
+	hint_unlock: Collection<HintUnlock>,
+	competition: Collection<Competition>,
+	material: Collection<Material>,
+	resource_tag: Collection<ResourceTag>,
+	learning_path: Collection<LearningPath>,
+	path_progress: Collection<PathProgress>,
+	resource_rating: Collection<ResourceRating>,
+	resource_bookmark: Collection<ResourceBookmark>,
+	resource_click: Collection<ResourceClick>,
 }
 
 impl Database {
@@ -51,9 +139,34 @@ impl Database {
 		let account: Collection<Account> = db.collection("Accounts");
 		let admin: Collection<Admin> = db.collection("Admin");
 		let resource: Collection<Resource> = db.collection("Resources");
+		let broadcast: Collection<Broadcast> = db.collection("Broadcasts");
+		let application_window: Collection<ApplicationWindow> = db.collection("ApplicationWindows");
+		let rubric: Collection<Rubric> = db.collection("Rubrics");
+		let evaluation: Collection<Evaluation> = db.collection("Evaluations");
+		let school_year: Collection<SchoolYear> = db.collection("SchoolYears");
+		let roster: Collection<RosterEntry> = db.collection("Rosters");
+		let alumni: Collection<Alumnus> = db.collection("Alumni");
+		let interview_slot: Collection<InterviewSlot> = db.collection("InterviewSlots");
+		let event: Collection<Event> = db.collection("Events");
+		let rsvp: Collection<Rsvp> = db.collection("Rsvps");
+		let attendance: Collection<Attendance> = db.collection("Attendance");
+		let challenge: Collection<Challenge> = db.collection("CtfChallenges");
+		let submission: Collection<Submission> = db.collection("CtfSubmissions");
+		let solve: Collection<Solve> = db.collection("CtfSolves");
+		let ctf_settings: Collection<CtfSettings> = db.collection("CtfSettings");
+		let team: Collection<Team> = db.collection("CtfTeams");
+		let hint_unlock: Collection<HintUnlock> = db.collection("CtfHintUnlocks");
+		let competition: Collection<Competition> = db.collection("CtfCompetitions");
+		let material: Collection<Material> = db.collection("Materials");
+		let resource_tag: Collection<ResourceTag> = db.collection("ResourceTags");
+		let learning_path: Collection<LearningPath> = db.collection("LearningPaths");
+		let path_progress: Collection<PathProgress> = db.collection("PathProgress");
+		let resource_rating: Collection<ResourceRating> = db.collection("ResourceRatings");
+		let resource_bookmark: Collection<ResourceBookmark> = db.collection("ResourceBookmarks");
+		let resource_click: Collection<ResourceClick> = db.collection("ResourceClicks");
 
 		// Return the Database struct
-		Database {
+		let database = Database {
 			general_member,
 			executive_member,
 			announcement,
@@ -61,7 +174,102 @@ impl Database {
 			account,
 			admin,
 			resource,
+			broadcast,
+			application_window,
+			rubric,
+			evaluation,
+			school_year,
+			roster,
+			alumni,
+			interview_slot,
+			event,
+			rsvp,
+			attendance,
+			challenge,
+			submission,
+			solve,
+			ctf_settings,
+			team,
+			hint_unlock,
+			competition,
+			material,
+			resource_tag,
+			learning_path,
+			path_progress,
+			resource_rating,
+			resource_bookmark,
+			resource_click,
+		};
+
+		// Bring documents saved by older versions of the server up to date
+		if let Err(err) = database.migrate().await {
+			println!("Error migrating the database: {}", err);
+		}
+		if let Err(err) = database.create_indexes().await {
+			println!("Error creating indexes: {}", err);
+		}
+
+		database
+	}
+
+	// Create the unique indexes that keep concurrent requests from saving duplicates
+	async fn create_indexes(&self) -> Result<(), Error> {
+		self.school_year.create_index(unique_index(doc! { "school_year": 1 })).await?;
+		self.rsvp.create_index(unique_index(doc! { "event_id": 1, "account_id": 1 })).await?;
+		self.attendance.create_index(unique_index(doc! { "event_id": 1, "account_id": 1 })).await?;
+		// An account can only be on one team (teams nobody is on yet don't count)
+		self.team
+			.create_index(sparse_unique_index(doc! { "members.account_id": 1 }, "members.account_id")).await?;
+		self.resource_tag.create_index(unique_index(doc! { "name": 1 })).await?;
+		self.resource_rating.create_index(unique_index(doc! { "resource_id": 1, "account_id": 1 })).await?;
+		self.resource_bookmark.create_index(unique_index(doc! { "resource_id": 1, "account_id": 1 })).await?;
+		self.resource_click
+			.create_index(unique_index(doc! { "resource_id": 1, "visitor": 1, "window": 1 })).await?;
+		// Clicks are only needed until their window is over
+		self.resource_click.create_index(
+			IndexModel::builder()
+				.keys(doc! { "date_created": 1 })
+				.options(
+					IndexOptions::builder()
+						.expire_after(Duration::from_millis((CLICK_WINDOW_MILLIS * 2) as u64))
+						.build()
+				)
+				.build()
+		).await?;
+
+		Ok(())
+	}
+
+	// Fill in the fields that documents saved before they existed are missing
+	async fn migrate(&self) -> Result<(), Error> {
+		self.announcement.update_many(
+			doc! { "date_published": { "$exists": false } },
+			vec![doc! { "$set": { "date_published": { "$ifNull": ["$publish_at", "$date_created"] } } }]
+		).await?;
+
+		// Members from before applications were reviewed are already in the club
+		self.general_member.update_many(
+			doc! { "status": { "$exists": false } },
+			doc! { "$set": { "status": "accepted" } }
+		).await?;
+		self.executive_member.update_many(
+			doc! { "status": { "$exists": false } },
+			doc! { "$set": { "status": "accepted" } }
+		).await?;
+
+		// Start the managed tag list with the tags resources already had before it existed
+		if self.resource_tag.count_documents(doc! {}).await? == 0 {
+			let names = self.resource.distinct("tags", doc! {}).await?;
+			for name in names.iter().filter_map(|name| name.as_str()) {
+				self.create_resource_tag(ResourceTag {
+					_id: ObjectId::new(),
+					name: name.to_string(),
+					date_created: DateTime::now(),
+				}).await?;
+			}
 		}
+
+		Ok(())
 	}
 
 	// General Members
@@ -109,6 +317,68 @@ impl Database {
 
 		Ok(result)
 	}
+	pub async fn get_general_member_by_id(&self, id: String) -> Result<Option<GeneralMember>, Error> {
+		let object_id = ObjectId::parse_str(&id).expect("Error parsing ID.");
+		let member = self.general_member.find_one(doc! { "_id": object_id }).await?;
+		Ok(member)
+	}
+	pub async fn update_general_member_status(
+		&self,
+		id: String,
+		note: ReviewerNote
+	) -> Result<UpdateResult, Error> {
+		let object_id = ObjectId::parse_str(&id).expect("Error parsing ID.");
+		let result = self.general_member.update_one(
+			doc! { "_id": object_id },
+			doc! {
+				"$set": { "status": note.status.clone() },
+				"$push": { "reviewer_notes": to_bson(&note)? },
+			}
+		).await?;
+
+		Ok(result)
+	}
+	pub async fn gen_mem_name_is_taken(&self, full_name: &str, id: ObjectId) -> bool {
+		let existing_member = self.general_member
+			.find_one(doc! { "full_name": full_name, "_id": { "$ne": id } }).await
+			.ok();
+		if let Some(existing_member) = existing_member {
+			return existing_member.is_some();
+		}
+		false
+	}
+	pub async fn edit_general_member(
+		&self,
+		id: ObjectId,
+		fields: Document,
+		edit: ApplicationEdit
+	) -> Result<UpdateResult, Error> {
+		let result = self.general_member.update_one(
+			doc! { "_id": id },
+			doc! { "$set": fields, "$push": { "edit_history": to_bson(&edit)? } }
+		).await?;
+
+		Ok(result)
+	}
+	pub async fn get_general_members_by_account_id(
+		&self,
+		account_id: ObjectId
+	) -> Result<Vec<GeneralMember>, Error> {
+		let cursor = self.general_member
+			.find(doc! { "account_id": account_id })
+			.sort(doc! { "date_created": -1 }).await?;
+		let members: Vec<GeneralMember> = cursor.try_collect().await?;
+		Ok(members)
+	}
+	pub async fn get_member_account_ids(&self) -> Result<Vec<ObjectId>, Error> {
+		// Accepted members that are linked to an account
+		let ids = self.general_member
+			.distinct("account_id", doc! { "status": "accepted", "account_id": { "$ne": Bson::Null } }).await?
+			.into_iter()
+			.filter_map(|id| id.as_object_id())
+			.collect();
+		Ok(ids)
+	}
 	pub async fn get_all_general_members(
 		&self,
 		page: u32,
@@ -117,133 +387,1403 @@ impl Database {
 		field: String
 	) -> Result<Vec<GeneralMember>, Error> {
 		let skip = (page - 1) * limit;
-		let filter = if search.is_empty() {
+		let filter = Database::general_member_filter(search, field);
+		let cursor = self.general_member.find(filter).skip(skip.into()).limit(limit.into()).await?;
+		let members = cursor.try_collect().await?;
+		Ok(members)
+	}
+	pub async fn export_general_members(
+		&self,
+		search: String,
+		field: String
+	) -> Result<Cursor<GeneralMember>, Error> {
+		let filter = Database::general_member_filter(search, field);
+		self.general_member.find(filter).sort(doc! { "date_created": 1 }).await
+	}
+	fn general_member_filter(search: String, field: String) -> Document {
+		if search.is_empty() {
 			doc! {}
 		} else if field == "grade".to_owned() {
 			let num = search.parse::<i32>().unwrap_or(9);
 			doc! { "grade": { "$regex": num, "$options": "i" } }
 		} else {
 			doc! { field : { "$regex": search, "$options": "i" } }
-		};
-		let cursor = self.general_member.find(filter).skip(skip.into()).limit(limit.into()).await?;
+		}
+	}
+
+	// Executive Members
+	pub async fn exec_mem_does_exist_full_name(&self, full_name: String) -> bool {
+		let existing_member = self.executive_member
+			.find_one(doc! { "full_name": &full_name, "term_ended": { "$ne": true } }).await
+			.ok();
+		if let Some(existing_member) = existing_member {
+			return existing_member.is_some();
+		}
+		false
+	}
+	pub async fn exec_mem_does_exist_email(&self, email: String) -> bool {
+		let existing_member = self.executive_member
+			.find_one(doc! { "email": &email, "term_ended": { "$ne": true } }).await
+			.ok();
+		if let Some(existing_member) = existing_member {
+			return existing_member.is_some();
+		}
+		false
+	}
+	pub async fn exec_mem_does_exist(&self, executive_member: &ExecutiveMember) -> bool {
+		let existing_member = self.executive_member
+			.find_one(
+				doc! {
+					"$or": [{ "full_name": &executive_member.full_name }, { "email": &executive_member.email }],
+					"term_ended": { "$ne": true },
+				}
+			).await
+			.ok();
+		if let Some(existing_member) = existing_member {
+			return existing_member.is_some();
+		}
+		false
+	}
+	pub async fn create_executive_member(
+		&self,
+		executive_member: ExecutiveMember
+	) -> Result<InsertOneResult, Error> {
+		if self.exec_mem_does_exist(&executive_member).await {
+			return Err(
+				Error::from(
+					std::io::Error::new(std::io::ErrorKind::AlreadyExists, "Member already exists.")
+				)
+			);
+		}
+
+		let result = self.executive_member
+			.insert_one(executive_member).await
+			.ok()
+			.expect("Error creating executive member.");
+
+		Ok(result)
+	}
+	pub async fn get_executive_member_by_id(
+		&self,
+		id: String
+	) -> Result<Option<ExecutiveMember>, Error> {
+		let object_id = ObjectId::parse_str(&id).expect("Error parsing ID.");
+		let member = self.executive_member.find_one(doc! { "_id": object_id }).await?;
+		Ok(member)
+	}
+	pub async fn update_executive_member_status(
+		&self,
+		id: String,
+		note: ReviewerNote
+	) -> Result<UpdateResult, Error> {
+		let object_id = ObjectId::parse_str(&id).expect("Error parsing ID.");
+		let result = self.executive_member.update_one(
+			doc! { "_id": object_id },
+			doc! {
+				"$set": { "status": note.status.clone() },
+				"$push": { "reviewer_notes": to_bson(&note)? },
+			}
+		).await?;
+
+		Ok(result)
+	}
+	pub async fn exec_mem_name_is_taken(&self, full_name: &str, id: ObjectId) -> bool {
+		let existing_member = self.executive_member
+			.find_one(
+				doc! { "full_name": full_name, "_id": { "$ne": id }, "term_ended": { "$ne": true } }
+			).await
+			.ok();
+		if let Some(existing_member) = existing_member {
+			return existing_member.is_some();
+		}
+		false
+	}
+	pub async fn edit_executive_member(
+		&self,
+		id: ObjectId,
+		fields: Document,
+		edit: ApplicationEdit
+	) -> Result<UpdateResult, Error> {
+		let result = self.executive_member.update_one(
+			doc! { "_id": id },
+			doc! { "$set": fields, "$push": { "edit_history": to_bson(&edit)? } }
+		).await?;
+
+		Ok(result)
+	}
+	pub async fn get_executive_members_by_account_id(
+		&self,
+		account_id: ObjectId
+	) -> Result<Vec<ExecutiveMember>, Error> {
+		let cursor = self.executive_member
+			.find(doc! { "account_id": account_id })
+			.sort(doc! { "date_created": -1 }).await?;
+		let members: Vec<ExecutiveMember> = cursor.try_collect().await?;
+		Ok(members)
+	}
+	pub async fn get_all_executive_members(
+		&self,
+		page: u32,
+		limit: u32,
+		search: String,
+		field: String
+	) -> Result<Vec<ExecutiveMember>, Error> {
+		let skip = (page - 1) * limit;
+		let filter = Database::executive_member_filter(search, field);
+		let cursor = self.executive_member.find(filter).skip(skip.into()).limit(limit.into()).await?;
 		let members = cursor.try_collect().await?;
 		Ok(members)
 	}
+	pub async fn export_executive_members(
+		&self,
+		search: String,
+		field: String
+	) -> Result<Cursor<ExecutiveMember>, Error> {
+		let filter = Database::executive_member_filter(search, field);
+		self.executive_member.find(filter).sort(doc! { "date_created": 1 }).await
+	}
+	fn executive_member_filter(search: String, field: String) -> Document {
+		if field == "marketing" || field == "events" || field == "development" {
+			doc! { "exec_type": field }
+		} else if search.is_empty() {
+			doc! {}
+		} else if field == "grade".to_owned() {
+			let num = search.parse::<i32>().unwrap_or(9);
+			doc! { "grade": { "$regex": num, "$options": "i" } }
+		} else {
+			doc! { field : { "$regex": search, "$options": "i" } }
+		}
+	}
+
+	// Executive Application Rubrics and Evaluations
+	pub async fn get_executive_members_by_exec_type(
+		&self,
+		exec_type: &str
+	) -> Result<Vec<ExecutiveMember>, Error> {
+		let cursor = self.executive_member
+			.find(doc! { "exec_type": exec_type, "term_ended": { "$ne": true } }).await?;
+		let members: Vec<ExecutiveMember> = cursor.try_collect().await?;
+		Ok(members)
+	}
+	pub async fn get_rubric(&self, exec_type: &str) -> Result<Option<Rubric>, Error> {
+		let rubric = self.rubric.find_one(doc! { "exec_type": exec_type }).await?;
+		Ok(rubric)
+	}
+	pub async fn set_rubric(&self, rubric: Rubric) -> Result<UpdateResult, Error> {
+		let result = self.rubric
+			.update_one(
+				doc! { "exec_type": &rubric.exec_type },
+				doc! {
+					"$set": { "criteria": to_bson(&rubric.criteria)?, "date_updated": rubric.date_updated },
+					"$setOnInsert": { "_id": rubric._id },
+				}
+			)
+			.upsert(true).await?;
+
+		Ok(result)
+	}
+	pub async fn save_evaluation(&self, evaluation: Evaluation) -> Result<UpdateResult, Error> {
+		// Each reviewer has one evaluation per application, which they can revise
+		let result = self.evaluation
+			.update_one(
+				doc! { "application_id": evaluation.application_id, "reviewer": &evaluation.reviewer },
+				doc! {
+					"$set": {
+						"scores": to_bson(&evaluation.scores)?,
+						"comment": &evaluation.comment,
+						"date_created": evaluation.date_created,
+					},
+					"$setOnInsert": { "_id": evaluation._id, "exec_type": &evaluation.exec_type },
+				}
+			)
+			.upsert(true).await?;
+
+		Ok(result)
+	}
+	pub async fn get_evaluations_by_application_id(
+		&self,
+		id: String
+	) -> Result<Vec<Evaluation>, Error> {
+		let object_id = ObjectId::parse_str(&id).expect("Error parsing ID.");
+		let cursor = self.evaluation.find(doc! { "application_id": object_id }).await?;
+		let evaluations: Vec<Evaluation> = cursor.try_collect().await?;
+		Ok(evaluations)
+	}
+	pub async fn get_evaluations_by_exec_type(
+		&self,
+		exec_type: &str
+	) -> Result<Vec<Evaluation>, Error> {
+		let cursor = self.evaluation.find(doc! { "exec_type": exec_type }).await?;
+		let evaluations: Vec<Evaluation> = cursor.try_collect().await?;
+		Ok(evaluations)
+	}
+
+	// Executive Interviews
+	pub async fn set_interview_token(&self, id: ObjectId, token: String) -> Result<UpdateResult, Error> {
+		let result = self.executive_member.update_one(
+			doc! { "_id": id },
+			doc! { "$set": { "interview_token": token } }
+		).await?;
+
+		Ok(result)
+	}
+	pub async fn create_interview_slot(&self, slot: InterviewSlot) -> Result<InsertOneResult, Error> {
+		let result = self.interview_slot.insert_one(slot).await?;
+		Ok(result)
+	}
+	pub async fn get_interview_slot_by_id(&self, id: String) -> Result<Option<InterviewSlot>, Error> {
+		let object_id = ObjectId::parse_str(&id).expect("Error parsing ID.");
+		let slot = self.interview_slot.find_one(doc! { "_id": object_id }).await?;
+		Ok(slot)
+	}
+	pub async fn get_interview_slots(&self, exec_type: &str) -> Result<Vec<InterviewSlot>, Error> {
+		let filter = if exec_type.is_empty() {
+			doc! {}
+		} else {
+			doc! { "exec_type": { "$in": [exec_type, ""] } }
+		};
+		let cursor = self.interview_slot.find(filter).sort(doc! { "starts_at": 1 }).await?;
+		let slots: Vec<InterviewSlot> = cursor.try_collect().await?;
+		Ok(slots)
+	}
+	pub async fn get_overlapping_interview_slots(
+		&self,
+		slot: &InterviewSlot
+	) -> Result<Vec<InterviewSlot>, Error> {
+		let cursor = self.interview_slot.find(
+			doc! { "starts_at": { "$lt": slot.ends_at }, "ends_at": { "$gt": slot.starts_at } }
+		).await?;
+		let slots: Vec<InterviewSlot> = cursor.try_collect().await?;
+		Ok(slots)
+	}
+	pub async fn get_available_interview_slots(
+		&self,
+		exec_type: &str,
+		application_id: ObjectId
+	) -> Result<Vec<InterviewSlot>, Error> {
+		// Future slots of the exec type that nobody else has booked
+		let cursor = self.interview_slot
+			.find(
+				doc! {
+					"exec_type": { "$in": [exec_type, ""] },
+					"starts_at": { "$gt": DateTime::now() },
+					"application_id": { "$in": [Bson::Null, application_id] },
+				}
+			)
+			.sort(doc! { "starts_at": 1 }).await?;
+		let slots: Vec<InterviewSlot> = cursor.try_collect().await?;
+		Ok(slots)
+	}
+	pub async fn book_interview_slot(
+		&self,
+		id: ObjectId,
+		application_id: ObjectId
+	) -> Result<Option<Vec<InterviewSlot>>, Error> {
+		// Only book the slot if it is still free, so two candidates can't get the same slot
+		let result = self.interview_slot.update_one(
+			doc! { "_id": id, "application_id": Bson::Null, "starts_at": { "$gt": DateTime::now() } },
+			doc! { "$set": { "application_id": application_id, "reminder_sent": false } }
+		).await?;
+		if result.modified_count == 0 {
+			return Ok(None);
+		}
+
+		// Free the slots the candidate had before, if any, and return them so they can be cancelled
+		let mut previous: Vec<InterviewSlot> = Vec::new();
+		while
+			let Some(slot) = self.interview_slot.find_one_and_update(
+				doc! { "application_id": application_id, "_id": { "$ne": id } },
+				doc! { "$set": { "application_id": Bson::Null } }
+			).await?
+		{
+			previous.push(slot);
+		}
+
+		Ok(Some(previous))
+	}
+	pub async fn delete_interview_slot(&self, id: String) -> Result<DeleteResult, Error> {
+		let object_id = ObjectId::parse_str(&id).expect("Error parsing ID.");
+		let result = self.interview_slot.delete_one(doc! { "_id": object_id }).await?;
+		Ok(result)
+	}
+	pub async fn get_interviews_needing_reminders(
+		&self,
+		before: DateTime
+	) -> Result<Vec<InterviewSlot>, Error> {
+		let cursor = self.interview_slot.find(
+			doc! {
+				"application_id": { "$ne": Bson::Null },
+				"reminder_sent": false,
+				"starts_at": { "$gt": DateTime::now(), "$lte": before },
+			}
+		).await?;
+		let slots: Vec<InterviewSlot> = cursor.try_collect().await?;
+
+		// Candidates who withdrew or were rejected are not coming
+		let application_ids: Vec<ObjectId> = slots
+			.iter()
+			.filter_map(|slot| slot.application_id)
+			.collect();
+		let active: Vec<ObjectId> = self.executive_member
+			.distinct(
+				"_id",
+				doc! {
+					"_id": { "$in": application_ids },
+					"status": { "$nin": ["withdrawn", "rejected"] },
+				}
+			).await?
+			.into_iter()
+			.filter_map(|id| id.as_object_id())
+			.collect();
+
+		Ok(
+			slots
+				.into_iter()
+				.filter(|slot| slot.application_id.is_some_and(|id| active.contains(&id)))
+				.collect()
+		)
+	}
+	pub async fn mark_interview_reminder_sent(&self, id: ObjectId) -> Result<UpdateResult, Error> {
+		let result = self.interview_slot.update_one(
+			doc! { "_id": id },
+			doc! { "$set": { "reminder_sent": true } }
+		).await?;
+
+		Ok(result)
+	}
+
+	// Application Windows
+	pub async fn get_application_windows(&self) -> Result<Vec<ApplicationWindow>, Error> {
+		let cursor = self.application_window.find(doc! {}).await?;
+		let windows: Vec<ApplicationWindow> = cursor.try_collect().await?;
+		Ok(windows)
+	}
+	pub async fn get_application_window(
+		&self,
+		form: &str,
+		exec_type: &str
+	) -> Result<Option<ApplicationWindow>, Error> {
+		let window = self.application_window.find_one(
+			doc! { "form": form, "exec_type": exec_type }
+		).await?;
+
+		// Executive forms fall back to the window shared by every exec type
+		if window.is_none() && !exec_type.is_empty() {
+			return self.application_window.find_one(doc! { "form": form, "exec_type": "" }).await;
+		}
+
+		Ok(window)
+	}
+	pub async fn is_form_open(&self, form: &str, exec_type: &str) -> Result<bool, Error> {
+		// Without a window, the general form stays open and the executive forms stay closed
+		match self.get_application_window(form, exec_type).await? {
+			Some(window) => Ok(window.is_open()),
+			None => Ok(form == "general"),
+		}
+	}
+	pub async fn set_application_window(
+		&self,
+		window: ApplicationWindow
+	) -> Result<UpdateResult, Error> {
+		let result = self.application_window
+			.update_one(
+				doc! { "form": &window.form, "exec_type": &window.exec_type },
+				doc! {
+					"$set": { "opens_at": window.opens_at, "closes_at": window.closes_at },
+					"$setOnInsert": { "_id": window._id },
+				}
+			)
+			.upsert(true).await?;
+
+		Ok(result)
+	}
+	pub async fn delete_application_window(
+		&self,
+		form: &str,
+		exec_type: &str
+	) -> Result<DeleteResult, Error> {
+		let result = self.application_window.delete_one(
+			doc! { "form": form, "exec_type": exec_type }
+		).await?;
+
+		Ok(result)
+	}
+
+	// School Years
+	pub async fn get_school_years(&self) -> Result<Vec<SchoolYear>, Error> {
+		let cursor = self.school_year
+			.find(doc! { "completed": true })
+			.sort(doc! { "school_year": -1 }).await?;
+		let school_years: Vec<SchoolYear> = cursor.try_collect().await?;
+		Ok(school_years)
+	}
+	pub async fn school_year_does_exist(&self, school_year: &str) -> bool {
+		let existing = self.school_year
+			.find_one(doc! { "school_year": school_year, "completed": true }).await
+			.ok();
+		if let Some(existing) = existing {
+			return existing.is_some();
+		}
+		false
+	}
+	// Get the latest school year that has been rolled over or is being rolled over
+	pub async fn get_latest_school_year(&self) -> Result<Option<String>, Error> {
+		let latest = self.school_year.find_one(doc! {}).sort(doc! { "school_year": -1 }).await?;
+		Ok(latest.map(|latest| latest.school_year))
+	}
+	// Claim a school year for a rollover, returning false if it has been rolled over or another rollover of
+	// it is running. A rollover that failed partway can be claimed again to finish it.
+	pub async fn claim_school_year(&self, school_year: &str) -> Result<bool, Error> {
+		let record = SchoolYear {
+			_id: ObjectId::new(),
+			school_year: school_year.to_string(),
+			general_members: 0,
+			executive_members: 0,
+			promoted: 0,
+			graduated: 0,
+			terms_ended: 0,
+			completed: false,
+			started_at: Some(DateTime::from_millis(0)),
+			date_created: DateTime::now(),
+		};
+		match
+			self.school_year
+				.update_one(doc! { "school_year": school_year }, doc! { "$setOnInsert": to_bson(&record)? })
+				.upsert(true).await
+		{
+			Ok(_) => (),
+			// Another request saved the record at the same time, and the lease below decides who runs
+			Err(err) if is_duplicate_key(&err) => (),
+			Err(err) => {
+				return Err(err);
+			}
+		}
+
+		let lease_expired = DateTime::from_millis(
+			DateTime::now().timestamp_millis() - ROLLOVER_LEASE_MILLIS
+		);
+		let claimed = self.school_year.find_one_and_update(
+			doc! {
+				"school_year": school_year,
+				"completed": false,
+				"started_at": { "$lt": lease_expired },
+			},
+			doc! { "$set": { "started_at": DateTime::now() } }
+		).await?;
+		Ok(claimed.is_some())
+	}
+	// Let a failed rollover be retried straight away
+	pub async fn release_school_year(&self, school_year: &str) -> Result<UpdateResult, Error> {
+		let result = self.school_year.update_one(
+			doc! { "school_year": school_year, "completed": false },
+			doc! { "$set": { "started_at": DateTime::from_millis(0) } }
+		).await?;
+		Ok(result)
+	}
+	// Roll over a claimed school year. Every step can be run again, so a rollover that failed partway can be
+	// finished by retrying it: members are marked with the year they were rolled over in, and the roster and
+	// alumni are saved by member.
+	pub async fn roll_over_school_year(&self, school_year: &str) -> Result<SchoolYear, Error> {
+		let not_rolled_over = doc! { "rolled_over": { "$ne": school_year } };
+		let general_members: Vec<GeneralMember> = self.general_member
+			.find(not_rolled_over.clone()).await?
+			.try_collect().await?;
+		let executive_members: Vec<ExecutiveMember> = self.executive_member
+			.find(doc! { "term_ended": { "$ne": true } }).await?
+			.try_collect().await?;
+
+		// Keep the roster of the year that is ending
+		let entries = general_members
+			.iter()
+			.map(|member| RosterEntry {
+				_id: ObjectId::new(),
+				school_year: school_year.to_string(),
+				roster: "general".to_string(),
+				member_id: member._id,
+				full_name: member.full_name.clone(),
+				email: member.email.clone(),
+				grade: member.grade,
+				exec_type: String::new(),
+				status: member.status.clone(),
+			})
+			.chain(
+				executive_members.iter().map(|member| RosterEntry {
+					_id: ObjectId::new(),
+					school_year: school_year.to_string(),
+					roster: "executive".to_string(),
+					member_id: member._id,
+					full_name: member.full_name.clone(),
+					email: member.email.clone(),
+					grade: member.grade,
+					exec_type: member.exec_type.clone(),
+					status: member.status.clone(),
+				})
+			);
+		for entry in entries {
+			self.roster
+				.update_one(
+					doc! {
+						"school_year": &entry.school_year,
+						"roster": &entry.roster,
+						"member_id": entry.member_id,
+					},
+					doc! { "$setOnInsert": to_bson(&entry)? }
+				)
+				.upsert(true).await?;
+		}
+
+		// Archive the graduating members, merging anyone who was both a general and an executive member
+		let graduates = general_members
+			.iter()
+			.filter(|member| member.grade >= 12 && member.status != "rejected" && member.status != "withdrawn")
+			.map(|member| (&member.full_name, &member.email, None))
+			.chain(
+				executive_members
+					.iter()
+					.filter(|member| member.grade >= 12 && member.status == "accepted")
+					.map(|member| (&member.full_name, &member.email, Some(&member.exec_type)))
+			);
+		for (full_name, email, exec_type) in graduates {
+			let exec_types: Vec<&String> = exec_type.into_iter().collect();
+			self.alumni
+				.update_one(
+					doc! { "school_year": school_year, "email": email },
+					doc! {
+						"$setOnInsert": {
+							"_id": ObjectId::new(),
+							"full_name": full_name,
+							"date_created": DateTime::now(),
+						},
+						"$addToSet": { "exec_types": { "$each": exec_types } },
+					}
+				)
+				.upsert(true).await?;
+		}
+
+		// Graduating members leave and everyone else moves up a grade, once per school year
+		let mut graduating = not_rolled_over.clone();
+		graduating.insert("grade", doc! { "$gte": 12 });
+		self.general_member.delete_many(graduating).await?;
+		self.general_member
+			.update_many(
+				not_rolled_over,
+				doc! { "$inc": { "grade": 1 }, "$set": { "rolled_over": school_year } }
+			).await?;
+
+		// Executive applications only last for one term
+		self.executive_member
+			.update_many(
+				doc! { "term_ended": { "$ne": true } },
+				doc! { "$set": { "term_ended": true, "rolled_over": school_year } }
+			).await?;
+
+		// Count what happened from what was saved, which includes the work of any earlier attempt
+		let general_count = self.roster
+			.count_documents(doc! { "school_year": school_year, "roster": "general" }).await? as u32;
+		let executive_count = self.roster
+			.count_documents(doc! { "school_year": school_year, "roster": "executive" }).await? as u32;
+		let promoted = self.general_member
+			.count_documents(doc! { "rolled_over": school_year }).await? as u32;
+		let graduated = self.alumni.count_documents(doc! { "school_year": school_year }).await? as u32;
+		let terms_ended = self.executive_member
+			.count_documents(doc! { "rolled_over": school_year }).await? as u32;
+
+		let record = self.school_year
+			.find_one_and_update(
+				doc! { "school_year": school_year },
+				doc! {
+					"$set": {
+						"general_members": general_count as i64,
+						"executive_members": executive_count as i64,
+						"promoted": promoted as i64,
+						"graduated": graduated as i64,
+						"terms_ended": terms_ended as i64,
+						"completed": true,
+						"date_created": DateTime::now(),
+					},
+				}
+			)
+			.return_document(ReturnDocument::After).await?;
+		record.ok_or_else(|| Error::custom("The school year record is missing."))
+	}
+	pub async fn get_roster(
+		&self,
+		school_year: &str,
+		roster: &str
+	) -> Result<Vec<RosterEntry>, Error> {
+		let mut filter = doc! { "school_year": school_year };
+		if !roster.is_empty() {
+			filter.insert("roster", roster);
+		}
+		let cursor = self.roster.find(filter).sort(doc! { "roster": 1, "full_name": 1 }).await?;
+		let entries: Vec<RosterEntry> = cursor.try_collect().await?;
+		Ok(entries)
+	}
+	pub async fn get_alumni(&self, school_year: &str) -> Result<Vec<Alumnus>, Error> {
+		let filter = if school_year.is_empty() {
+			doc! {}
+		} else {
+			doc! { "school_year": school_year }
+		};
+		let cursor = self.alumni.find(filter).sort(doc! { "school_year": -1, "full_name": 1 }).await?;
+		let alumni: Vec<Alumnus> = cursor.try_collect().await?;
+		Ok(alumni)
+	}
+
+	// Announcements
+	fn live_announcement_filter() -> Document {
+		// Hide drafts, scheduled announcements, and expired announcements
+		let now = DateTime::now();
+		doc! {
+			"draft": { "$ne": true },
+			"$and": [
+				{ "$or": [{ "publish_at": null }, { "publish_at": { "$lte": now } }] },
+				{ "$or": [{ "expires_at": null }, { "expires_at": { "$gt": now } }] },
+			],
+		}
+	}
+	pub async fn get_announcements(
+		&self,
+		page: u32,
+		limit: u32,
+		search: String,
+		field: String,
+		include_hidden: bool
+	) -> Result<Vec<Announcement>, Error> {
+		let skip = (page - 1) * limit;
+		let mut filter = if include_hidden {
+			doc! {}
+		} else {
+			Database::live_announcement_filter()
+		};
+		if !search.is_empty() {
+			filter.insert(field, doc! { "$regex": search, "$options": "i" });
+		}
+		let cursor = self.announcement
+			.find(filter)
+			.sort(doc! { "date_published": -1, "_id": -1 })
+			.skip(skip.into())
+			.limit(limit.into()).await?;
+		let posts: Vec<Announcement> = cursor.try_collect().await?;
+		Ok(posts)
+	}
+	pub async fn get_amount_of_announcements(&self, include_hidden: bool) -> Result<u64, Error> {
+		let filter = if include_hidden {
+			doc! {}
+		} else {
+			Database::live_announcement_filter()
+		};
+		let amount = self.announcement.count_documents(filter).await?;
+		Ok(amount)
+	}
+	pub async fn get_live_announcements(&self) -> Result<Vec<Announcement>, Error> {
+		let cursor = self.announcement.find(Database::live_announcement_filter()).await?;
+		let posts: Vec<Announcement> = cursor.try_collect().await?;
+		Ok(posts)
+	}
+	pub async fn get_announcement_by_id(&self, id: String) -> Result<Option<Announcement>, Error> {
+		let object_id = ObjectId::parse_str(&id).expect("Error parsing ID.");
+		let post = self.announcement.find_one(doc! { "_id": object_id }).await?;
+		Ok(post)
+	}
+	pub async fn create_announcement(
+		&self,
+		announcement: Announcement
+	) -> Result<InsertOneResult, Error> {
+		let result = self.announcement
+			.insert_one(announcement).await
+			.ok()
+			.expect("Error creating announcement.");
+
+		Ok(result)
+	}
+	pub async fn update_announcement(
+		&self,
+		id: String,
+		announcement: &Announcement
+	) -> Result<UpdateResult, Error> {
+		let object_id = ObjectId::parse_str(&id).expect("Error parsing ID.");
+		let result = self.announcement.update_one(
+			doc! { "_id": object_id },
+			doc! {
+				"$set": {
+					"title": announcement.title.clone(),
+					"body": announcement.body.clone(),
+					"draft": announcement.draft,
+					"publish_at": announcement.publish_at,
+					"expires_at": announcement.expires_at,
+					"date_updated": announcement.date_updated,
+					"date_published": announcement.date_published(),
+				},
+			}
+		).await?;
+
+		Ok(result)
+	}
+	pub async fn delete_announcement(&self, id: String) -> Result<DeleteResult, Error> {
+		let object_id = ObjectId::parse_str(&id).expect("Error parsing ID.");
+		let result = self.announcement
+			.delete_one(doc! { "_id": object_id }).await
+			.ok()
+			.expect("Error deleting announcement.");
+
+		Ok(result)
+	}
+
+	// Announcement Broadcasts
+	pub async fn get_broadcast_recipients(
+		&self,
+		audience: &BroadcastAudience
+	) -> Result<Vec<String>, Error> {
+		let mut emails: Vec<String> = Vec::new();
+
+		// Only verified accounts can receive broadcasts
+		if audience.accounts {
+			let cursor = self.account.find(doc! { "verified": true }).await?;
+			let accounts: Vec<Account> = cursor.try_collect().await?;
+			emails.extend(accounts.into_iter().map(|acc| acc.email));
+		}
+
+		// Only accepted members can receive broadcasts, filtered by grade if any grades were given. Members
+		// saved before applications had a status were never reviewed, so they count as accepted.
+		let mut member_filter = doc! { "status": { "$in": ["accepted", null] } };
+		if !audience.grades.is_empty() {
+			let grades: Vec<i32> = audience.grades
+				.iter()
+				.map(|grade| *grade as i32)
+				.collect();
+			member_filter.insert("grade", doc! { "$in": grades });
+		}
+
+		if audience.general_members {
+			let cursor = self.general_member.find(member_filter.clone()).await?;
+			let members: Vec<GeneralMember> = cursor.try_collect().await?;
+			emails.extend(members.into_iter().map(|member| member.email));
+		}
+
+		if audience.executive_members {
+			let mut exec_filter = member_filter.clone();
+			exec_filter.insert("term_ended", doc! { "$ne": true });
+			if !audience.exec_types.is_empty() {
+				exec_filter.insert("exec_type", doc! { "$in": audience.exec_types.clone() });
+			}
+			let cursor = self.executive_member.find(exec_filter).await?;
+			let members: Vec<ExecutiveMember> = cursor.try_collect().await?;
+			emails.extend(members.into_iter().map(|member| member.email));
+		}
+
+		// Remove duplicate emails so nobody gets the announcement twice
+		emails.sort();
+		emails.dedup();
+		Ok(emails)
+	}
+	pub async fn create_broadcast(&self, broadcast: Broadcast) -> Result<InsertOneResult, Error> {
+		let result = self.broadcast.insert_one(broadcast).await?;
+		Ok(result)
+	}
+	pub async fn get_broadcast_by_id(&self, id: String) -> Result<Option<Broadcast>, Error> {
+		let object_id = ObjectId::parse_str(&id).expect("Error parsing ID.");
+		let broadcast = self.broadcast.find_one(doc! { "_id": object_id }).await?;
+		Ok(broadcast)
+	}
+	pub async fn get_broadcasts_by_announcement_id(
+		&self,
+		id: String
+	) -> Result<Vec<Broadcast>, Error> {
+		let object_id = ObjectId::parse_str(&id).expect("Error parsing ID.");
+		let cursor = self.broadcast
+			.find(doc! { "announcement_id": object_id })
+			.sort(doc! { "date_created": -1 }).await?;
+		let broadcasts: Vec<Broadcast> = cursor.try_collect().await?;
+		Ok(broadcasts)
+	}
+	pub async fn update_broadcast_status(
+		&self,
+		id: ObjectId,
+		status: &str
+	) -> Result<UpdateResult, Error> {
+		let update = if status == "finished" {
+			doc! { "$set": { "status": status, "date_finished": DateTime::now() } }
+		} else {
+			doc! { "$set": { "status": status } }
+		};
+		let result = self.broadcast.update_one(doc! { "_id": id }, update).await?;
+		Ok(result)
+	}
+	pub async fn fail_unfinished_broadcasts(&self, before: DateTime) -> Result<UpdateResult, Error> {
+		let result = self.broadcast.update_many(
+			doc! { "status": { "$in": ["queued", "sending"] }, "date_created": { "$lt": before } },
+			doc! { "$set": { "status": "failed", "date_finished": DateTime::now() } }
+		).await?;
+		Ok(result)
+	}
+	pub async fn record_broadcast_delivery(
+		&self,
+		id: ObjectId,
+		email: &str,
+		delivered: bool
+	) -> Result<UpdateResult, Error> {
+		let update = if delivered {
+			doc! { "$inc": { "sent": 1 } }
+		} else {
+			doc! { "$inc": { "failed": 1 }, "$push": { "failed_emails": email } }
+		};
+		let result = self.broadcast.update_one(doc! { "_id": id }, update).await?;
+		Ok(result)
+	}
+
+	// Events and their RSVPs
+	fn event_filter(past: bool) -> Document {
+		if past {
+			doc! { "ends_at": { "$lte": DateTime::now() } }
+		} else {
+			doc! { "ends_at": { "$gt": DateTime::now() } }
+		}
+	}
+	pub async fn get_events(&self, page: u32, limit: u32, past: bool) -> Result<Vec<Event>, Error> {
+		let skip = (page - 1) * limit;
+		// Upcoming events are listed soonest first and past events most recent first
+		let sort = if past { doc! { "starts_at": -1 } } else { doc! { "starts_at": 1 } };
+		let cursor = self.event
+			.find(Database::event_filter(past))
+			.sort(sort)
+			.skip(skip.into())
+			.limit(limit.into()).await?;
+		let events: Vec<Event> = cursor.try_collect().await?;
+		Ok(events)
+	}
+	pub async fn get_amount_of_events(&self, past: bool) -> Result<u64, Error> {
+		let count = self.event.count_documents(Database::event_filter(past)).await?;
+		Ok(count)
+	}
+	pub async fn get_event_by_id(&self, id: String) -> Result<Option<Event>, Error> {
+		let object_id = ObjectId::parse_str(&id).expect("Error parsing ID.");
+		let event = self.event.find_one(doc! { "_id": object_id }).await?;
+		Ok(event)
+	}
+	pub async fn create_event(&self, event: Event) -> Result<InsertOneResult, Error> {
+		let result = self.event.insert_one(event).await?;
+		Ok(result)
+	}
+	pub async fn update_event(&self, id: ObjectId, event: &Event) -> Result<UpdateResult, Error> {
+		let result = self.event.update_one(
+			doc! { "_id": id },
+			doc! {
+				"$set": {
+					"title": &event.title,
+					"description": &event.description,
+					"kind": &event.kind,
+					"location": &event.location,
+					"starts_at": event.starts_at,
+					"ends_at": event.ends_at,
+					"capacity": event.capacity as i64,
+					"date_updated": DateTime::now(),
+				},
+				"$inc": { "sequence": 1 },
+			}
+		).await?;
+
+		Ok(result)
+	}
+	pub async fn cancel_event(&self, id: ObjectId) -> Result<UpdateResult, Error> {
+		let result = self.event.update_one(
+			doc! { "_id": id },
+			doc! {
+				"$set": { "cancelled": true, "date_updated": DateTime::now() },
+				"$inc": { "sequence": 1 },
+			}
+		).await?;
+
+		Ok(result)
+	}
+	pub async fn get_calendar_events(&self, since: DateTime) -> Result<Vec<Event>, Error> {
+		// Cancelled events stay in the feed so calendars remove them
+		let cursor = self.event
+			.find(doc! { "ends_at": { "$gt": since } })
+			.sort(doc! { "starts_at": 1 }).await?;
+		let events: Vec<Event> = cursor.try_collect().await?;
+		Ok(events)
+	}
+	pub async fn get_events_by_ids(&self, ids: Vec<ObjectId>) -> Result<Vec<Event>, Error> {
+		let cursor = self.event
+			.find(doc! { "_id": { "$in": ids } })
+			.sort(doc! { "starts_at": 1 }).await?;
+		let events: Vec<Event> = cursor.try_collect().await?;
+		Ok(events)
+	}
+	pub async fn count_rsvps(&self, event_id: ObjectId, status: &str) -> Result<u64, Error> {
+		let count = self.rsvp.count_documents(doc! { "event_id": event_id, "status": status }).await?;
+		Ok(count)
+	}
+	pub async fn get_rsvp(
+		&self,
+		event_id: ObjectId,
+		account_id: ObjectId
+	) -> Result<Option<Rsvp>, Error> {
+		let rsvp = self.rsvp.find_one(doc! { "event_id": event_id, "account_id": account_id }).await?;
+		Ok(rsvp)
+	}
+	pub async fn get_rsvps(&self, event_id: ObjectId) -> Result<Vec<Rsvp>, Error> {
+		let cursor = self.rsvp
+			.find(doc! { "event_id": event_id })
+			.sort(doc! { "date_created": 1 }).await?;
+		let rsvps: Vec<Rsvp> = cursor.try_collect().await?;
+		Ok(rsvps)
+	}
+	pub async fn get_rsvps_by_event_ids(&self, event_ids: Vec<ObjectId>) -> Result<Vec<Rsvp>, Error> {
+		let cursor = self.rsvp.find(doc! { "event_id": { "$in": event_ids } }).await?;
+		let rsvps: Vec<Rsvp> = cursor.try_collect().await?;
+		Ok(rsvps)
+	}
+	pub async fn get_rsvps_by_account_id(&self, account_id: ObjectId) -> Result<Vec<Rsvp>, Error> {
+		let cursor = self.rsvp.find(doc! { "account_id": account_id }).await?;
+		let rsvps: Vec<Rsvp> = cursor.try_collect().await?;
+		Ok(rsvps)
+	}
+	// Take a spot at an event if there is one left (a capacity of 0 means there is no limit)
+	async fn take_event_spot(&self, event_id: ObjectId) -> Result<bool, Error> {
+		let result = self.event.update_one(
+			doc! {
+				"_id": event_id,
+				"$or": [{ "capacity": 0 }, { "$expr": { "$lt": ["$going", "$capacity"] } }],
+			},
+			doc! { "$inc": { "going": 1 } }
+		).await?;
+		Ok(result.modified_count > 0)
+	}
+	async fn free_event_spot(&self, event_id: ObjectId) -> Result<UpdateResult, Error> {
+		let result = self.event.update_one(
+			doc! { "_id": event_id, "going": { "$gt": 0 } },
+			doc! { "$inc": { "going": -1 } }
+		).await?;
+		Ok(result)
+	}
+	// RSVP to an event, joining the waitlist if it is full. RSVPing again returns the RSVP that already exists.
+	pub async fn create_rsvp(&self, mut rsvp: Rsvp) -> Result<Rsvp, Error> {
+		let took_spot = self.take_event_spot(rsvp.event_id).await?;
+		rsvp.status = (if took_spot { "going" } else { "waitlisted" }).to_string();
+
+		match self.rsvp.insert_one(&rsvp).await {
+			Ok(_) => Ok(rsvp),
+			Err(err) if is_duplicate_key(&err) => {
+				if took_spot {
+					self.free_event_spot(rsvp.event_id).await?;
+				}
+				let existing = self.get_rsvp(rsvp.event_id, rsvp.account_id).await?;
+				existing.ok_or(err)
+			}
+			Err(err) => {
+				if took_spot {
+					self.free_event_spot(rsvp.event_id).await?;
+				}
+				Err(err)
+			}
+		}
+	}
+	pub async fn delete_rsvp(
+		&self,
+		event_id: ObjectId,
+		account_id: ObjectId
+	) -> Result<Option<Rsvp>, Error> {
+		let rsvp = self.rsvp.find_one_and_delete(
+			doc! { "event_id": event_id, "account_id": account_id }
+		).await?;
+		if rsvp.as_ref().is_some_and(|rsvp| rsvp.status == "going") {
+			self.free_event_spot(event_id).await?;
+		}
+
+		Ok(rsvp)
+	}
+	pub async fn promote_waitlist(&self, event: &Event) -> Result<Vec<Rsvp>, Error> {
+		// Move people off the waitlist in the order they joined it until the event is full
+		let mut promoted: Vec<Rsvp> = Vec::new();
+		while self.take_event_spot(event._id).await? {
+			let rsvp = self.rsvp
+				.find_one_and_update(
+					doc! { "event_id": event._id, "status": "waitlisted" },
+					doc! { "$set": { "status": "going" } }
+				)
+				.sort(doc! { "date_created": 1 })
+				.return_document(ReturnDocument::After).await?;
+			match rsvp {
+				Some(rsvp) => promoted.push(rsvp),
+				None => {
+					// Nobody is waiting, so give the spot back
+					self.free_event_spot(event._id).await?;
+					break;
+				}
+			}
+		}
+		Ok(promoted)
+	}
+
+	// Event Attendance
+	pub async fn record_attendance(&self, attendance: Attendance) -> Result<bool, Error> {
+		// Checking in twice keeps the first check-in
+		let result = self.attendance.update_one(
+			doc! { "event_id": attendance.event_id, "account_id": attendance.account_id },
+			doc! { "$setOnInsert": to_bson(&attendance)? }
+		)
+		.upsert(true).await?;
+
+		Ok(result.upserted_id.is_some())
+	}
+	pub async fn get_attendance_by_event_id(&self, event_id: ObjectId) -> Result<Vec<Attendance>, Error> {
+		let cursor = self.attendance
+			.find(doc! { "event_id": event_id })
+			.sort(doc! { "date_created": 1 }).await?;
+		let attendance: Vec<Attendance> = cursor.try_collect().await?;
+		Ok(attendance)
+	}
+	pub async fn get_attendance_between(
+		&self,
+		start: DateTime,
+		end: DateTime
+	) -> Result<Vec<Attendance>, Error> {
+		let cursor = self.attendance
+			.find(doc! { "date_created": { "$gte": start, "$lt": end } })
+			.sort(doc! { "date_created": 1 }).await?;
+		let attendance: Vec<Attendance> = cursor.try_collect().await?;
+		Ok(attendance)
+	}
+	pub async fn get_events_between(&self, start: DateTime, end: DateTime) -> Result<Vec<Event>, Error> {
+		let cursor = self.event
+			.find(doc! { "starts_at": { "$gte": start, "$lt": end }, "cancelled": false })
+			.sort(doc! { "starts_at": 1 }).await?;
+		let events: Vec<Event> = cursor.try_collect().await?;
+		Ok(events)
+	}
+
+	// CTF Challenges and Solves
+	pub async fn get_challenges(
+		&self,
+		competition_id: Option<ObjectId>
+	) -> Result<Vec<Challenge>, Error> {
+		// Practice challenges are the ones outside any competition
+		let cursor = self.challenge
+			.find(doc! { "hidden": false, "competition_id": competition_id })
+			.sort(doc! { "category": 1, "points": 1, "title": 1 }).await?;
+		let challenges: Vec<Challenge> = cursor.try_collect().await?;
+		Ok(challenges)
+	}
+	pub async fn get_all_challenges(&self) -> Result<Vec<Challenge>, Error> {
+		let cursor = self.challenge
+			.find(doc! {})
+			.sort(doc! { "category": 1, "points": 1, "title": 1 }).await?;
+		let challenges: Vec<Challenge> = cursor.try_collect().await?;
+		Ok(challenges)
+	}
+	pub async fn get_challenge_by_id(&self, id: String) -> Result<Option<Challenge>, Error> {
+		let object_id = ObjectId::parse_str(&id).expect("Error parsing ID.");
+		let challenge = self.challenge.find_one(doc! { "_id": object_id }).await?;
+		Ok(challenge)
+	}
+	pub async fn challenge_title_is_taken(&self, title: &str, id: ObjectId) -> Result<bool, Error> {
+		let existing = self.challenge.find_one(doc! { "title": title, "_id": { "$ne": id } }).await?;
+		Ok(existing.is_some())
+	}
+	pub async fn create_challenge(&self, challenge: Challenge) -> Result<InsertOneResult, Error> {
+		let result = self.challenge.insert_one(challenge).await?;
+		Ok(result)
+	}
+	pub async fn update_challenge(&self, challenge: &Challenge) -> Result<UpdateResult, Error> {
+		let result = self.challenge.update_one(
+			doc! { "_id": challenge._id },
+			doc! {
+				"$set": {
+					"title": &challenge.title,
+					"category": &challenge.category,
+					"description": &challenge.description,
+					"points": challenge.points as i64,
+					"minimum_points": challenge.minimum_points as i64,
+					"decay": challenge.decay as i64,
+					"attachments": &challenge.attachments,
+					"hints": to_bson(&challenge.hints)?,
+					"competition_id": challenge.competition_id,
+					"flag_hash": &challenge.flag_hash,
+					"flag_regex": &challenge.flag_regex,
+					"hidden": challenge.hidden,
+					"date_updated": DateTime::now(),
+				},
+			}
+		).await?;
+
+		Ok(result)
+	}
+	pub async fn delete_challenge(&self, id: ObjectId) -> Result<DeleteResult, Error> {
+		self.submission.delete_many(doc! { "challenge_id": id }).await?;
+		self.solve.delete_many(doc! { "challenge_id": id }).await?;
+		self.hint_unlock.delete_many(doc! { "challenge_id": id }).await?;
+		let result = self.challenge.delete_one(doc! { "_id": id }).await?;
+		Ok(result)
+	}
+	pub async fn count_recent_submissions(
+		&self,
+		challenge_id: ObjectId,
+		account_id: ObjectId,
+		since: DateTime
+	) -> Result<u64, Error> {
+		let count = self.submission.count_documents(
+			doc! {
+				"challenge_id": challenge_id,
+				"account_id": account_id,
+				"date_created": { "$gte": since },
+			}
+		).await?;
+
+		Ok(count)
+	}
+	pub async fn create_submission(&self, submission: Submission) -> Result<InsertOneResult, Error> {
+		let result = self.submission.insert_one(submission).await?;
+		Ok(result)
+	}
+	pub async fn record_solve(&self, solve: Solve) -> Result<bool, Error> {
+		// Solving a challenge again keeps the first solve
+		let result = self.solve.update_one(
+			doc! { "challenge_id": solve.challenge_id, "account_id": solve.account_id },
+			doc! { "$setOnInsert": to_bson(&solve)? }
+		)
+		.upsert(true).await?;
+
+		Ok(result.upserted_id.is_some())
+	}
+	pub async fn get_solves(&self, challenge_id: ObjectId) -> Result<Vec<Solve>, Error> {
+		let cursor = self.solve
+			.find(doc! { "challenge_id": challenge_id })
+			.sort(doc! { "date_created": 1 }).await?;
+		let solves: Vec<Solve> = cursor.try_collect().await?;
+		Ok(solves)
+	}
+	pub async fn get_solves_by_account_id(&self, account_id: ObjectId) -> Result<Vec<Solve>, Error> {
+		let cursor = self.solve
+			.find(doc! { "account_id": account_id })
+			.sort(doc! { "date_created": 1 }).await?;
+		let solves: Vec<Solve> = cursor.try_collect().await?;
+		Ok(solves)
+	}
+	pub async fn count_solves(&self, challenge_id: ObjectId, before: Option<DateTime>) -> Result<u64, Error> {
+		let filter = match before {
+			Some(before) => doc! { "challenge_id": challenge_id, "date_created": { "$lt": before } },
+			None => doc! { "challenge_id": challenge_id },
+		};
+		let count = self.solve.count_documents(filter).await?;
+		Ok(count)
+	}
+
+	pub async fn get_all_solves(&self) -> Result<Vec<Solve>, Error> {
+		let cursor = self.solve.find(doc! {}).sort(doc! { "date_created": 1 }).await?;
+		let solves: Vec<Solve> = cursor.try_collect().await?;
+		Ok(solves)
+	}
+	pub async fn get_scoreboard_freeze(&self) -> Result<Option<DateTime>, Error> {
+		let settings = self.ctf_settings.find_one(doc! { "name": "scoreboard" }).await?;
+		Ok(settings.and_then(|settings| settings.freeze_at))
+	}
+	pub async fn set_scoreboard_freeze(&self, freeze_at: Option<DateTime>) -> Result<UpdateResult, Error> {
+		let result = self.ctf_settings
+			.update_one(
+				doc! { "name": "scoreboard" },
+				doc! {
+					"$set": { "freeze_at": freeze_at },
+					"$setOnInsert": { "_id": ObjectId::new() },
+				}
+			)
+			.upsert(true).await?;
+
+		Ok(result)
+	}
+
+	pub async fn get_hint_unlocks(
+		&self,
+		challenge_id: ObjectId,
+		account_id: ObjectId
+	) -> Result<Vec<HintUnlock>, Error> {
+		let cursor = self.hint_unlock
+			.find(doc! { "challenge_id": challenge_id, "account_id": account_id })
+			.sort(doc! { "index": 1 }).await?;
+		let unlocks: Vec<HintUnlock> = cursor.try_collect().await?;
+		Ok(unlocks)
+	}
+	pub async fn get_hint_unlocks_by_account_id(
+		&self,
+		account_id: ObjectId
+	) -> Result<Vec<HintUnlock>, Error> {
+		let cursor = self.hint_unlock.find(doc! { "account_id": account_id }).await?;
+		let unlocks: Vec<HintUnlock> = cursor.try_collect().await?;
+		Ok(unlocks)
+	}
+	pub async fn get_all_hint_unlocks(&self) -> Result<Vec<HintUnlock>, Error> {
+		let cursor = self.hint_unlock.find(doc! {}).sort(doc! { "date_created": 1 }).await?;
+		let unlocks: Vec<HintUnlock> = cursor.try_collect().await?;
+		Ok(unlocks)
+	}
+	pub async fn unlock_hint(&self, unlock: HintUnlock) -> Result<bool, Error> {
+		// Unlocking a hint again does not cost anything more
+		let result = self.hint_unlock.update_one(
+			doc! {
+				"challenge_id": unlock.challenge_id,
+				"account_id": unlock.account_id,
+				"index": unlock.index as i64,
+			},
+			doc! { "$setOnInsert": to_bson(&unlock)? }
+		)
+		.upsert(true).await?;
+
+		Ok(result.upserted_id.is_some())
+	}
+
+	// CTF Competitions
+	pub async fn get_competitions(&self) -> Result<Vec<Competition>, Error> {
+		let cursor = self.competition.find(doc! {}).sort(doc! { "starts_at": -1 }).await?;
+		let competitions: Vec<Competition> = cursor.try_collect().await?;
+		Ok(competitions)
+	}
+	pub async fn get_competition_by_id(&self, id: ObjectId) -> Result<Option<Competition>, Error> {
+		let competition = self.competition.find_one(doc! { "_id": id }).await?;
+		Ok(competition)
+	}
+	pub async fn create_competition(&self, competition: Competition) -> Result<InsertOneResult, Error> {
+		let result = self.competition.insert_one(competition).await?;
+		Ok(result)
+	}
+	pub async fn update_competition(&self, competition: &Competition) -> Result<UpdateResult, Error> {
+		let result = self.competition.update_one(
+			doc! { "_id": competition._id },
+			doc! {
+				"$set": {
+					"name": &competition.name,
+					"starts_at": competition.starts_at,
+					"ends_at": competition.ends_at,
+					"freeze_at": competition.freeze_at,
+					"max_team_size": competition.max_team_size as i64,
+				},
+			}
+		).await?;
+
+		Ok(result)
+	}
+	pub async fn competition_has_challenges(&self, id: ObjectId) -> Result<bool, Error> {
+		let challenge = self.challenge.find_one(doc! { "competition_id": id }).await?;
+		Ok(challenge.is_some())
+	}
+	pub async fn delete_competition(&self, id: ObjectId) -> Result<DeleteResult, Error> {
+		let result = self.competition.delete_one(doc! { "_id": id }).await?;
+		Ok(result)
+	}
 
-	// Executive Members
-	pub async fn exec_mem_does_exist_full_name(&self, full_name: String) -> bool {
-		let existing_member = self.executive_member
-			.find_one(doc! { "full_name": &full_name }).await
-			.ok();
-		if let Some(existing_member) = existing_member {
-			return existing_member.is_some();
-		}
-		false
+	// CTF Teams
+	pub async fn get_teams(&self) -> Result<Vec<Team>, Error> {
+		let cursor = self.team.find(doc! {}).sort(doc! { "name": 1 }).await?;
+		let teams: Vec<Team> = cursor.try_collect().await?;
+		Ok(teams)
 	}
-	pub async fn exec_mem_does_exist_email(&self, email: String) -> bool {
-		let existing_member = self.executive_member.find_one(doc! { "email": &email }).await.ok();
-		if let Some(existing_member) = existing_member {
-			return existing_member.is_some();
-		}
-		false
+	pub async fn get_team_by_account_id(&self, account_id: ObjectId) -> Result<Option<Team>, Error> {
+		let team = self.team.find_one(doc! { "members.account_id": account_id }).await?;
+		Ok(team)
 	}
-	pub async fn exec_mem_does_exist(&self, executive_member: &ExecutiveMember) -> bool {
-		let existing_member = self.executive_member
-			.find_one(
-				doc! { "$or": [{ "full_name": &executive_member.full_name }, { "email": &executive_member.email }] }
-			).await
-			.ok();
-		if let Some(existing_member) = existing_member {
-			return existing_member.is_some();
+	pub async fn get_team_by_invite_code(&self, invite_code: &str) -> Result<Option<Team>, Error> {
+		let team = self.team.find_one(doc! { "invite_code": invite_code }).await?;
+		Ok(team)
+	}
+	pub async fn team_name_is_taken(&self, name: &str) -> Result<bool, Error> {
+		// Team names are unique regardless of case
+		let name = format!("^{}$", regex::escape(name));
+		let existing = self.team.find_one(
+			doc! { "name": { "$regex": name, "$options": "i" } }
+		).await?;
+		Ok(existing.is_some())
+	}
+	// Create a team, or return None if its captain is already on another team
+	pub async fn create_team(&self, team: Team) -> Result<Option<InsertOneResult>, Error> {
+		match self.team.insert_one(team).await {
+			Ok(result) => Ok(Some(result)),
+			Err(err) if is_duplicate_key(&err) => Ok(None),
+			Err(err) => Err(err),
 		}
-		false
 	}
-	pub async fn create_executive_member(
+	pub async fn join_team(
 		&self,
-		executive_member: ExecutiveMember
-	) -> Result<InsertOneResult, Error> {
-		if self.exec_mem_does_exist(&executive_member).await {
-			return Err(
-				Error::from(
-					std::io::Error::new(std::io::ErrorKind::AlreadyExists, "Member already exists.")
-				)
-			);
-		}
+		team_id: ObjectId,
+		member: TeamMember,
+		max_team_size: u32
+	) -> Result<bool, Error> {
+		// Only add the member if the team still has room, so two people can't take the last spot
+		let last_spot = format!("members.{}", max_team_size.saturating_sub(1));
+		let result = self.team.update_one(
+			doc! {
+				"_id": team_id,
+				"members.account_id": { "$ne": member.account_id },
+				last_spot: { "$exists": false },
+			},
+			doc! { "$push": { "members": to_bson(&member)? } }
+		).await?;
 
-		let result = self.executive_member
-			.insert_one(executive_member).await
-			.ok()
-			.expect("Error creating executive member.");
+		Ok(result.modified_count > 0)
+	}
+	pub async fn leave_team(&self, team_id: ObjectId, account_id: ObjectId) -> Result<UpdateResult, Error> {
+		let result = self.team.update_one(
+			doc! { "_id": team_id },
+			doc! { "$pull": { "members": { "account_id": account_id } } }
+		).await?;
 
 		Ok(result)
 	}
-	pub async fn get_all_executive_members(
-		&self,
-		page: u32,
-		limit: u32,
-		search: String,
-		field: String
-	) -> Result<Vec<ExecutiveMember>, Error> {
-		let skip = (page - 1) * limit;
-		let filter = if field == "marketing" || field == "events" || field == "development" {
-			doc! { "exec_type": field }
-		} else if search.is_empty() {
-			doc! {}
-		} else if field == "grade".to_owned() {
-			let num = search.parse::<i32>().unwrap_or(9);
-			doc! { "grade": { "$regex": num, "$options": "i" } }
-		} else {
-			doc! { field : { "$regex": search, "$options": "i" } }
-		};
-		let cursor = self.executive_member.find(filter).skip(skip.into()).limit(limit.into()).await?;
-		let members = cursor.try_collect().await?;
-		Ok(members)
+	pub async fn delete_team(&self, team_id: ObjectId) -> Result<DeleteResult, Error> {
+		let result = self.team.delete_one(doc! { "_id": team_id }).await?;
+		Ok(result)
 	}
-
-	// Announcements
-	pub async fn get_announcements(
+	pub async fn set_team_captain(
 		&self,
-		page: u32,
-		limit: u32,
-		search: String,
-		field: String
-	) -> Result<Vec<Announcement>, Error> {
-		let skip = (page - 1) * limit;
-		let filter = if search.is_empty() {
-			doc! {}
-		} else {
-			doc! { field : { "$regex": search, "$options": "i" } }
-		};
-		let cursor = self.announcement.find(filter).skip(skip.into()).limit(limit.into()).await?;
-		let posts: Vec<Announcement> = cursor.try_collect().await?;
-		Ok(posts)
-	}
-	pub async fn get_amount_of_announcements(&self) -> Result<u64, Error> {
-		let amount = self.announcement.count_documents(doc! {}).await?;
-		Ok(amount)
-	}
-	pub async fn get_announcement_by_id(&self, id: String) -> Result<Option<Announcement>, Error> {
-		let object_id = ObjectId::parse_str(&id).expect("Error parsing ID.");
-		let post = self.announcement.find_one(doc! { "_id": object_id }).await?;
-		Ok(post)
+		team_id: ObjectId,
+		account_id: ObjectId
+	) -> Result<UpdateResult, Error> {
+		let result = self.team.update_one(
+			doc! { "_id": team_id },
+			doc! { "$set": { "captain_id": account_id } }
+		).await?;
+
+		Ok(result)
 	}
-	pub async fn create_announcement(
+	pub async fn set_team_invite_code(
 		&self,
-		announcement: Announcement
-	) -> Result<InsertOneResult, Error> {
-		let result = self.announcement
-			.insert_one(announcement).await
-			.ok()
-			.expect("Error creating announcement.");
+		team_id: ObjectId,
+		invite_code: String
+	) -> Result<UpdateResult, Error> {
+		let result = self.team.update_one(
+			doc! { "_id": team_id },
+			doc! { "$set": { "invite_code": invite_code } }
+		).await?;
 
 		Ok(result)
 	}
-	pub async fn delete_announcement(&self, id: String) -> Result<DeleteResult, Error> {
-		let object_id = ObjectId::parse_str(&id).expect("Error parsing ID.");
-		let result = self.announcement
-			.delete_one(doc! { "_id": object_id }).await
-			.ok()
-			.expect("Error deleting announcement.");
+	pub async fn get_max_team_size(&self) -> Result<u32, Error> {
+		let settings = self.ctf_settings.find_one(doc! { "name": "teams" }).await?;
+		match settings {
+			Some(settings) if settings.max_team_size > 0 => Ok(settings.max_team_size),
+			_ => Ok(DEFAULT_MAX_TEAM_SIZE),
+		}
+	}
+	pub async fn get_team_size_limit(&self) -> Result<u32, Error> {
+		// Teams play in every competition, so they have to fit the smallest limit of the ones that have not ended
+		let competitions: Vec<Competition> = self.competition
+			.find(doc! { "ends_at": { "$gt": DateTime::now() }, "max_team_size": { "$gt": 0 } }).await?
+			.try_collect().await?;
+		let limit = competitions
+			.iter()
+			.map(|competition| competition.max_team_size)
+			.fold(self.get_max_team_size().await?, u32::min);
+		Ok(limit)
+	}
+	pub async fn set_max_team_size(&self, max_team_size: u32) -> Result<UpdateResult, Error> {
+		let result = self.ctf_settings
+			.update_one(
+				doc! { "name": "teams" },
+				doc! {
+					"$set": { "max_team_size": max_team_size as i64 },
+					"$setOnInsert": { "_id": ObjectId::new() },
+				}
+			)
+			.upsert(true).await?;
 
 		Ok(result)
 	}
@@ -270,6 +1810,11 @@ impl Database {
 		let amount = self.forum_post.count_documents(doc! {}).await?;
 		Ok(amount)
 	}
+	pub async fn get_recent_forum_posts(&self, limit: i64) -> Result<Vec<Post>, Error> {
+		let cursor = self.forum_post.find(doc! {}).sort(doc! { "date_created": -1 }).limit(limit).await?;
+		let posts: Vec<Post> = cursor.try_collect().await?;
+		Ok(posts)
+	}
 	pub async fn get_forum_post_by_id(&self, id: String) -> Result<Option<Post>, Error> {
 		let object_id = ObjectId::parse_str(&id).expect("Error parsing ID.");
 		let post = self.forum_post.find_one(doc! { "_id": object_id }).await?;
@@ -384,6 +1929,57 @@ impl Database {
 
 		Ok(result)
 	}
+	pub async fn get_account_by_id(&self, id: String) -> Result<Option<Account>, Error> {
+		let object_id = ObjectId::parse_str(&id).expect("Error parsing ID.");
+		let acc = self.account.find_one(doc! { "_id": object_id }).await?;
+		Ok(acc)
+	}
+	pub async fn get_accounts_by_ids(&self, ids: Vec<ObjectId>) -> Result<Vec<Account>, Error> {
+		let cursor = self.account.find(doc! { "_id": { "$in": ids } }).await?;
+		let accounts: Vec<Account> = cursor.try_collect().await?;
+		Ok(accounts)
+	}
+	pub async fn get_account_by_calendar_token(
+		&self,
+		calendar_token: &str
+	) -> Result<Option<Account>, Error> {
+		let acc = self.account.find_one(doc! { "calendar_token": calendar_token }).await?;
+		Ok(acc)
+	}
+	pub async fn set_calendar_token(
+		&self,
+		id: ObjectId,
+		calendar_token: String
+	) -> Result<UpdateResult, Error> {
+		let result = self.account.update_one(
+			doc! { "_id": id },
+			doc! { "$set": { "calendar_token": calendar_token } }
+		).await?;
+
+		Ok(result)
+	}
+	pub async fn link_applications_to_account(&self, acc: &Account) -> Result<(u64, u64), Error> {
+		// Only link applications that no other account has claimed
+		let filter = doc! { "email": &acc.email, "account_id": null };
+		let update = doc! { "$set": { "account_id": acc._id } };
+		let general = self.general_member.update_many(filter.clone(), update.clone()).await?;
+		let executive = self.executive_member.update_many(filter, update).await?;
+
+		Ok((general.modified_count, executive.modified_count))
+	}
+	pub async fn link_all_applications(&self) -> Result<(u64, u64), Error> {
+		// Only verified accounts have proven that they own their email
+		let cursor = self.account.find(doc! { "verified": true }).await?;
+		let accounts: Vec<Account> = cursor.try_collect().await?;
+
+		let mut linked = (0, 0);
+		for acc in accounts {
+			let (general, executive) = self.link_applications_to_account(&acc).await?;
+			linked.0 += general;
+			linked.1 += executive;
+		}
+		Ok(linked)
+	}
 	pub async fn create_account(&self, acc: Account) -> Result<InsertOneResult, Error> {
 		if self.account_does_exist(&acc).await {
 			return Err(
@@ -405,17 +2001,28 @@ impl Database {
 		field: String
 	) -> Result<Vec<Account>, Error> {
 		let skip = (page - 1) * limit;
-		let filter = if field == "verified".to_owned() || field == "unverified".to_owned() {
+		let filter = Database::account_filter(search, field);
+		let cursor = self.account.find(filter).skip(skip.into()).limit(limit.into()).await?;
+		let members = cursor.try_collect().await?;
+		Ok(members)
+	}
+	pub async fn export_accounts(
+		&self,
+		search: String,
+		field: String
+	) -> Result<Cursor<Account>, Error> {
+		let filter = Database::account_filter(search, field);
+		self.account.find(filter).sort(doc! { "date_created": 1 }).await
+	}
+	fn account_filter(search: String, field: String) -> Document {
+		if field == "verified".to_owned() || field == "unverified".to_owned() {
 			let boolean = field == "verified".to_owned();
 			doc! { "verified": { "$regex": boolean, "$options": "i" } }
 		} else if search.is_empty() {
 			doc! {}
 		} else {
 			doc! { field : { "$regex": search, "$options": "i" } }
-		};
-		let cursor = self.account.find(filter).skip(skip.into()).limit(limit.into()).await?;
-		let members = cursor.try_collect().await?;
-		Ok(members)
+		}
 	}
 
 	// Admin
@@ -467,12 +2074,10 @@ impl Database {
 	// Resources
 	pub async fn get_resources(
 		&self,
-		page: u32,
-		limit: u32,
-		search: String,
-		field: String,
-		tag: String
+		args: ResourcePaginationArgs,
+		ids: Option<Vec<ObjectId>>
 	) -> Result<Vec<Resource>, mongodb::error::Error> {
+		let ResourcePaginationArgs { page, limit, search, field, tag, sort, .. } = args;
 		let skip = (page - 1) * limit;
 		let mut filter = if search.is_empty() {
 			doc! {}
@@ -483,9 +2088,381 @@ impl Database {
 		if !tag.is_empty() {
 			filter.insert("tags", doc! { "$in": [tag.clone()] });
 		}
+		if let Some(ids) = ids {
+			filter.insert("_id", doc! { "$in": ids });
+		}
+
+		// Newer resources have bigger IDs, so the ID breaks ties
+		let sort = match sort.as_str() {
+			"newest" => doc! { "_id": -1 },
+			"top_rated" => doc! { "average_rating": -1, "rating_count": -1, "_id": -1 },
+			"most_used" => doc! { "clicks": -1, "_id": -1 },
+			_ => doc! {},
+		};
+
+		let cursor = self.resource
+			.find(filter)
+			.sort(sort)
+			.skip(skip.into())
+			.limit(limit.into()).await?;
+		let resources: Vec<Resource> = cursor.try_collect().await?;
+		Ok(resources)
+	}
+	pub async fn create_resource(&self, resource: Resource) -> Result<InsertOneResult, Error> {
+		let result = self.resource.insert_one(resource).await?;
+		Ok(result)
+	}
+	pub async fn update_resource(&self, id: ObjectId, resource: &Resource) -> Result<UpdateResult, Error> {
+		// A new link starts over with no checks
+		self.resource.update_one(
+			doc! { "_id": id, "link": { "$ne": &resource.link } },
+			doc! { "$set": { "link_health": to_bson(&LinkHealth::default())? } }
+		).await?;
+
+		let result = self.resource.update_one(
+			doc! { "_id": id },
+			doc! {
+				"$set": {
+					"title": &resource.title,
+					"link": &resource.link,
+					"tags": &resource.tags,
+					"description": &resource.description,
+				},
+			}
+		).await?;
+
+		Ok(result)
+	}
+	pub async fn get_resources_to_check(
+		&self,
+		checked_before: Option<DateTime>
+	) -> Result<Vec<Resource>, Error> {
+		// Links that were never checked are always due
+		let filter = match checked_before {
+			Some(checked_before) =>
+				doc! {
+					"$or": [
+						{ "link_health.last_checked": { "$lt": checked_before } },
+						{ "link_health.last_checked": null },
+					],
+				},
+			None => doc! {},
+		};
+		let cursor = self.resource.find(filter).await?;
+		let resources: Vec<Resource> = cursor.try_collect().await?;
+		Ok(resources)
+	}
+	pub async fn set_link_health(
+		&self,
+		id: ObjectId,
+		link: &str,
+		health: &LinkHealth
+	) -> Result<UpdateResult, Error> {
+		let result = self.resource.update_one(
+			doc! { "_id": id, "link": link },
+			doc! { "$set": { "link_health": to_bson(health)? } }
+		).await?;
 
-		let cursor = self.resource.find(filter).skip(skip.into()).limit(limit.into()).await?;
+		Ok(result)
+	}
+	pub async fn get_link_report(&self, dead_only: bool) -> Result<Vec<Resource>, Error> {
+		let filter = if dead_only {
+			doc! { "link_health.dead": true }
+		} else {
+			doc! { "link_health.consecutive_failures": { "$gt": 0 } }
+		};
+		let cursor = self.resource
+			.find(filter)
+			.sort(doc! { "link_health.dead": -1, "link_health.consecutive_failures": -1, "title": 1 }).await?;
+		let resources: Vec<Resource> = cursor.try_collect().await?;
+		Ok(resources)
+	}
+	pub async fn get_resources_by_ids(&self, ids: Vec<ObjectId>) -> Result<Vec<Resource>, Error> {
+		let cursor = self.resource.find(doc! { "_id": { "$in": ids } }).await?;
 		let resources: Vec<Resource> = cursor.try_collect().await?;
 		Ok(resources)
 	}
+	pub async fn delete_resource(&self, id: ObjectId) -> Result<DeleteResult, Error> {
+		// Take the resource out of the learning paths it is in
+		self.learning_path.update_many(
+			doc! { "modules.resource_ids": id },
+			doc! { "$pull": { "modules.$[].resource_ids": id } }
+		).await?;
+		self.path_progress.delete_many(doc! { "resource_id": id }).await?;
+		self.resource_rating.delete_many(doc! { "resource_id": id }).await?;
+		self.resource_bookmark.delete_many(doc! { "resource_id": id }).await?;
+		self.resource_click.delete_many(doc! { "resource_id": id }).await?;
+
+		let result = self.resource.delete_one(doc! { "_id": id }).await?;
+		Ok(result)
+	}
+	// Count a visitor following a resource's link, once per click window
+	pub async fn record_resource_click(&self, id: ObjectId, visitor: &str) -> Result<Option<Resource>, Error> {
+		let resource = match self.resource.find_one(doc! { "_id": id }).await? {
+			Some(resource) => resource,
+			None => {
+				return Ok(None);
+			}
+		};
+
+		let click = ResourceClick {
+			_id: ObjectId::new(),
+			resource_id: id,
+			visitor: visitor.to_string(),
+			window: DateTime::now().timestamp_millis() / CLICK_WINDOW_MILLIS,
+			date_created: DateTime::now(),
+		};
+		match self.resource_click.insert_one(click).await {
+			Ok(_) => {
+				self.resource.update_one(doc! { "_id": id }, doc! { "$inc": { "clicks": 1 } }).await?;
+			}
+			// The visitor already clicked in this window
+			Err(err) if is_duplicate_key(&err) => (),
+			Err(err) => {
+				return Err(err);
+			}
+		}
+		Ok(Some(resource))
+	}
+	pub async fn rate_resource(&self, rating: ResourceRating) -> Result<(), Error> {
+		let previous = self.resource_rating
+			.find_one_and_update(
+				doc! { "resource_id": rating.resource_id, "account_id": rating.account_id },
+				doc! {
+					"$set": { "rating": rating.rating as i64, "date_updated": rating.date_updated },
+					"$setOnInsert": {
+						"_id": rating._id,
+						"resource_id": rating.resource_id,
+						"account_id": rating.account_id,
+					},
+				}
+			)
+			.upsert(true).await?;
+
+		// Replacing a rating changes the total but not the count
+		match previous {
+			Some(previous) =>
+				self.update_resource_rating(
+					rating.resource_id,
+					(rating.rating as i64) - (previous.rating as i64),
+					0
+				).await,
+			None => self.update_resource_rating(rating.resource_id, rating.rating as i64, 1).await,
+		}
+	}
+	pub async fn delete_resource_rating(
+		&self,
+		resource_id: ObjectId,
+		account_id: ObjectId
+	) -> Result<(), Error> {
+		let deleted = self.resource_rating.find_one_and_delete(
+			doc! { "resource_id": resource_id, "account_id": account_id }
+		).await?;
+		match deleted {
+			Some(deleted) => self.update_resource_rating(resource_id, -(deleted.rating as i64), -1).await,
+			None => Ok(()),
+		}
+	}
+	// Change a resource's rating total and count, and work out the average from them, in one update
+	async fn update_resource_rating(&self, resource_id: ObjectId, total: i64, count: i64) -> Result<(), Error> {
+		self.resource.update_one(
+			doc! { "_id": resource_id },
+			vec![
+				doc! {
+					"$set": {
+						"rating_total": { "$add": [{ "$ifNull": ["$rating_total", 0] }, total] },
+						"rating_count": { "$add": [{ "$ifNull": ["$rating_count", 0] }, count] },
+					},
+				},
+				doc! {
+					"$set": {
+						"average_rating": {
+							"$cond": [
+								{ "$gt": ["$rating_count", 0] },
+								{ "$divide": ["$rating_total", "$rating_count"] },
+								0.0,
+							],
+						},
+					},
+				}
+			]
+		).await?;
+		Ok(())
+	}
+	pub async fn get_resource_ratings_by_account_id(
+		&self,
+		account_id: ObjectId
+	) -> Result<Vec<ResourceRating>, Error> {
+		let cursor = self.resource_rating.find(doc! { "account_id": account_id }).await?;
+		let ratings: Vec<ResourceRating> = cursor.try_collect().await?;
+		Ok(ratings)
+	}
+	pub async fn bookmark_resource(&self, bookmark: ResourceBookmark) -> Result<bool, Error> {
+		let result = self.resource_bookmark
+			.update_one(
+				doc! { "resource_id": bookmark.resource_id, "account_id": bookmark.account_id },
+				doc! { "$setOnInsert": to_bson(&bookmark)? }
+			)
+			.upsert(true).await?;
+		Ok(result.upserted_id.is_some())
+	}
+	pub async fn delete_resource_bookmark(
+		&self,
+		resource_id: ObjectId,
+		account_id: ObjectId
+	) -> Result<DeleteResult, Error> {
+		let result = self.resource_bookmark.delete_one(
+			doc! { "resource_id": resource_id, "account_id": account_id }
+		).await?;
+		Ok(result)
+	}
+	pub async fn get_resource_bookmarks_by_account_id(
+		&self,
+		account_id: ObjectId
+	) -> Result<Vec<ResourceBookmark>, Error> {
+		let cursor = self.resource_bookmark.find(doc! { "account_id": account_id }).await?;
+		let bookmarks: Vec<ResourceBookmark> = cursor.try_collect().await?;
+		Ok(bookmarks)
+	}
+	pub async fn get_resource_tags(&self) -> Result<Vec<ResourceTag>, Error> {
+		let cursor = self.resource_tag.find(doc! {}).sort(doc! { "name": 1 }).await?;
+		let tags: Vec<ResourceTag> = cursor.try_collect().await?;
+		Ok(tags)
+	}
+	pub async fn count_resources_with_tag(&self, name: &str) -> Result<u64, Error> {
+		let count = self.resource.count_documents(doc! { "tags": name }).await?;
+		Ok(count)
+	}
+	pub async fn create_resource_tag(&self, tag: ResourceTag) -> Result<bool, Error> {
+		let result = self.resource_tag
+			.update_one(doc! { "name": &tag.name }, doc! { "$setOnInsert": to_bson(&tag)? })
+			.upsert(true).await?;
+		Ok(result.upserted_id.is_some())
+	}
+	pub async fn delete_resource_tag(&self, name: &str) -> Result<DeleteResult, Error> {
+		let result = self.resource_tag.delete_one(doc! { "name": name }).await?;
+		Ok(result)
+	}
+
+	// Session Materials
+	pub async fn get_materials(&self, session: &str) -> Result<Vec<Material>, Error> {
+		let filter = if session.is_empty() { doc! {} } else { doc! { "session": session } };
+		let cursor = self.material
+			.find(filter)
+			.sort(doc! { "session_date": -1, "title": 1 }).await?;
+		let materials: Vec<Material> = cursor.try_collect().await?;
+		Ok(materials)
+	}
+	pub async fn get_material_by_id(&self, id: String) -> Result<Option<Material>, Error> {
+		let object_id = ObjectId::parse_str(&id).expect("Error parsing ID.");
+		let material = self.material.find_one(doc! { "_id": object_id }).await?;
+		Ok(material)
+	}
+	pub async fn create_material(&self, material: Material) -> Result<InsertOneResult, Error> {
+		let result = self.material.insert_one(material).await?;
+		Ok(result)
+	}
+	pub async fn update_material(&self, id: ObjectId, material: &Material) -> Result<UpdateResult, Error> {
+		let result = self.material.update_one(
+			doc! { "_id": id },
+			doc! {
+				"$set": {
+					"title": &material.title,
+					"session": &material.session,
+					"session_date": material.session_date,
+					"presenter": &material.presenter,
+					"members_only": material.members_only,
+				},
+			}
+		).await?;
+
+		Ok(result)
+	}
+	pub async fn delete_material(&self, id: ObjectId) -> Result<DeleteResult, Error> {
+		let result = self.material.delete_one(doc! { "_id": id }).await?;
+		Ok(result)
+	}
+
+	// Learning Paths
+	pub async fn get_learning_paths(&self, difficulty: &str) -> Result<Vec<LearningPath>, Error> {
+		let filter = if difficulty.is_empty() { doc! {} } else { doc! { "difficulty": difficulty } };
+		let cursor = self.learning_path.find(filter).sort(doc! { "title": 1 }).await?;
+		let paths: Vec<LearningPath> = cursor.try_collect().await?;
+		Ok(paths)
+	}
+	pub async fn get_learning_path_by_id(&self, id: ObjectId) -> Result<Option<LearningPath>, Error> {
+		let path = self.learning_path.find_one(doc! { "_id": id }).await?;
+		Ok(path)
+	}
+	pub async fn create_learning_path(&self, path: LearningPath) -> Result<InsertOneResult, Error> {
+		let result = self.learning_path.insert_one(path).await?;
+		Ok(result)
+	}
+	pub async fn update_learning_path(
+		&self,
+		id: ObjectId,
+		path: &LearningPath
+	) -> Result<UpdateResult, Error> {
+		let result = self.learning_path.update_one(
+			doc! { "_id": id },
+			doc! {
+				"$set": {
+					"title": &path.title,
+					"description": &path.description,
+					"difficulty": &path.difficulty,
+					"modules": to_bson(&path.modules)?,
+					"date_updated": DateTime::now(),
+				},
+			}
+		).await?;
+
+		// Progress on resources that were taken out of the path no longer counts
+		self.path_progress.delete_many(
+			doc! { "path_id": id, "resource_id": { "$nin": path.resource_ids() } }
+		).await?;
+
+		Ok(result)
+	}
+	pub async fn delete_learning_path(&self, id: ObjectId) -> Result<DeleteResult, Error> {
+		self.path_progress.delete_many(doc! { "path_id": id }).await?;
+		let result = self.learning_path.delete_one(doc! { "_id": id }).await?;
+		Ok(result)
+	}
+	pub async fn complete_path_resource(&self, progress: PathProgress) -> Result<bool, Error> {
+		let result = self.path_progress
+			.update_one(
+				doc! {
+					"account_id": progress.account_id,
+					"path_id": progress.path_id,
+					"resource_id": progress.resource_id,
+				},
+				doc! { "$setOnInsert": to_bson(&progress)? }
+			)
+			.upsert(true).await?;
+		Ok(result.upserted_id.is_some())
+	}
+	pub async fn uncomplete_path_resource(
+		&self,
+		account_id: ObjectId,
+		path_id: ObjectId,
+		resource_id: ObjectId
+	) -> Result<DeleteResult, Error> {
+		let result = self.path_progress.delete_one(
+			doc! { "account_id": account_id, "path_id": path_id, "resource_id": resource_id }
+		).await?;
+		Ok(result)
+	}
+	pub async fn get_path_progress(
+		&self,
+		account_id: ObjectId,
+		path_id: Option<ObjectId>
+	) -> Result<Vec<PathProgress>, Error> {
+		let mut filter = doc! { "account_id": account_id };
+		if let Some(path_id) = path_id {
+			filter.insert("path_id", path_id);
+		}
+		let cursor = self.path_progress.find(filter).await?;
+		let progress: Vec<PathProgress> = cursor.try_collect().await?;
+		Ok(progress)
+	}
 }
@@ -0,0 +1,369 @@
+use std::env::var;
+use mail_builder::MessageBuilder;
+use mail_send::SmtpClientBuilder;
+
+use crate::utilities::escape::escape_html;
+
+// Define the EmailTemplate enum (every email the server sends)
+#[derive(Clone)]
+pub enum EmailTemplate {
+	Verification {
+		username: String,
+		verify_url: String,
+	},
+	ApplicationReceived {
+		full_name: String,
+		form: String,
+		edit_url: String,
+	},
+	ApplicationDecision {
+		full_name: String,
+		form: String,
+		status: String,
+		note: String,
+	},
+	AnnouncementBroadcast {
+		title: String,
+		body: String,
+	},
+	InterviewInvite {
+		full_name: String,
+		exec_type: String,
+		book_url: String,
+	},
+	InterviewConfirmation {
+		full_name: String,
+		exec_type: String,
+		time: String,
+		location: String,
+	},
+	InterviewReminder {
+		full_name: String,
+		exec_type: String,
+		time: String,
+		location: String,
+	},
+	InterviewCancelled {
+		full_name: String,
+		exec_type: String,
+		time: String,
+	},
+	WaitlistPromoted {
+		username: String,
+		title: String,
+		time: String,
+		location: String,
+	},
+}
+
+// The names of the templates, used by the preview endpoint
+pub const TEMPLATE_NAMES: [&str; 9] = [
+	"verification",
+	"application_received",
+	"application_decision",
+	"announcement_broadcast",
+	"interview_invite",
+	"interview_confirmation",
+	"interview_reminder",
+	"interview_cancelled",
+	"waitlist_promoted",
+];
+
+// Get the address the emails are sent from
+pub fn sender_address() -> String {
+	format!("{}@gmail.com", var("EMAIL_NAME").unwrap())
+}
+
+// Get the website's URL
+pub fn client_url() -> String {
+	var("CLIENT_URL").unwrap_or_default()
+}
+
+impl EmailTemplate {
+	// Create a template filled with sample data to preview it
+	pub fn sample(name: &str) -> Option<Self> {
+		let url = format!("{}/sample", client_url());
+		match name {
+			"verification" =>
+				Some(EmailTemplate::Verification {
+					username: "cyber_student".to_string(),
+					verify_url: url,
+				}),
+			"application_received" =>
+				Some(EmailTemplate::ApplicationReceived {
+					full_name: "Jane Doe".to_string(),
+					form: "general".to_string(),
+					edit_url: url,
+				}),
+			"application_decision" =>
+				Some(EmailTemplate::ApplicationDecision {
+					full_name: "Jane Doe".to_string(),
+					form: "executive".to_string(),
+					status: "accepted".to_string(),
+					note: "Welcome to the team!".to_string(),
+				}),
+			"announcement_broadcast" =>
+				Some(EmailTemplate::AnnouncementBroadcast {
+					title: "First meeting of the year".to_string(),
+					body: "Our first meeting is this Thursday after school.\nSee you there!".to_string(),
+				}),
+			"interview_invite" =>
+				Some(EmailTemplate::InterviewInvite {
+					full_name: "Jane Doe".to_string(),
+					exec_type: "development".to_string(),
+					book_url: url,
+				}),
+			"interview_confirmation" =>
+				Some(EmailTemplate::InterviewConfirmation {
+					full_name: "Jane Doe".to_string(),
+					exec_type: "development".to_string(),
+					time: "Thursday, September 17 at 3:30 PM EDT".to_string(),
+					location: "Room 214".to_string(),
+				}),
+			"interview_reminder" =>
+				Some(EmailTemplate::InterviewReminder {
+					full_name: "Jane Doe".to_string(),
+					exec_type: "development".to_string(),
+					time: "Thursday, September 17 at 3:30 PM EDT".to_string(),
+					location: "Room 214".to_string(),
+				}),
+			"interview_cancelled" =>
+				Some(EmailTemplate::InterviewCancelled {
+					full_name: "Jane Doe".to_string(),
+					exec_type: "development".to_string(),
+					time: "Thursday, September 17 at 3:30 PM EDT".to_string(),
+				}),
+			"waitlist_promoted" =>
+				Some(EmailTemplate::WaitlistPromoted {
+					username: "cyber_student".to_string(),
+					title: "Intro to web exploitation".to_string(),
+					time: "Thursday, September 17 at 3:30 PM EDT".to_string(),
+					location: "Room 214".to_string(),
+				}),
+			_ => None,
+		}
+	}
+
+	// Get the subject line
+	pub fn subject(&self) -> String {
+		match self {
+			EmailTemplate::Verification { .. } => "Please verify your account!".to_string(),
+			EmailTemplate::ApplicationReceived { form, .. } =>
+				format!("We received your {} application!", form),
+			EmailTemplate::ApplicationDecision { form, .. } =>
+				format!("An update on your {} application", form),
+			EmailTemplate::AnnouncementBroadcast { title, .. } => format!("Announcement: {}", title),
+			EmailTemplate::InterviewInvite { .. } => "Book your executive interview".to_string(),
+			EmailTemplate::InterviewConfirmation { .. } => "Your interview is booked!".to_string(),
+			EmailTemplate::InterviewReminder { .. } => "Reminder: your interview is coming up".to_string(),
+			EmailTemplate::InterviewCancelled { .. } => "Your interview time has changed".to_string(),
+			EmailTemplate::WaitlistPromoted { title, .. } => format!("You got a spot at {}!", title),
+		}
+	}
+
+	// Get the content of the HTML body (without the shared layout)
+	fn html_content(&self) -> String {
+		match self {
+			EmailTemplate::Verification { username, verify_url } =>
+				format!(
+					"<h1>Verify your account!</h1><p>Hi {}, click the link below to verify your account and start using the forums.</p><p><a href=\"{}\">{}</a></p>",
+					escape_html(username),
+					escape_html(verify_url),
+					escape_html(verify_url)
+				),
+			EmailTemplate::ApplicationReceived { full_name, form, edit_url } =>
+				format!(
+					"<h1>Thanks for applying!</h1><p>Hi {}, we received your {} application. We will email you when its status changes.</p><p>You can change your application until the form closes with the link below. Do not share it with anyone.</p><p><a href=\"{}\">{}</a></p>",
+					escape_html(full_name),
+					escape_html(form),
+					escape_html(edit_url),
+					escape_html(edit_url)
+				),
+			EmailTemplate::ApplicationDecision { full_name, form, status, note } => {
+				let note = if note.is_empty() {
+					String::new()
+				} else {
+					format!("<p>{}</p>", escape_html(note).replace('\n', "<br>"))
+				};
+				format!(
+					"<h1>Application update</h1><p>Hi {}, the status of your {} application is now <strong>{}</strong>.</p>{}",
+					escape_html(full_name),
+					escape_html(form),
+					escape_html(&status.replace('_', " ")),
+					note
+				)
+			}
+			EmailTemplate::AnnouncementBroadcast { title, body } =>
+				format!(
+					"<h1>{}</h1><p>{}</p><p><a href=\"{}/forum/announcements\">View all announcements</a></p>",
+					escape_html(title),
+					escape_html(body).replace('\n', "<br>"),
+					escape_html(&client_url())
+				),
+			EmailTemplate::InterviewInvite { full_name, exec_type, book_url } =>
+				format!(
+					"<h1>You're invited to an interview!</h1><p>Hi {}, we would like to interview you for the {} executive team. Pick a time that works for you with the link below. Do not share it with anyone.</p><p><a href=\"{}\">{}</a></p>",
+					escape_html(full_name),
+					escape_html(exec_type),
+					escape_html(book_url),
+					escape_html(book_url)
+				),
+			EmailTemplate::InterviewConfirmation { full_name, exec_type, time, location } =>
+				format!(
+					"<h1>Your interview is booked!</h1><p>Hi {}, your interview for the {} executive team is on <strong>{}</strong> in <strong>{}</strong>. The attached file adds it to your calendar.</p>",
+					escape_html(full_name),
+					escape_html(exec_type),
+					escape_html(time),
+					escape_html(location)
+				),
+			EmailTemplate::InterviewCancelled { full_name, exec_type, time } =>
+				format!(
+					"<h1>Your interview time has changed</h1><p>Hi {}, your interview for the {} executive team on <strong>{}</strong> was cancelled because you booked another time. The attached file removes it from your calendar.</p>",
+					escape_html(full_name),
+					escape_html(exec_type),
+					escape_html(time)
+				),
+			EmailTemplate::WaitlistPromoted { username, title, time, location } =>
+				format!(
+					"<h1>You're off the waitlist!</h1><p>Hi {}, a spot opened up at <strong>{}</strong> and it's yours. See you on <strong>{}</strong> in <strong>{}</strong>. If you can't make it anymore, please cancel your RSVP so someone else can have the spot.</p>",
+					escape_html(username),
+					escape_html(title),
+					escape_html(time),
+					escape_html(location)
+				),
+			EmailTemplate::InterviewReminder { full_name, exec_type, time, location } =>
+				format!(
+					"<h1>See you soon!</h1><p>Hi {}, this is a reminder that your interview for the {} executive team is on <strong>{}</strong> in <strong>{}</strong>.</p>",
+					escape_html(full_name),
+					escape_html(exec_type),
+					escape_html(time),
+					escape_html(location)
+				),
+		}
+	}
+
+	// Get the content of the plain-text body (without the shared layout)
+	fn text_content(&self) -> String {
+		match self {
+			EmailTemplate::Verification { username, verify_url } =>
+				format!("Hi {}, verify your account at the following link: {}", username, verify_url),
+			EmailTemplate::ApplicationReceived { full_name, form, edit_url } =>
+				format!(
+					"Hi {}, we received your {} application. We will email you when its status changes.\nYou can change your application until the form closes at the following link (do not share it with anyone): {}",
+					full_name,
+					form,
+					edit_url
+				),
+			EmailTemplate::ApplicationDecision { full_name, form, status, note } => {
+				let mut text = format!(
+					"Hi {}, the status of your {} application is now {}.",
+					full_name,
+					form,
+					status.replace('_', " ")
+				);
+				if !note.is_empty() {
+					text.push_str(&format!("\n\n{}", note));
+				}
+				text
+			}
+			EmailTemplate::AnnouncementBroadcast { title, body } =>
+				format!(
+					"{}\n\n{}\n\nView all announcements at {}/forum/announcements",
+					title,
+					body,
+					client_url()
+				),
+			EmailTemplate::InterviewInvite { full_name, exec_type, book_url } =>
+				format!(
+					"Hi {}, we would like to interview you for the {} executive team. Pick a time that works for you at the following link (do not share it with anyone): {}",
+					full_name,
+					exec_type,
+					book_url
+				),
+			EmailTemplate::InterviewConfirmation { full_name, exec_type, time, location } =>
+				format!(
+					"Hi {}, your interview for the {} executive team is on {} in {}. The attached file adds it to your calendar.",
+					full_name,
+					exec_type,
+					time,
+					location
+				),
+			EmailTemplate::InterviewCancelled { full_name, exec_type, time } =>
+				format!(
+					"Hi {}, your interview for the {} executive team on {} was cancelled because you booked another time. The attached file removes it from your calendar.",
+					full_name,
+					exec_type,
+					time
+				),
+			EmailTemplate::WaitlistPromoted { username, title, time, location } =>
+				format!(
+					"Hi {}, a spot opened up at {} and it's yours. See you on {} in {}. If you can't make it anymore, please cancel your RSVP so someone else can have the spot.",
+					username,
+					title,
+					time,
+					location
+				),
+			EmailTemplate::InterviewReminder { full_name, exec_type, time, location } =>
+				format!(
+					"Hi {}, this is a reminder that your interview for the {} executive team is on {} in {}.",
+					full_name,
+					exec_type,
+					time,
+					location
+				),
+		}
+	}
+
+	// Get the HTML body wrapped in the shared layout
+	pub fn html(&self) -> String {
+		format!(
+			"<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{}</title></head><body style=\"margin:0;padding:0;background-color:#0b0f19;font-family:Arial,Helvetica,sans-serif;color:#e5e7eb;\"><table width=\"100%\" cellpadding=\"0\" cellspacing=\"0\"><tr><td align=\"center\" style=\"padding:24px;\"><table width=\"600\" cellpadding=\"0\" cellspacing=\"0\" style=\"background-color:#111827;border-radius:8px;\"><tr><td style=\"padding:24px;border-bottom:2px solid #22c55e;\"><a href=\"{}\" style=\"color:#22c55e;font-size:24px;font-weight:bold;text-decoration:none;\">HB CyberTech</a></td></tr><tr><td style=\"padding:24px;line-height:1.5;\">{}</td></tr><tr><td style=\"padding:24px;font-size:12px;color:#9ca3af;\">You are receiving this email because of your activity with HB CyberTech.<br><a href=\"{}\" style=\"color:#9ca3af;\">{}</a></td></tr></table></td></tr></table></body></html>",
+			escape_html(&self.subject()),
+			escape_html(&client_url()),
+			self.html_content(),
+			escape_html(&client_url()),
+			escape_html(&client_url())
+		)
+	}
+
+	// Get the plain-text body wrapped in the shared layout
+	pub fn text(&self) -> String {
+		format!(
+			"HB CyberTech\n\n{}\n\n--\nYou are receiving this email because of your activity with HB CyberTech.\n{}",
+			self.text_content(),
+			client_url()
+		)
+	}
+
+	// Create the message to send
+	pub fn message(&self, to: String) -> MessageBuilder<'static> {
+		MessageBuilder::new()
+			.from(("HB CyberTech".to_owned(), sender_address()))
+			.to(to)
+			.subject(self.subject())
+			.html_body(self.html())
+			.text_body(self.text())
+	}
+}
+
+// Create the SMTP client builder with the club's credentials
+pub fn smtp_client() -> SmtpClientBuilder<String> {
+	SmtpClientBuilder::new("smtp.gmail.com".to_string(), 587)
+		.implicit_tls(false)
+		.credentials((var("EMAIL_NAME").unwrap(), var("EMAIL_PASSWORD").unwrap()))
+}
+
+// Send an email to the given address
+pub async fn send_email(to: String, template: &EmailTemplate) -> Result<(), mail_send::Error> {
+	smtp_client().connect().await?.send(template.message(to)).await
+}
+
+// Send an email with an iCalendar file attached
+pub async fn send_email_with_calendar(
+	to: String,
+	template: &EmailTemplate,
+	calendar: String
+) -> Result<(), mail_send::Error> {
+	let message = template.message(to).attachment("text/calendar", "invite.ics", calendar);
+	smtp_client().connect().await?.send(message).await
+}
@@ -0,0 +1,159 @@
+use actix_web::web::Bytes;
+use rust_xlsxwriter::{ Format, Workbook, XlsxError };
+
+use crate::models::{
+	account::Account,
+	executive_member::ExecutiveMember,
+	general_member::GeneralMember,
+};
+
+// Define the Exportable trait (a record that can be exported as a spreadsheet row)
+pub trait Exportable {
+	// The columns that can be exported, in their default order
+	const COLUMNS: &'static [&'static str];
+
+	// Get the value of a column
+	fn value(&self, column: &str) -> String;
+}
+
+impl Exportable for GeneralMember {
+	const COLUMNS: &'static [&'static str] = &[
+		"id",
+		"full_name",
+		"email",
+		"grade",
+		"skills",
+		"extra",
+		"status",
+		"date_created",
+	];
+
+	fn value(&self, column: &str) -> String {
+		match column {
+			"id" => self._id.to_string(),
+			"full_name" => self.full_name.clone(),
+			"email" => self.email.clone(),
+			"grade" => self.grade.to_string(),
+			"skills" => self.skills.to_string(),
+			"extra" => self.extra.clone(),
+			"status" => self.status.clone(),
+			"date_created" => self.date_created.to_string(),
+			_ => String::new(),
+		}
+	}
+}
+
+impl Exportable for ExecutiveMember {
+	const COLUMNS: &'static [&'static str] = &[
+		"id",
+		"full_name",
+		"email",
+		"grade",
+		"exec_type",
+		"why",
+		"experience",
+		"portfolio",
+		"extra",
+		"status",
+		"date_created",
+	];
+
+	fn value(&self, column: &str) -> String {
+		match column {
+			"id" => self._id.to_string(),
+			"full_name" => self.full_name.clone(),
+			"email" => self.email.clone(),
+			"grade" => self.grade.to_string(),
+			"exec_type" => self.exec_type.clone(),
+			"why" => self.why.clone(),
+			"experience" => self.experience.clone(),
+			"portfolio" => self.portfolio.clone(),
+			"extra" => self.extra.clone(),
+			"status" => self.status.clone(),
+			"date_created" => self.date_created.to_string(),
+			_ => String::new(),
+		}
+	}
+}
+
+// Passwords are never exportable
+impl Exportable for Account {
+	const COLUMNS: &'static [&'static str] = &["id", "username", "email", "verified", "date_created"];
+
+	fn value(&self, column: &str) -> String {
+		match column {
+			"id" => self._id.to_string(),
+			"username" => self.username.clone(),
+			"email" => self.email.clone(),
+			"verified" => self.verified.to_string(),
+			"date_created" => self.date_created.to_string(),
+			_ => String::new(),
+		}
+	}
+}
+
+// Get the columns to export, where no columns means every column
+pub fn select_columns<T: Exportable>(columns: &[String]) -> Result<Vec<&'static str>, String> {
+	if columns.is_empty() {
+		return Ok(T::COLUMNS.to_vec());
+	}
+
+	columns
+		.iter()
+		.map(|column| {
+			T::COLUMNS.iter()
+				.find(|allowed| *allowed == column)
+				.copied()
+				.ok_or_else(|| format!("Invalid column: {}.", column))
+		})
+		.collect()
+}
+
+// Get the values of the selected columns of a record
+pub fn row<T: Exportable>(item: &T, columns: &[&str]) -> Vec<String> {
+	columns
+		.iter()
+		.map(|column| item.value(column))
+		.collect()
+}
+
+// Stop spreadsheet programs from running values as formulas
+fn neutralize(value: &str) -> String {
+	if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+		format!("'{}", value)
+	} else {
+		value.to_string()
+	}
+}
+
+// Write a single CSV line
+pub fn csv_line<S: AsRef<str>>(values: &[S]) -> Bytes {
+	let mut writer = csv::Writer::from_writer(Vec::new());
+	writer
+		.write_record(values.iter().map(|value| neutralize(value.as_ref())))
+		.expect("Error writing CSV record.");
+	Bytes::from(writer.into_inner().expect("Error writing CSV record."))
+}
+
+// Write every row to an XLSX workbook
+pub fn xlsx(sheet_name: &str, columns: &[&str], rows: Vec<Vec<String>>) -> Result<Vec<u8>, XlsxError> {
+	let mut workbook = Workbook::new();
+	let worksheet = workbook.add_worksheet();
+	worksheet.set_name(sheet_name)?;
+
+	// Write the header in bold and keep it visible while scrolling
+	let bold = Format::new().set_bold();
+	for (col, column) in columns.iter().enumerate() {
+		worksheet.write_string_with_format(0, col as u16, *column, &bold)?;
+	}
+	worksheet.set_freeze_panes(1, 0)?;
+
+	for (index, values) in rows.iter().enumerate() {
+		for (col, value) in values.iter().enumerate() {
+			worksheet.write_string((index as u32) + 1, col as u16, value)?;
+		}
+	}
+	worksheet.autofit();
+
+	workbook.save_to_buffer()
+}
@@ -0,0 +1,97 @@
+use chrono::{ DateTime, Utc };
+use mongodb::bson;
+
+use crate::utilities::escape::escape_html;
+
+// Define the FeedEntry struct (a single announcement or forum post in a feed)
+pub struct FeedEntry {
+	pub id: String,
+	pub title: String,
+	pub link: String,
+	pub author: String,
+	pub content: String,
+	pub published: DateTime<Utc>,
+	pub updated: DateTime<Utc>,
+}
+
+// Define the Feed struct
+pub struct Feed {
+	pub id: String,
+	pub title: String,
+	pub link: String,
+	pub self_link: String,
+	pub entries: Vec<FeedEntry>,
+}
+
+// Convert a BSON date to a chrono date
+pub fn to_chrono(date: bson::DateTime) -> DateTime<Utc> {
+	DateTime::<Utc>::from_timestamp_millis(date.timestamp_millis()).unwrap_or_default()
+}
+
+impl Feed {
+	// Get the date of the most recent change in the feed
+	pub fn updated(&self) -> DateTime<Utc> {
+		self.entries
+			.iter()
+			.map(|entry| entry.updated)
+			.max()
+			.unwrap_or_default()
+	}
+
+	// Render the feed as Atom
+	pub fn to_atom(&self) -> String {
+		let entries: String = self.entries
+			.iter()
+			.map(|entry|
+				format!(
+					"<entry><id>{}</id><title>{}</title><link rel=\"alternate\" href=\"{}\"/><author><name>{}</name></author><published>{}</published><updated>{}</updated><content type=\"text\">{}</content></entry>",
+					escape_html(&entry.id),
+					escape_html(&entry.title),
+					escape_html(&entry.link),
+					escape_html(&entry.author),
+					entry.published.to_rfc3339(),
+					entry.updated.to_rfc3339(),
+					escape_html(&entry.content)
+				)
+			)
+			.collect();
+
+		format!(
+			"<?xml version=\"1.0\" encoding=\"utf-8\"?><feed xmlns=\"http://www.w3.org/2005/Atom\"><id>{}</id><title>{}</title><link rel=\"alternate\" href=\"{}\"/><link rel=\"self\" href=\"{}\"/><updated>{}</updated>{}</feed>",
+			escape_html(&self.id),
+			escape_html(&self.title),
+			escape_html(&self.link),
+			escape_html(&self.self_link),
+			self.updated().to_rfc3339(),
+			entries
+		)
+	}
+
+	// Render the feed as RSS 2.0
+	pub fn to_rss(&self) -> String {
+		let items: String = self.entries
+			.iter()
+			.map(|entry|
+				format!(
+					"<item><guid isPermaLink=\"false\">{}</guid><title>{}</title><link>{}</link><dc:creator>{}</dc:creator><pubDate>{}</pubDate><description>{}</description></item>",
+					escape_html(&entry.id),
+					escape_html(&entry.title),
+					escape_html(&entry.link),
+					escape_html(&entry.author),
+					entry.published.to_rfc2822(),
+					escape_html(&entry.content)
+				)
+			)
+			.collect();
+
+		format!(
+			"<?xml version=\"1.0\" encoding=\"utf-8\"?><rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\"><channel><title>{}</title><link>{}</link><description>{}</description><atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/><lastBuildDate>{}</lastBuildDate>{}</channel></rss>",
+			escape_html(&self.title),
+			escape_html(&self.link),
+			escape_html(&self.title),
+			escape_html(&self.self_link),
+			self.updated().to_rfc2822(),
+			items
+		)
+	}
+}
@@ -0,0 +1,106 @@
+use std::time::Duration;
+use actix_web::{ rt, web::Data };
+use mongodb::bson::DateTime;
+
+use crate::{
+	models::interview::InterviewSlot,
+	services::{
+		calendar::{ calendar, calendar_with_method, local_time, CalendarEvent },
+		db::Database,
+		email::{ send_email_with_calendar, EmailTemplate },
+		feed::to_chrono,
+	},
+};
+
+// How often to look for interviews that need a reminder
+const CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+// How long before an interview its reminder is sent
+const REMINDER_LEAD_MILLIS: i64 = 24 * 60 * 60 * 1000;
+
+// Write the calendar event of an interview
+fn interview_event(slot: &InterviewSlot, exec_type: &str, sequence: u32, status: &str) -> CalendarEvent {
+	CalendarEvent {
+		uid: format!("interview-{}@hbcybertech", slot._id),
+		summary: format!("HB CyberTech {} executive interview", exec_type),
+		description: String::new(),
+		location: slot.location.clone(),
+		url: String::new(),
+		starts_at: to_chrono(slot.starts_at),
+		ends_at: to_chrono(slot.ends_at),
+		date_updated: to_chrono(DateTime::now()),
+		sequence,
+		status: status.to_string(),
+	}
+}
+
+// Write the iCalendar file of a booked interview
+pub fn interview_calendar(slot: &InterviewSlot, exec_type: &str) -> String {
+	calendar("HB CyberTech interview", &[interview_event(slot, exec_type, 0, "CONFIRMED")])
+}
+
+// Write the iCalendar file that removes an interview the candidate no longer has from their calendar
+pub fn interview_cancellation(slot: &InterviewSlot, exec_type: &str) -> String {
+	calendar_with_method(
+		"HB CyberTech interview",
+		"CANCEL",
+		&[interview_event(slot, exec_type, 1, "CANCELLED")]
+	)
+}
+
+// Start the task that reminds candidates of their interviews the day before
+pub fn start_reminders(db: Data<Database>) {
+	rt::spawn(async move {
+		loop {
+			send_reminders(&db).await;
+			rt::time::sleep(CHECK_INTERVAL).await;
+		}
+	});
+}
+
+// Send a reminder for every booked interview starting soon
+async fn send_reminders(db: &Database) {
+	let before = DateTime::from_millis(DateTime::now().timestamp_millis() + REMINDER_LEAD_MILLIS);
+	let slots = match db.get_interviews_needing_reminders(before).await {
+		Ok(slots) => slots,
+		Err(err) => {
+			println!("Error getting interviews: {}", err);
+			return;
+		}
+	};
+
+	for slot in slots {
+		let member = match slot.application_id {
+			Some(id) => db.get_executive_member_by_id(id.to_hex()).await,
+			None => {
+				continue;
+			}
+		};
+		let member = match member {
+			Ok(Some(member)) => member,
+			Ok(None) => {
+				continue;
+			}
+			Err(err) => {
+				println!("Error getting executive member: {}", err);
+				continue;
+			}
+		};
+
+		let email = EmailTemplate::InterviewReminder {
+			full_name: member.full_name.clone(),
+			exec_type: member.exec_type.clone(),
+			time: local_time(slot.starts_at),
+			location: slot.location.clone(),
+		};
+		let calendar = interview_calendar(&slot, &member.exec_type);
+		match send_email_with_calendar(member.email.clone(), &email, calendar).await {
+			Ok(_) => {
+				if let Err(err) = db.mark_interview_reminder_sent(slot._id).await {
+					println!("Error updating interview: {}", err);
+				}
+			}
+			Err(err) => println!("Error sending email: {}", err),
+		}
+	}
+}
@@ -0,0 +1,255 @@
+use std::{ env::var, sync::atomic::{ AtomicBool, Ordering }, time::Duration };
+use actix_web::{ rt, web::Data };
+use mongodb::bson::DateTime;
+use reqwest::{ redirect::Policy, Client, Response };
+
+use crate::{ models::resource::{ LinkCheck, LinkHealth }, services::db::Database };
+
+// How long a link has to answer, and how many redirects are followed
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+const MAX_REDIRECTS: usize = 10;
+
+// How many checks are kept in a resource's history
+pub const HISTORY_LENGTH: usize = 20;
+
+// Whether every link is being checked right now
+static CHECKING: AtomicBool = AtomicBool::new(false);
+
+// Get the time between two rounds of checks (LINK_CHECK_INTERVAL_HOURS, every day by default)
+fn check_interval() -> Duration {
+	let hours = var("LINK_CHECK_INTERVAL_HOURS")
+		.ok()
+		.and_then(|hours| hours.parse::<u64>().ok())
+		.filter(|hours| *hours > 0)
+		.unwrap_or(24);
+	Duration::from_secs(hours * 60 * 60)
+}
+
+// Get how many checks in a row a link has to fail to be dead (LINK_DEAD_AFTER, 3 by default)
+fn dead_after() -> u32 {
+	var("LINK_DEAD_AFTER")
+		.ok()
+		.and_then(|checks| checks.parse::<u32>().ok())
+		.filter(|checks| *checks > 0)
+		.unwrap_or(3)
+}
+
+// Build the client the links are checked with
+pub fn link_client(timeout: Duration) -> reqwest::Result<Client> {
+	Client::builder()
+		.redirect(Policy::limited(MAX_REDIRECTS))
+		.timeout(timeout)
+		.user_agent("HB CyberTech link checker")
+		.build()
+}
+
+// Turn the response to a request into a check
+fn to_check(response: reqwest::Result<Response>) -> LinkCheck {
+	match response {
+		Ok(response) =>
+			LinkCheck {
+				date: DateTime::now(),
+				ok: response.status().is_success(),
+				status: Some(response.status().as_u16()),
+				error: String::new(),
+			},
+		Err(err) =>
+			LinkCheck {
+				date: DateTime::now(),
+				ok: false,
+				status: err.status().map(|status| status.as_u16()),
+				error: err.without_url().to_string(),
+			},
+	}
+}
+
+// Check a link with a HEAD request, trying a GET request instead if that fails since some sites refuse HEAD
+pub async fn check_link(client: &Client, link: &str) -> LinkCheck {
+	let check = to_check(client.head(link).send().await);
+	if check.ok {
+		return check;
+	}
+	to_check(client.get(link).send().await)
+}
+
+// Add a check to a link's health, marking it dead once it has failed enough checks in a row
+pub fn record_check(health: &mut LinkHealth, check: LinkCheck, dead_after: u32) {
+	health.last_checked = Some(check.date);
+	if check.ok {
+		health.consecutive_failures = 0;
+		health.dead = false;
+	} else {
+		health.consecutive_failures += 1;
+		health.dead = health.consecutive_failures >= dead_after;
+	}
+
+	health.history.push(check);
+	if health.history.len() > HISTORY_LENGTH {
+		let extra = health.history.len() - HISTORY_LENGTH;
+		health.history.drain(..extra);
+	}
+}
+
+// Start the task that checks every resource's link periodically
+pub fn start_link_checker(db: Data<Database>) {
+	rt::spawn(async move {
+		loop {
+			if !CHECKING.swap(true, Ordering::SeqCst) {
+				check_all_links(&db, true).await;
+			}
+			rt::time::sleep(check_interval()).await;
+		}
+	});
+}
+
+// Start checking every resource's link in the background, returning false if a round of checks is already going
+pub fn start_link_check(db: Data<Database>) -> bool {
+	if CHECKING.swap(true, Ordering::SeqCst) {
+		return false;
+	}
+	rt::spawn(async move {
+		check_all_links(&db, false).await;
+	});
+	true
+}
+
+// Check the link of every resource, once the round of checks has been claimed. When only due links are
+// checked, the ones checked within the check interval are skipped so restarting the server doesn't check
+// every link again.
+async fn check_all_links(db: &Database, only_due: bool) {
+	let client = match link_client(REQUEST_TIMEOUT) {
+		Ok(client) => client,
+		Err(err) => {
+			println!("Error building the link checker: {}", err);
+			CHECKING.store(false, Ordering::SeqCst);
+			return;
+		}
+	};
+	let checked_before = only_due.then(|| {
+		DateTime::from_millis(DateTime::now().timestamp_millis() - (check_interval().as_millis() as i64))
+	});
+	let resources = match db.get_resources_to_check(checked_before).await {
+		Ok(resources) => resources,
+		Err(err) => {
+			println!("Error getting resources: {}", err);
+			CHECKING.store(false, Ordering::SeqCst);
+			return;
+		}
+	};
+
+	let dead_after = dead_after();
+	for resource in resources {
+		let mut health = resource.link_health;
+		record_check(&mut health, check_link(&client, &resource.link).await, dead_after);
+
+		// The health is only saved if the link was not changed while it was being checked
+		if let Err(err) = db.set_link_health(resource._id, &resource.link, &health).await {
+			println!("Error saving the link check of {}: {}", resource.link, err);
+		}
+	}
+
+	CHECKING.store(false, Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{ io::{ BufRead, BufReader, Write }, net::TcpListener, thread };
+	use super::*;
+
+	// Serve each request with the response the handler gives for its method and path, or keep the connection
+	// open without answering if it gives none
+	fn serve(handler: fn(&str, &str) -> Option<String>) -> String {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let address = listener.local_addr().unwrap();
+		thread::spawn(move || {
+			for stream in listener.incoming() {
+				let mut stream = stream.unwrap();
+				let mut reader = BufReader::new(stream.try_clone().unwrap());
+				let mut request_line = String::new();
+				reader.read_line(&mut request_line).unwrap();
+				let mut line = String::new();
+				while reader.read_line(&mut line).unwrap() > 2 {
+					line.clear();
+				}
+
+				let mut parts = request_line.split_whitespace();
+				let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+				match handler(method, path) {
+					Some(response) => {
+						stream.write_all(response.as_bytes()).unwrap();
+					}
+					None => thread::sleep(Duration::from_secs(5)),
+				}
+			}
+		});
+		format!("http://{}", address)
+	}
+
+	fn response(status: &str, headers: &str) -> String {
+		format!("HTTP/1.1 {}\r\n{}Content-Length: 0\r\nConnection: close\r\n\r\n", status, headers)
+	}
+
+	fn check(ok: bool) -> LinkCheck {
+		LinkCheck { date: DateTime::now(), ok, status: None, error: String::new() }
+	}
+
+	#[actix_web::test]
+	async fn falls_back_to_get_when_head_is_refused() {
+		let url = serve(|method, _| {
+			Some(match method {
+				"HEAD" => response("405 Method Not Allowed", ""),
+				_ => response("200 OK", ""),
+			})
+		});
+		let client = link_client(REQUEST_TIMEOUT).unwrap();
+
+		let check = check_link(&client, &url).await;
+		assert!(check.ok);
+		assert_eq!(check.status, Some(200));
+	}
+
+	#[actix_web::test]
+	async fn follows_redirects() {
+		let url = serve(|_, path| {
+			Some(match path {
+				"/old" => response("301 Moved Permanently", "Location: /new\r\n"),
+				"/new" => response("200 OK", ""),
+				_ => response("404 Not Found", ""),
+			})
+		});
+		let client = link_client(REQUEST_TIMEOUT).unwrap();
+
+		let check = check_link(&client, &format!("{}/old", url)).await;
+		assert!(check.ok);
+		assert_eq!(check.status, Some(200));
+	}
+
+	#[actix_web::test]
+	async fn fails_when_the_link_times_out() {
+		let url = serve(|_, _| None);
+		let client = link_client(Duration::from_millis(200)).unwrap();
+
+		let check = check_link(&client, &url).await;
+		assert!(!check.ok);
+		assert_eq!(check.status, None);
+		assert!(!check.error.is_empty());
+	}
+
+	#[test]
+	fn marks_a_link_dead_after_enough_failures_in_a_row() {
+		let mut health = LinkHealth::default();
+		record_check(&mut health, check(false), 3);
+		record_check(&mut health, check(false), 3);
+		assert!(!health.dead);
+		assert_eq!(health.consecutive_failures, 2);
+
+		record_check(&mut health, check(false), 3);
+		assert!(health.dead);
+		assert_eq!(health.consecutive_failures, 3);
+
+		record_check(&mut health, check(true), 3);
+		assert!(!health.dead);
+		assert_eq!(health.consecutive_failures, 0);
+		assert_eq!(health.history.len(), 4);
+	}
+}
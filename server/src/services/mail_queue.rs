@@ -0,0 +1,97 @@
+use std::{ env::var, time::Duration };
+use actix_web::{ rt, web::Data };
+use mongodb::bson::{ oid::ObjectId, DateTime };
+use tokio::sync::mpsc::{ unbounded_channel, UnboundedSender };
+
+use crate::services::{ db::Database, email::{ smtp_client, EmailTemplate } };
+
+// Define the MailJob struct (one email sent to many recipients)
+pub struct MailJob {
+	pub broadcast_id: ObjectId,
+	pub recipients: Vec<String>,
+	pub template: EmailTemplate,
+}
+
+// Define the MailQueue struct
+pub struct MailQueue {
+	sender: UnboundedSender<MailJob>,
+}
+
+// Get the delay between two emails so the SMTP server does not throttle us
+fn send_delay() -> Duration {
+	let millis = var("EMAIL_DELAY_MS")
+		.ok()
+		.and_then(|delay| delay.parse::<u64>().ok())
+		.unwrap_or(1000);
+	Duration::from_millis(millis)
+}
+
+impl MailQueue {
+	// Start the worker that sends the queued emails one at a time
+	pub fn start(db: Data<Database>) -> Self {
+		let (sender, mut receiver) = unbounded_channel::<MailJob>();
+		let started = DateTime::now();
+
+		rt::spawn(async move {
+			// The queue is only kept in memory, so broadcasts a previous run did not finish can't be sent anymore
+			if let Err(err) = db.fail_unfinished_broadcasts(started).await {
+				println!("Error updating broadcasts: {}", err);
+			}
+
+			while let Some(job) = receiver.recv().await {
+				process_job(&db, job).await;
+			}
+		});
+
+		MailQueue { sender }
+	}
+
+	// Add a job to the queue
+	pub fn enqueue(&self, job: MailJob) -> bool {
+		self.sender.send(job).is_ok()
+	}
+}
+
+// Send every email of a job and record the results in the delivery report
+async fn process_job(db: &Database, job: MailJob) {
+	if let Err(err) = db.update_broadcast_status(job.broadcast_id, "sending").await {
+		println!("Error updating broadcast: {}", err);
+	}
+
+	// Reuse the connection between emails and reconnect if it drops
+	let mut client = None;
+	for email in job.recipients {
+		if client.is_none() {
+			client = match smtp_client().connect().await {
+				Ok(client) => Some(client),
+				Err(err) => {
+					println!("Error connecting to the SMTP server: {}", err);
+					None
+				}
+			};
+		}
+
+		let delivered = match client.as_mut() {
+			Some(smtp) =>
+				match smtp.send(job.template.message(email.clone())).await {
+					Ok(_) => true,
+					Err(err) => {
+						println!("Error sending email to {}: {}", email, err);
+						client = None;
+						false
+					}
+				}
+			None => false,
+		};
+
+		if let Err(err) = db.record_broadcast_delivery(job.broadcast_id, &email, delivered).await {
+			println!("Error updating broadcast: {}", err);
+		}
+
+		rt::time::sleep(send_delay()).await;
+	}
+
+	if let Err(err) = db.update_broadcast_status(job.broadcast_id, "finished").await {
+		println!("Error updating broadcast: {}", err);
+	}
+}
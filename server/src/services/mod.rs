@@ -1,3 +1,13 @@
 // Module: services
 
+pub mod calendar;
+pub mod checkin;
 pub mod db;
+pub mod email;
+pub mod export;
+pub mod feed;
+pub mod interview;
+pub mod link_checker;
+pub mod mail_queue;
+pub mod scoreboard;
+pub mod storage;
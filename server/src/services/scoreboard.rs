@@ -0,0 +1,291 @@
+use std::collections::{ HashMap, HashSet };
+use mongodb::bson::{ oid::ObjectId, DateTime };
+
+use crate::models::ctf::{
+	Challenge,
+	ChallengeScore,
+	HintUnlock,
+	Scoreboard,
+	ScoreboardEntry,
+	ScorePoint,
+	Solve,
+};
+
+// Define the Competitor struct (a running total while the solves and hints are replayed)
+struct Competitor {
+	name: String,
+	score: i64,
+	solves: u64,
+	hint_penalty: u64,
+	last_solve: Option<DateTime>,
+	first_bloods: Vec<String>,
+	history: Vec<ScorePoint>,
+}
+
+// Build the scoreboard from the visible challenges and the solves to count, replaying the solves in order.
+// Every solve of a challenge is worth the challenge's current value, so scores drop as more people solve it,
+// and ties go to whoever reached their score first. Unlocked hints take their cost off the score. Given the
+// names of the teams, it ranks teams instead, counting only the first solve of each challenge (and the first
+// unlock of each hint) by a team. A frozen scoreboard leaves out everything from the freeze on.
+pub fn build_scoreboard(
+	challenges: &[Challenge],
+	mut solves: Vec<Solve>,
+	mut unlocks: Vec<HintUnlock>,
+	teams: Option<&HashMap<ObjectId, String>>,
+	frozen: bool,
+	freeze_at: Option<DateTime>
+) -> Scoreboard {
+	let challenges: HashMap<ObjectId, &Challenge> = challenges
+		.iter()
+		.map(|challenge| (challenge._id, challenge))
+		.collect();
+	let before_freeze = |date: DateTime| !frozen || Some(date) < freeze_at;
+	solves.retain(|solve| challenges.contains_key(&solve.challenge_id) && before_freeze(solve.date_created));
+	solves.sort_by_key(|solve| solve.date_created);
+
+	unlocks.retain(|unlock| challenges.contains_key(&unlock.challenge_id) && before_freeze(unlock.date_created));
+	unlocks.sort_by_key(|unlock| unlock.date_created);
+
+	// Get who a solve or an unlock counts for
+	let competitor_of = |account_id: ObjectId, username: &str, team_id: Option<ObjectId>| {
+		match teams {
+			Some(teams) => {
+				let team_id = team_id?;
+				Some((team_id, teams.get(&team_id)?.clone()))
+			}
+			None => Some((account_id, username.to_string())),
+		}
+	};
+	let solver_of = |solve: &Solve| competitor_of(solve.account_id, &solve.username, solve.team_id);
+	if teams.is_some() {
+		let mut counted: HashSet<(ObjectId, ObjectId)> = HashSet::new();
+		solves.retain(|solve| {
+			solver_of(solve).is_some_and(|(id, _)| counted.insert((id, solve.challenge_id)))
+		});
+		let mut counted: HashSet<(ObjectId, ObjectId, u32)> = HashSet::new();
+		unlocks.retain(|unlock| {
+			competitor_of(unlock.account_id, &unlock.username, unlock.team_id).is_some_and(|(id, _)|
+				counted.insert((id, unlock.challenge_id, unlock.index))
+			)
+		});
+	}
+
+	// Work out what each challenge is worth and who solved it first
+	let mut solve_counts: HashMap<ObjectId, u64> = HashMap::new();
+	let mut first_bloods: HashMap<ObjectId, &Solve> = HashMap::new();
+	for solve in &solves {
+		*solve_counts.entry(solve.challenge_id).or_insert(0) += 1;
+		first_bloods.entry(solve.challenge_id).or_insert(solve);
+	}
+	let values: HashMap<ObjectId, u32> = challenges
+		.values()
+		.map(|challenge| {
+			let solves = solve_counts.get(&challenge._id).copied().unwrap_or(0);
+			(challenge._id, challenge.value(solves))
+		})
+		.collect();
+
+	// Replay the solves and unlocks in the order they happened
+	let mut events: Vec<(DateTime, Option<&Solve>, Option<&HintUnlock>)> = solves
+		.iter()
+		.map(|solve| (solve.date_created, Some(solve), None))
+		.chain(unlocks.iter().map(|unlock| (unlock.date_created, None, Some(unlock))))
+		.collect();
+	events.sort_by_key(|(date, _, _)| *date);
+
+	let mut competitors: HashMap<ObjectId, Competitor> = HashMap::new();
+	for (date, solve, unlock) in events {
+		let competitor = match (solve, unlock) {
+			(Some(solve), _) => solver_of(solve),
+			(_, Some(unlock)) => competitor_of(unlock.account_id, &unlock.username, unlock.team_id),
+			_ => None,
+		};
+		let (id, name) = match competitor {
+			Some(competitor) => competitor,
+			None => {
+				continue;
+			}
+		};
+		let competitor = competitors.entry(id).or_insert(Competitor {
+			name,
+			score: 0,
+			solves: 0,
+			hint_penalty: 0,
+			last_solve: None,
+			first_bloods: Vec::new(),
+			history: Vec::new(),
+		});
+
+		if let Some(solve) = solve {
+			competitor.score += values[&solve.challenge_id] as i64;
+			competitor.solves += 1;
+			competitor.last_solve = Some(solve.date_created);
+			if first_bloods[&solve.challenge_id]._id == solve._id {
+				competitor.first_bloods.push(challenges[&solve.challenge_id].title.clone());
+			}
+		}
+		if let Some(unlock) = unlock {
+			competitor.score -= unlock.cost as i64;
+			competitor.hint_penalty += unlock.cost as u64;
+		}
+		competitor.history.push(ScorePoint {
+			date: date.to_string(),
+			score: competitor.score,
+		});
+	}
+
+	let mut competitors: Vec<Competitor> = competitors.into_values().collect();
+	competitors.sort_by(|a, b| {
+		b.score
+			.cmp(&a.score)
+			.then((a.last_solve.is_none(), a.last_solve).cmp(&(b.last_solve.is_none(), b.last_solve)))
+	});
+	let entries: Vec<ScoreboardEntry> = competitors
+		.into_iter()
+		.enumerate()
+		.map(|(index, competitor)| ScoreboardEntry {
+			rank: (index as u64) + 1,
+			name: competitor.name,
+			score: competitor.score,
+			solves: competitor.solves,
+			hint_penalty: competitor.hint_penalty,
+			last_solve: competitor.last_solve.map(|date| date.to_string()).unwrap_or_default(),
+			first_bloods: competitor.first_bloods,
+			history: competitor.history,
+		})
+		.collect();
+
+	let mut challenge_scores: Vec<ChallengeScore> = challenges
+		.values()
+		.map(|challenge| ChallengeScore {
+			id: challenge._id.to_string(),
+			title: challenge.title.clone(),
+			category: challenge.category.clone(),
+			value: values[&challenge._id],
+			solves: solve_counts.get(&challenge._id).copied().unwrap_or(0),
+			first_blood: first_bloods
+				.get(&challenge._id)
+				.and_then(|solve| solver_of(solve))
+				.map(|(_, name)| name)
+				.unwrap_or_default(),
+		})
+		.collect();
+	challenge_scores.sort_by(|a, b| a.category.cmp(&b.category).then(a.title.cmp(&b.title)));
+
+	Scoreboard {
+		frozen,
+		freeze_at: freeze_at.map(|date| date.to_string()).unwrap_or_default(),
+		challenges: challenge_scores,
+		entries,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn challenge(title: &str, points: u32) -> Challenge {
+		Challenge {
+			_id: ObjectId::new(),
+			title: title.to_string(),
+			category: "misc".to_string(),
+			description: "Description".to_string(),
+			points,
+			minimum_points: points,
+			decay: 0,
+			attachments: Vec::new(),
+			hints: Vec::new(),
+			flag_hash: String::new(),
+			flag_regex: String::new(),
+			hidden: false,
+			competition_id: None,
+			date_created: DateTime::from_millis(0),
+			date_updated: DateTime::from_millis(0),
+		}
+	}
+
+	// Get an account with its username
+	fn player(username: &str) -> (ObjectId, String) {
+		(ObjectId::new(), username.to_string())
+	}
+
+	fn solve(challenge: &Challenge, player: &(ObjectId, String), team_id: Option<ObjectId>, at: i64) -> Solve {
+		Solve {
+			_id: ObjectId::new(),
+			challenge_id: challenge._id,
+			account_id: player.0,
+			username: player.1.clone(),
+			team_id,
+			date_created: DateTime::from_millis(at),
+		}
+	}
+
+	fn names(scoreboard: &Scoreboard) -> Vec<(String, i64)> {
+		scoreboard.entries
+			.iter()
+			.map(|entry| (entry.name.clone(), entry.score))
+			.collect()
+	}
+
+	#[test]
+	fn ties_go_to_whoever_reached_the_score_first() {
+		let (web, crypto) = (challenge("Web", 100), challenge("Crypto", 100));
+		let (alice, bob) = (player("alice"), player("bob"));
+		let solves = vec![
+			solve(&web, &bob, None, 1),
+			solve(&web, &alice, None, 2),
+			solve(&crypto, &alice, None, 3),
+			solve(&crypto, &bob, None, 4)
+		];
+
+		let scoreboard = build_scoreboard(&[web, crypto], solves, Vec::new(), None, false, None);
+		assert_eq!(names(&scoreboard), vec![("alice".to_string(), 200), ("bob".to_string(), 200)]);
+		assert_eq!(scoreboard.entries[1].rank, 2);
+	}
+
+	#[test]
+	fn first_blood_goes_to_the_first_solve() {
+		let web = challenge("Web", 100);
+		let (alice, bob) = (player("alice"), player("bob"));
+		let solves = vec![solve(&web, &alice, None, 2), solve(&web, &bob, None, 1)];
+
+		let scoreboard = build_scoreboard(&[web], solves, Vec::new(), None, false, None);
+		assert_eq!(scoreboard.challenges[0].first_blood, "bob");
+		let bob = scoreboard.entries.iter().find(|entry| entry.name == "bob").unwrap();
+		assert_eq!(bob.first_bloods, vec!["Web".to_string()]);
+	}
+
+	#[test]
+	fn teams_count_each_challenge_once() {
+		let (web, crypto) = (challenge("Web", 100), challenge("Crypto", 100));
+		let (alice, bob) = (player("alice"), player("bob"));
+		let team_id = ObjectId::new();
+		let teams = HashMap::from([(team_id, "Red".to_string())]);
+		let solves = vec![
+			solve(&web, &alice, Some(team_id), 1),
+			solve(&web, &bob, Some(team_id), 2),
+			solve(&crypto, &bob, Some(team_id), 3)
+		];
+
+		let scoreboard = build_scoreboard(&[web, crypto], solves, Vec::new(), Some(&teams), false, None);
+		assert_eq!(names(&scoreboard), vec![("Red".to_string(), 200)]);
+		assert_eq!(scoreboard.entries[0].solves, 2);
+	}
+
+	#[test]
+	fn frozen_scoreboards_leave_out_later_solves() {
+		let web = challenge("Web", 100);
+		let (alice, bob) = (player("alice"), player("bob"));
+		let solves = vec![solve(&web, &alice, None, 1), solve(&web, &bob, None, 10)];
+		let freeze_at = Some(DateTime::from_millis(5));
+
+		let frozen = build_scoreboard(std::slice::from_ref(&web), solves, Vec::new(), None, true, freeze_at);
+		assert!(frozen.frozen);
+		assert_eq!(names(&frozen), vec![("alice".to_string(), 100)]);
+		assert_eq!(frozen.challenges[0].solves, 1);
+
+		let solves = vec![solve(&web, &alice, None, 1), solve(&web, &bob, None, 10)];
+		let live = build_scoreboard(&[web], solves, Vec::new(), None, false, freeze_at);
+		assert_eq!(live.entries.len(), 2);
+	}
+}
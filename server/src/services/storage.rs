@@ -0,0 +1,70 @@
+use std::{ env::var, io, path::PathBuf };
+use actix_multipart::Field;
+use futures_util::TryStreamExt;
+use tokio::{ fs, io::AsyncWriteExt };
+
+// The biggest file that can be uploaded
+pub const MAX_UPLOAD_BYTES: u64 = 50 * 1024 * 1024;
+
+// The kinds of files that can be uploaded, by extension
+pub const ALLOWED_EXTENSIONS: [&str; 3] = ["pdf", "pptx", "zip"];
+
+// Get the folder uploads are kept in (UPLOAD_DIR, or an uploads folder next to the server)
+fn upload_dir() -> PathBuf {
+	PathBuf::from(var("UPLOAD_DIR").unwrap_or_else(|_| "uploads".to_string()))
+}
+
+// Get where a stored file lives, refusing names that could point outside the upload folder
+pub fn stored_path(stored_name: &str) -> Option<PathBuf> {
+	let valid = !stored_name.is_empty() &&
+		stored_name.chars().all(|character| character.is_ascii_alphanumeric() || character == '.') &&
+		!stored_name.starts_with('.');
+	valid.then(|| upload_dir().join(stored_name))
+}
+
+// Get the lowercase extension of an uploaded file's name if it is allowed
+pub fn allowed_extension(filename: &str) -> Option<String> {
+	let extension = filename.rsplit_once('.')?.1.to_lowercase();
+	ALLOWED_EXTENSIONS.contains(&extension.as_str()).then_some(extension)
+}
+
+// Save an uploaded file under the given name, returning its size, and giving up past the size limit
+pub async fn save_upload(field: &mut Field, stored_name: &str) -> io::Result<u64> {
+	let path = stored_path(stored_name).ok_or_else(||
+		io::Error::new(io::ErrorKind::InvalidInput, "Invalid file name.")
+	)?;
+	fs::create_dir_all(upload_dir()).await?;
+
+	let mut file = fs::File::create(&path).await?;
+	let mut size: u64 = 0;
+	loop {
+		let chunk = match field.try_next().await {
+			Ok(Some(chunk)) => chunk,
+			Ok(None) => {
+				break;
+			}
+			Err(err) => {
+				drop(file);
+				let _ = fs::remove_file(&path).await;
+				return Err(io::Error::other(err.to_string()));
+			}
+		};
+		size += chunk.len() as u64;
+		if size > MAX_UPLOAD_BYTES {
+			drop(file);
+			let _ = fs::remove_file(&path).await;
+			return Err(io::Error::new(io::ErrorKind::InvalidData, "The file is too big."));
+		}
+		file.write_all(&chunk).await?;
+	}
+	file.flush().await?;
+	Ok(size)
+}
+
+// Delete a stored file
+pub async fn delete_upload(stored_name: &str) -> io::Result<()> {
+	match stored_path(stored_name) {
+		Some(path) => fs::remove_file(path).await,
+		None => Ok(()),
+	}
+}
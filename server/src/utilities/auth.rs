@@ -0,0 +1,116 @@
+use std::env::var;
+use actix_web::{ HttpRequest, HttpResponse };
+use bcrypt::{ hash, verify, DEFAULT_COST };
+use chrono::Utc;
+use jsonwebtoken::{ decode, DecodingKey, Validation };
+use mongodb::bson::oid::ObjectId;
+use rand::{ distributions::Alphanumeric, Rng };
+
+use crate::{
+	models::account::Account,
+	services::db::Database,
+	utilities::claims::{ AdminClaims, UserClaims },
+};
+
+// Get the token from the Authorization header
+pub fn get_token(req: &HttpRequest) -> Option<String> {
+	req.headers()
+		.get("Authorization")
+		.and_then(|header_value| header_value.to_str().ok())
+		.map(|token| token.to_string())
+}
+
+// Check if the JWT belongs to the admin
+pub async fn is_admin(db: &Database, token: &str) -> bool {
+	// Decode the JWT
+	let claims = match
+		decode::<AdminClaims>(
+			token,
+			&DecodingKey::from_secret(var("SECRET").unwrap().as_ref()),
+			&Validation::default()
+		)
+	{
+		Ok(data) => data.claims,
+		Err(err) => {
+			println!("Error decoding token: {}", err);
+			return false;
+		}
+	};
+
+	// Verify the token
+	let now = Utc::now().timestamp() as usize;
+	now <= claims.exp &&
+		verify(
+			claims.token,
+			hash(db.get_admin().await.unwrap().token.to_string(), DEFAULT_COST).unwrap().as_str()
+		).unwrap()
+}
+
+// Get the claims of a user's JWT
+pub fn get_user_claims(token: &str) -> Option<UserClaims> {
+	match
+		decode::<UserClaims>(
+			token,
+			&DecodingKey::from_secret(var("SECRET").unwrap().as_ref()),
+			&Validation::default()
+		)
+	{
+		Ok(data) => Some(data.claims),
+		Err(err) => {
+			println!("Error decoding token: {}", err);
+			None
+		}
+	}
+}
+
+// Get the account that is signed in, if any
+pub async fn get_signed_in_account(db: &Database, req: &HttpRequest) -> Option<Account> {
+	let claims = get_user_claims(&get_token(req)?)?;
+	db.get_account_by_email(claims.email).await.ok().flatten()
+}
+
+// Get the signed-in account for something only verified accounts may do, or the response refusing it
+pub async fn get_verified_account(
+	db: &Database,
+	req: &HttpRequest,
+	action: &str
+) -> Result<Account, HttpResponse> {
+	match get_signed_in_account(db, req).await {
+		Some(acc) if acc.verified => Ok(acc),
+		Some(_) => Err(HttpResponse::Forbidden().body(format!("Verify your account to {}.", action))),
+		None => Err(HttpResponse::Unauthorized().body("Unauthorized.")),
+	}
+}
+
+// Generate the secret of a link sent by email
+pub fn generate_link_token() -> String {
+	rand::thread_rng().sample_iter(&Alphanumeric).take(32).map(char::from).collect()
+}
+
+// Generate a short code that people can type in to join something
+pub fn generate_invite_code() -> String {
+	rand::thread_rng()
+		.sample_iter(&Alphanumeric)
+		.take(8)
+		.map(|character| char::from(character).to_ascii_uppercase())
+		.collect()
+}
+
+// Check who may edit an application: the verified account it is linked to, or anyone with its edit link
+pub async fn get_application_editor(
+	db: &Database,
+	req: &HttpRequest,
+	account_id: Option<ObjectId>,
+	edit_token: &str,
+	token: &str
+) -> Option<String> {
+	if let Some(acc) = get_signed_in_account(db, req).await {
+		if acc.verified && Some(acc._id) == account_id {
+			return Some("account".to_string());
+		}
+	}
+	if !token.is_empty() && !edit_token.is_empty() && verify(token, edit_token).unwrap_or(false) {
+		return Some("link".to_string());
+	}
+	None
+}
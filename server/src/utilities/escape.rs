@@ -0,0 +1,9 @@
+// Escape text before placing it inside of HTML or XML
+pub fn escape_html(text: &str) -> String {
+	text
+		.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+		.replace('\'', "&#39;")
+}
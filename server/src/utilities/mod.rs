@@ -1,4 +1,6 @@
 // Module: utilities
 
+pub mod auth;
 pub mod claims;
+pub mod escape;
 pub mod pagination_args;
@@ -27,4 +27,17 @@ pub struct ResourcePaginationArgs {
 	pub search: String,
 	pub field: String,
 	pub tag: String,
+	#[serde(default)]
+	pub sort: String,
+	#[serde(default)]
+	pub bookmarked: bool,
+}
+
+// Define the EventPaginationArgs struct
+#[derive(Deserialize)]
+pub struct EventPaginationArgs {
+	pub page: u32,
+	pub limit: u32,
+	#[serde(default)]
+	pub past: bool,
 }